@@ -0,0 +1,173 @@
+//! Command-line arguments
+//!
+//! ```text
+//! browser [OPTIONS] [URL]
+//! ```
+//!
+//! The URL is opened as if typed in the URL bar, so one without a scheme
+//! (`browser example.com`) tries https first and local hosts use http.
+
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Usage text printed for `--help` and invalid arguments
+pub const USAGE: &str = "\
+Usage: browser [OPTIONS] [URL]
+
+Arguments:
+  [URL]            Page to open in the first tab (default: configured home page)
+
+Options:
+      --private        Don't save history or other browsing data
+      --config <PATH>  Config file (default: browser.toml)
+  -h, --help           Print this help";
+
+/// Parsed command-line arguments
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Args {
+    /// URL for the first tab, overriding the home page
+    pub url: Option<String>,
+    /// Private browsing requested
+    pub private: bool,
+    /// Config file overriding the default location
+    pub config: Option<PathBuf>,
+}
+
+/// Why arguments couldn't be turned into `Args`
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CliError {
+    /// `--help` was given
+    #[error("help requested")]
+    Help,
+
+    /// Arguments were invalid
+    #[error("{0}")]
+    Invalid(String),
+}
+
+/// Parse arguments, excluding the program name
+///
+/// # Errors
+/// Returns `CliError::Help` for `-h`/`--help` and `CliError::Invalid` for
+/// unknown options, missing values, extra arguments or an unparseable URL
+pub fn parse_args<I, S>(args: I) -> Result<Args, CliError>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let mut parsed = Args::default();
+    let mut args = args.into_iter().map(Into::into);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Err(CliError::Help),
+            "--private" => parsed.private = true,
+            "--config" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| CliError::Invalid("--config requires a path".to_string()))?;
+                parsed.config = Some(PathBuf::from(path));
+            }
+            _ => {
+                if let Some(path) = arg.strip_prefix("--config=") {
+                    parsed.config = Some(PathBuf::from(path));
+                } else if arg.starts_with('-') {
+                    return Err(CliError::Invalid(format!("Unknown option: {}", arg)));
+                } else if parsed.url.is_some() {
+                    return Err(CliError::Invalid(format!("Unexpected argument: {}", arg)));
+                } else {
+                    parsed.url = Some(parse_url(&arg)?);
+                }
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Check a URL argument, keeping it as typed
+///
+/// Only URLs with a scheme are checked here; the scheme for the rest is
+/// chosen when the URL is navigated to.
+fn parse_url(arg: &str) -> Result<String, CliError> {
+    if arg.contains("://") && url::Url::parse(arg).is_err() {
+        return Err(CliError::Invalid(format!("Invalid URL: {}", arg)));
+    }
+    Ok(arg.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_args() {
+        assert_eq!(parse_args(Vec::<String>::new()), Ok(Args::default()));
+    }
+
+    #[test]
+    fn test_url_and_flags() {
+        let args = parse_args([
+            "--private",
+            "https://example.com/page",
+            "--config",
+            "my.toml",
+        ]);
+        assert_eq!(
+            args,
+            Ok(Args {
+                url: Some("https://example.com/page".to_string()),
+                private: true,
+                config: Some(PathBuf::from("my.toml")),
+            })
+        );
+
+        let args = parse_args(["--config=my.json"]).unwrap();
+        assert_eq!(args.config, Some(PathBuf::from("my.json")));
+    }
+
+    #[test]
+    fn test_url_without_scheme() {
+        let args = parse_args(["Example.com"]).unwrap();
+        assert_eq!(args.url.as_deref(), Some("Example.com"));
+
+        // Left for the navigation's scheme choice (http for local hosts)
+        let args = parse_args(["localhost:3000"]).unwrap();
+        assert_eq!(args.url.as_deref(), Some("localhost:3000"));
+    }
+
+    #[test]
+    fn test_url_kept_as_typed() {
+        let args = parse_args(["https://example.com/docs#install"]).unwrap();
+        assert_eq!(
+            args.url.as_deref(),
+            Some("https://example.com/docs#install")
+        );
+    }
+
+    #[test]
+    fn test_help() {
+        assert_eq!(parse_args(["--help"]), Err(CliError::Help));
+        assert_eq!(parse_args(["example.com", "-h"]), Err(CliError::Help));
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        assert!(matches!(
+            parse_args(["--verbose"]),
+            Err(CliError::Invalid(_))
+        ));
+        assert!(matches!(
+            parse_args(["--config"]),
+            Err(CliError::Invalid(_))
+        ));
+        assert!(matches!(
+            parse_args(["example.com", "example.org"]),
+            Err(CliError::Invalid(_))
+        ));
+        assert!(matches!(
+            parse_args(["http://[bad"]),
+            Err(CliError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,300 @@
+//! Application configuration
+//!
+//! App-wide settings loaded at startup from a TOML or JSON file (chosen by
+//! extension, TOML otherwise). Every field is optional; missing fields take
+//! their defaults, and a missing default config file means all defaults.
+//!
+//! ```toml
+//! db_path = "browser.db"
+//! home_page = "https://example.com"
+//! search_url = "https://duckduckgo.com/?q={query}"
+//! log_level = "debug"
+//! private = false
+//! json_logs = false
+//!
+//! [window]
+//! width = 1280.0
+//! height = 800.0
+//! ```
+
+// Allow dead code temporarily - fields are read by the binary only
+#![allow(dead_code)]
+
+use crate::error::{BrowserError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::Level;
+
+/// Config file read when no `--config` path is given
+pub const DEFAULT_CONFIG_FILE: &str = "browser.toml";
+
+/// Placeholder replaced by the encoded query in `search_url`
+pub const SEARCH_QUERY_PLACEHOLDER: &str = "{query}";
+
+/// Initial window size in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowSize {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Default for WindowSize {
+    fn default() -> Self {
+        Self {
+            width: 1024.0,
+            height: 768.0,
+        }
+    }
+}
+
+/// App-wide settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    /// SQLite database for history, bookmarks and settings
+    pub db_path: PathBuf,
+    /// URL opened in the first tab
+    pub home_page: String,
+    /// Search URL template; `{query}` is replaced by the search terms.
+    /// Picks the default engine until engines are saved in settings
+    pub search_url: String,
+    /// Initial window size
+    pub window: WindowSize,
+    /// Log level: `trace`, `debug`, `info`, `warn` or `error`
+    pub log_level: String,
+    /// Private browsing: no history, cookies or cache are persisted
+    pub private: bool,
+    /// Log COE error reports as JSON for log aggregation
+    pub json_logs: bool,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            db_path: PathBuf::from("browser.db"),
+            home_page: "https://example.com".to_string(),
+            search_url: "https://www.google.com/search?q={query}".to_string(),
+            window: WindowSize::default(),
+            log_level: "info".to_string(),
+            private: false,
+            json_logs: false,
+        }
+    }
+}
+
+impl AppConfig {
+    /// Load and validate the config file at `path`
+    ///
+    /// # Errors
+    /// Returns `BrowserError::ConfigError` if the file can't be read or
+    /// parsed, or fails validation
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            BrowserError::ConfigError(format!("Can't read {}: {}", path.display(), e))
+        })?;
+
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let config = if is_json {
+            Self::from_json(&contents)?
+        } else {
+            Self::from_toml(&contents)?
+        };
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load `path`, or `DEFAULT_CONFIG_FILE` if it exists, else defaults
+    ///
+    /// Never fails: an unusable config yields the defaults plus the error,
+    /// which is returned rather than logged so the caller can report it
+    /// once logging (configured from this very file) is set up.
+    pub fn load_or_default(path: Option<&Path>) -> (Self, Option<BrowserError>) {
+        let path = match path {
+            Some(path) => path,
+            None => {
+                let default = Path::new(DEFAULT_CONFIG_FILE);
+                if !default.exists() {
+                    return (Self::default(), None);
+                }
+                default
+            }
+        };
+
+        match Self::load(path) {
+            Ok(config) => (config, None),
+            Err(e) => (Self::default(), Some(e)),
+        }
+    }
+
+    /// Parse a TOML config
+    ///
+    /// # Errors
+    /// Returns `BrowserError::ConfigError` for invalid TOML or field types
+    pub fn from_toml(contents: &str) -> Result<Self> {
+        toml::from_str(contents)
+            .map_err(|e| BrowserError::ConfigError(format!("Invalid TOML config: {}", e)))
+    }
+
+    /// Parse a JSON config
+    ///
+    /// # Errors
+    /// Returns `BrowserError::ConfigError` for invalid JSON or field types
+    pub fn from_json(contents: &str) -> Result<Self> {
+        serde_json::from_str(contents)
+            .map_err(|e| BrowserError::ConfigError(format!("Invalid JSON config: {}", e)))
+    }
+
+    /// Check values serde can't
+    ///
+    /// # Errors
+    /// Returns `BrowserError::ConfigError` describing the first invalid field
+    pub fn validate(&self) -> Result<()> {
+        if !(self.window.width > 0.0 && self.window.height > 0.0) {
+            return Err(BrowserError::ConfigError(format!(
+                "Window size must be positive, got {}x{}",
+                self.window.width, self.window.height
+            )));
+        }
+        if url::Url::parse(&self.home_page).is_err() {
+            return Err(BrowserError::ConfigError(format!(
+                "Invalid home_page URL: {}",
+                self.home_page
+            )));
+        }
+        if !self.search_url.contains(SEARCH_QUERY_PLACEHOLDER) {
+            return Err(BrowserError::ConfigError(format!(
+                "search_url must contain {}: {}",
+                SEARCH_QUERY_PLACEHOLDER, self.search_url
+            )));
+        }
+        self.log_level()?;
+        Ok(())
+    }
+
+    /// Parsed `log_level`
+    ///
+    /// # Errors
+    /// Returns `BrowserError::ConfigError` for an unknown level
+    pub fn log_level(&self) -> Result<Level> {
+        self.log_level.parse().map_err(|_| {
+            BrowserError::ConfigError(format!("Invalid log_level: {}", self.log_level))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        let config = AppConfig::default();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.db_path, PathBuf::from("browser.db"));
+        assert_eq!(config.log_level().unwrap(), Level::INFO);
+    }
+
+    #[test]
+    fn test_from_toml() {
+        let config = AppConfig::from_toml(
+            r#"
+            db_path = "/tmp/profile.db"
+            home_page = "https://start.example.com"
+            search_url = "https://duckduckgo.com/?q={query}"
+            log_level = "debug"
+            private = true
+
+            [window]
+            width = 1280.0
+            height = 800.0
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.db_path, PathBuf::from("/tmp/profile.db"));
+        assert_eq!(config.home_page, "https://start.example.com");
+        assert_eq!(config.search_url, "https://duckduckgo.com/?q={query}");
+        assert_eq!(config.window.width, 1280.0);
+        assert_eq!(config.window.height, 800.0);
+        assert_eq!(config.log_level().unwrap(), Level::DEBUG);
+        assert!(config.private);
+    }
+
+    #[test]
+    fn test_missing_fields_use_defaults() {
+        let config = AppConfig::from_toml("home_page = \"https://start.example.com\"").unwrap();
+        assert_eq!(config.home_page, "https://start.example.com");
+        assert_eq!(config.window, WindowSize::default());
+        assert_eq!(config.log_level, "info");
+
+        let config = AppConfig::from_json(r#"{"window": {"width": 800.0}}"#).unwrap();
+        assert_eq!(config.window.width, 800.0);
+        assert_eq!(config.window.height, WindowSize::default().height);
+        assert_eq!(config.db_path, AppConfig::default().db_path);
+    }
+
+    #[test]
+    fn test_load_json_by_extension() {
+        let mut file = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+        write!(file, r#"{{"log_level": "warn"}}"#).unwrap();
+
+        let config = AppConfig::load(file.path()).unwrap();
+        assert_eq!(config.log_level().unwrap(), Level::WARN);
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_values() {
+        let mut config = AppConfig::default();
+        config.window.height = 0.0;
+        assert!(config.validate().is_err());
+
+        let config = AppConfig {
+            home_page: "not a url".to_string(),
+            ..AppConfig::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = AppConfig {
+            search_url: "https://duckduckgo.com/".to_string(),
+            ..AppConfig::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = AppConfig {
+            log_level: "loud".to_string(),
+            ..AppConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_file_falls_back_to_defaults() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "window = \"large\"").unwrap();
+
+        let (config, error) = AppConfig::load_or_default(Some(file.path()));
+        assert_eq!(config, AppConfig::default());
+        assert!(matches!(error, Some(BrowserError::ConfigError(_))));
+
+        // Parses, but fails validation
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "log_level = \"loud\"").unwrap();
+        let (config, error) = AppConfig::load_or_default(Some(file.path()));
+        assert_eq!(config, AppConfig::default());
+        assert!(error.is_some());
+    }
+
+    #[test]
+    fn test_missing_explicit_file_falls_back_to_defaults() {
+        let (config, error) =
+            AppConfig::load_or_default(Some(Path::new("/nonexistent/dir/browser.toml")));
+        assert_eq!(config, AppConfig::default());
+        assert!(error.is_some());
+    }
+}
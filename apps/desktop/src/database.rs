@@ -0,0 +1,107 @@
+//! Opening the browser database, with an in-memory fallback
+//!
+//! Services open the database by path for each operation. If the file
+//! can't be opened (permissions, a read-only or missing directory), the
+//! browser still starts: services get the URI of a shared in-memory
+//! database instead, so browsing works but nothing outlives the session.
+
+use crate::error::{BrowserError, Result};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use storage::Database;
+use tracing::{error, info};
+
+/// Numbers fallback databases, which are shared by name within a process
+static NEXT_FALLBACK: AtomicU32 = AtomicU32::new(0);
+
+/// The database the browser's services use
+pub struct AppDatabase {
+    path: PathBuf,
+    /// Connection to the in-memory fallback; SQLite frees a shared
+    /// in-memory database when its last connection closes
+    fallback: Option<Database>,
+}
+
+impl AppDatabase {
+    /// Open the database at `path`, falling back to memory if it can't be
+    ///
+    /// # Errors
+    /// Returns `BrowserError::Database` only if the fallback fails too
+    pub fn open(path: &Path) -> Result<Self> {
+        let e = match Database::new(path) {
+            Ok(_) => {
+                return Ok(Self {
+                    path: path.to_path_buf(),
+                    fallback: None,
+                })
+            }
+            Err(e) => e,
+        };
+        error!(
+            "⚠️ Can't open database {:?}: {:#}. Using an in-memory database; \
+             history, bookmarks and settings won't be saved",
+            path, e
+        );
+
+        let uri = PathBuf::from(format!(
+            "file:browser-fallback-{}-{}?mode=memory&cache=shared",
+            std::process::id(),
+            NEXT_FALLBACK.fetch_add(1, Ordering::Relaxed)
+        ));
+        let fallback = Database::new(&uri)
+            .map_err(|e| BrowserError::Database(format!("In-memory fallback failed: {:#}", e)))?;
+        info!("✅ In-memory database ready");
+
+        Ok(Self {
+            path: uri,
+            fallback: Some(fallback),
+        })
+    }
+
+    /// Path for services to open; a `file:` URI for the fallback
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether this is the in-memory fallback
+    pub fn is_fallback(&self) -> bool {
+        self.fallback.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_opens_database_on_disk() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let database = AppDatabase::open(temp_file.path()).unwrap();
+        assert!(!database.is_fallback());
+        assert_eq!(database.path(), temp_file.path());
+    }
+
+    #[test]
+    fn test_unopenable_path_falls_back_to_memory() {
+        let database = AppDatabase::open(Path::new("/nonexistent/dir/browser.db")).unwrap();
+        assert!(database.is_fallback());
+
+        // Services opening the path share the same in-memory data
+        let db = Database::new(database.path()).unwrap();
+        db.add_history("https://example.com", Some("Example"))
+            .unwrap();
+        db.set_setting("homepage", "https://home.example").unwrap();
+        drop(db);
+
+        let reader = Database::open_readonly(database.path()).unwrap();
+        assert_eq!(reader.get_recent_history(10).unwrap().len(), 1);
+        assert_eq!(reader.homepage().unwrap(), "https://home.example");
+
+        // Each fallback is separate
+        let other = AppDatabase::open(Path::new("/nonexistent/dir/browser.db")).unwrap();
+        assert_ne!(other.path(), database.path());
+        let other_db = Database::open_readonly(other.path()).unwrap();
+        assert!(other_db.get_recent_history(10).unwrap().is_empty());
+    }
+}
@@ -6,11 +6,29 @@
 //! - **Actionable**: Suggest remediation paths
 //!
 //! This enables post-incident COE analysis and error rate tracking.
+//!
+//! With JSON logging enabled (`set_json_logging`), COE reports are logged
+//! as one JSON object per line for log aggregation.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Serialize, Serializer};
 use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
 use thiserror::Error;
 
+/// Whether `log_error_with_coe` logs reports as JSON
+static JSON_LOGGING: AtomicBool = AtomicBool::new(false);
+
+/// Log COE reports as JSON (`true`) or as `[COE]` log entries (`false`)
+pub fn set_json_logging(enabled: bool) {
+    JSON_LOGGING.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether COE reports are logged as JSON
+pub fn json_logging() -> bool {
+    JSON_LOGGING.load(Ordering::Relaxed)
+}
+
 /// Browser application errors with COE context
 #[derive(Debug, Error)]
 pub enum BrowserError {
@@ -22,6 +40,14 @@ pub enum BrowserError {
     #[error("Navigation failed: url={url}, reason={reason}")]
     NavigationFailed { url: String, reason: String },
 
+    /// Navigation refused because the host is on the blocklist
+    #[error("Navigation blocked: url={url}, host={host}")]
+    NavigationBlocked { url: String, host: String },
+
+    /// Navigation superseded by a newer one before it finished
+    #[error("Navigation cancelled: url={url}")]
+    NavigationCancelled { url: String },
+
     /// Database operation failed
     #[error("Database error: {0}")]
     Database(String),
@@ -49,6 +75,8 @@ impl BrowserError {
         match self {
             Self::WebViewCreation(_) => "webview_creation",
             Self::NavigationFailed { .. } => "navigation_failed",
+            Self::NavigationBlocked { .. } => "navigation_blocked",
+            Self::NavigationCancelled { .. } => "navigation_cancelled",
             Self::Database(_) => "database",
             Self::IpcError(_) => "ipc",
             Self::ConfigError(_) => "config",
@@ -69,6 +97,10 @@ impl BrowserError {
                     url
                 )
             }
+            Self::NavigationBlocked { url, host } => {
+                format!("{} is blocked because {} is on your blocklist.", url, host)
+            }
+            Self::NavigationCancelled { url } => format!("Stopped loading {}.", url),
             Self::Database(_) => {
                 "Browser data error. Your history and bookmarks may not be saved.".to_string()
             }
@@ -88,6 +120,8 @@ impl BrowserError {
                 "Check platform WebView availability (WKWebView/WebView2/WebKitGTK)"
             }
             Self::NavigationFailed { .. } => "Verify URL validity, check network connectivity",
+            Self::NavigationBlocked { .. } => "None needed unless the host should be unblocked",
+            Self::NavigationCancelled { .. } => "None needed, a newer navigation replaced it",
             Self::Database(_) => "Check database file permissions, verify disk space",
             Self::IpcError(_) => "Investigate IPC message format, check serialization",
             Self::ConfigError(_) => "Validate configuration file, reset to defaults",
@@ -124,11 +158,12 @@ impl BrowserError {
 /// COE (Correction of Errors) error report
 ///
 /// Used for post-incident analysis following AWS operational excellence
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ErrorReport {
     pub error_type: String,
     pub error_message: String,
     pub user_message: String,
+    #[serde(serialize_with = "serialize_rfc3339")]
     pub timestamp: DateTime<Utc>,
     pub suggested_fix: String,
     pub context: String,
@@ -145,15 +180,37 @@ impl ErrorReport {
             self.suggested_fix
         )
     }
+
+    /// Format as a single-line JSON object
+    ///
+    /// The timestamp is RFC 3339 in UTC.
+    pub fn to_json(&self) -> String {
+        // Only strings and a timestamp, so serialization can't fail
+        serde_json::to_string(self).expect("ErrorReport serializes to JSON")
+    }
+}
+
+/// Serialize a timestamp as RFC 3339 (e.g. `2024-01-15T10:30:00.123Z`)
+fn serialize_rfc3339<S: Serializer>(
+    timestamp: &DateTime<Utc>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&timestamp.to_rfc3339_opts(SecondsFormat::Millis, true))
 }
 
 /// Log a BrowserError with COE context
 ///
-/// AWS Pattern: Structured error logging for operational analysis
+/// AWS Pattern: Structured error logging for operational analysis. Logs
+/// the report as JSON when `set_json_logging(true)` was called.
 pub fn log_error_with_coe(error: &BrowserError) {
     use tracing::error;
 
     let report = error.to_coe_report();
+    if json_logging() {
+        error!("{}", report.to_json());
+        return;
+    }
+
     error!(
         error_type = %report.error_type,
         timestamp = %report.timestamp.to_rfc3339(),
@@ -241,6 +298,42 @@ mod tests {
         assert!(log_entry.contains("fix="));
     }
 
+    #[test]
+    fn test_coe_report_json() {
+        let error = BrowserError::NavigationFailed {
+            url: "https://example.com".to_string(),
+            reason: "timeout".to_string(),
+        };
+        let report = error.to_coe_report();
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["error_type"], "navigation_failed");
+        assert_eq!(json["suggested_fix"], error.suggested_fix());
+        assert_eq!(json["user_message"], error.user_message());
+        assert_eq!(json["context"], "URL: https://example.com, Reason: timeout");
+
+        let timestamp = json["timestamp"].as_str().unwrap();
+        let parsed = DateTime::parse_from_rfc3339(timestamp).unwrap();
+        assert_eq!(
+            parsed.timestamp_millis(),
+            report.timestamp.timestamp_millis()
+        );
+        assert!(timestamp.ends_with('Z'));
+
+        // One line per report for line-oriented log sinks
+        assert!(!report.to_json().contains('\n'));
+    }
+
+    #[test]
+    fn test_json_logging_toggle() {
+        assert!(!json_logging());
+        set_json_logging(true);
+        assert!(json_logging());
+        log_error_with_coe(&BrowserError::Unexpected("test".to_string()));
+        set_json_logging(false);
+        assert!(!json_logging());
+    }
+
     #[test]
     fn test_all_error_types_have_suggestions() {
         let errors = vec![
@@ -249,6 +342,13 @@ mod tests {
                 url: "test".to_string(),
                 reason: "test".to_string(),
             },
+            BrowserError::NavigationBlocked {
+                url: "test".to_string(),
+                host: "test".to_string(),
+            },
+            BrowserError::NavigationCancelled {
+                url: "test".to_string(),
+            },
             BrowserError::Database("test".to_string()),
             BrowserError::IpcError("test".to_string()),
             BrowserError::ConfigError("test".to_string()),
@@ -5,7 +5,8 @@
 //! # Health Check Types
 //!
 //! - **Liveness**: Is the process running? (WebViews exist, event loop active)
-//! - **Readiness**: Can handle requests? (Database accessible, metrics healthy)
+//! - **Readiness**: Can handle requests? (Database accessible and writable,
+//!   enough free disk space, metrics healthy)
 //!
 //! # AWS Pattern: ORR Health Checks
 //!
@@ -13,15 +14,26 @@
 //! - Clear health indicators
 //! - Automatic recovery detection
 //! - Integration with monitoring systems
+//!
+//! Checks can also run on a background thread via
+//! `HealthChecker::spawn_periodic`.
 
 // Allow dead code temporarily - APIs will be integrated in Week 2.3 (structured logging)
 #![allow(dead_code)]
 
-use crate::metrics::Metrics;
-use std::path::PathBuf;
-use std::sync::Arc;
+use crate::metrics::{Metrics, MetricsSnapshot};
+use rusqlite::Connection;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
 use storage::Database;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+
+/// Number of recent health checks kept for trends
+pub const HEALTH_HISTORY_SIZE: usize = 20;
 
 /// Health check status
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -48,10 +60,16 @@ pub struct HealthCheck {
     pub status: HealthStatus,
     /// Database connectivity
     pub database_healthy: bool,
+    /// Database writable and enough free disk space
+    pub disk_healthy: bool,
+    /// Free space on the database's filesystem (None if unknown)
+    pub free_disk_bytes: Option<u64>,
     /// Metrics system health
     pub metrics_healthy: bool,
     /// WebView availability
     pub webview_healthy: bool,
+    /// Metrics at the time of the check (error rate, load times, ...)
+    pub metrics: MetricsSnapshot,
     /// Detailed message
     pub message: String,
 }
@@ -68,32 +86,86 @@ impl HealthCheck {
     }
 }
 
+/// Thresholds for the metrics part of the health check
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HealthThresholds {
+    /// Highest acceptable error rate (0.0 - 1.0)
+    pub max_error_rate: f64,
+    /// Any error more recent than this marks metrics unhealthy
+    pub error_recency: Duration,
+    /// Minimum free space on the database's filesystem
+    pub min_free_disk_bytes: u64,
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        Self {
+            max_error_rate: 0.05,
+            error_recency: Duration::from_secs(300),
+            min_free_disk_bytes: 50 * 1024 * 1024,
+        }
+    }
+}
+
 /// Health checker for browser application
 ///
 /// AWS ORR pattern: Centralized health monitoring
 pub struct HealthChecker {
     db_path: PathBuf,
     metrics: Arc<Metrics>,
+    thresholds: HealthThresholds,
+    /// Last `HEALTH_HISTORY_SIZE` results, oldest first
+    history: Mutex<VecDeque<HealthCheck>>,
+    /// `db_path` is the in-memory fallback, see `AppDatabase`
+    database_fallback: bool,
 }
 
 impl HealthChecker {
     /// Create new health checker
     pub fn new(db_path: PathBuf, metrics: Arc<Metrics>) -> Self {
-        Self { db_path, metrics }
+        Self::with_thresholds(db_path, metrics, HealthThresholds::default())
+    }
+
+    /// Create health checker with custom metrics thresholds
+    pub fn with_thresholds(
+        db_path: PathBuf,
+        metrics: Arc<Metrics>,
+        thresholds: HealthThresholds,
+    ) -> Self {
+        Self {
+            db_path,
+            metrics,
+            thresholds,
+            history: Mutex::new(VecDeque::with_capacity(HEALTH_HISTORY_SIZE)),
+            database_fallback: false,
+        }
+    }
+
+    /// Report the database unhealthy because it's the in-memory fallback
+    ///
+    /// The browser works, so the status is at worst Degraded while
+    /// metrics are healthy, but nothing is being saved.
+    pub fn set_database_fallback(&mut self, fallback: bool) {
+        self.database_fallback = fallback;
     }
 
     /// Perform comprehensive health check
     ///
     /// Checks:
     /// - Database connectivity (can open connection?)
-    /// - Metrics health (error rate <5%?)
+    /// - Disk health (database writable, enough free space?)
+    /// - Metrics health (error rate within thresholds, 5% by default)
     /// - Overall system status
     pub fn check_health(&self) -> HealthCheck {
-        let database_healthy = self.check_database();
-        let metrics_healthy = self.metrics.is_healthy();
+        // One connection per check; cheap enough to run on a timer
+        let db = self.check_database().filter(|_| !self.database_fallback);
+        let database_healthy = db.is_some();
+        let free_disk_bytes = free_space(&self.db_path);
+        let disk_healthy = db.is_some_and(|db| self.check_disk(&db, free_disk_bytes));
+        let metrics_healthy = self.metrics.is_healthy_with(&self.thresholds);
 
         // Determine overall status
-        let status = if database_healthy && metrics_healthy {
+        let status = if database_healthy && disk_healthy && metrics_healthy {
             HealthStatus::Healthy
         } else if database_healthy || metrics_healthy {
             HealthStatus::Degraded
@@ -105,8 +177,12 @@ impl HealthChecker {
             HealthStatus::Healthy => "All systems operational".to_string(),
             HealthStatus::Degraded => {
                 let mut issues = vec![];
-                if !database_healthy {
+                if self.database_fallback {
+                    issues.push("database unavailable, nothing is being saved");
+                } else if !database_healthy {
                     issues.push("database unreachable");
+                } else if !disk_healthy {
+                    issues.push("disk not writable or low on space");
                 }
                 if !metrics_healthy {
                     issues.push("high error rate");
@@ -118,13 +194,34 @@ impl HealthChecker {
 
         info!("Health check: {:?} - {}", status, message);
 
-        HealthCheck {
+        let health = HealthCheck {
             status,
             database_healthy,
+            disk_healthy,
+            free_disk_bytes,
             metrics_healthy,
             webview_healthy: true, // Always true if process running
+            metrics: self.metrics.get_stats(),
             message,
+        };
+        self.record(health.clone());
+        health
+    }
+
+    /// Recent health check results, oldest first
+    ///
+    /// Holds up to `HEALTH_HISTORY_SIZE` checks for a "health over time" view.
+    pub fn recent_checks(&self) -> Vec<HealthCheck> {
+        self.history.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Append a result to the history, dropping the oldest once full
+    fn record(&self, health: HealthCheck) {
+        let mut history = self.history.lock().unwrap();
+        if history.len() >= HEALTH_HISTORY_SIZE {
+            history.pop_front();
         }
+        history.push_back(health);
     }
 
     /// Check liveness (is process alive?)
@@ -142,17 +239,72 @@ impl HealthChecker {
         health.is_ready()
     }
 
-    /// Check database connectivity
-    fn check_database(&self) -> bool {
+    /// Check database connectivity, returning the open database
+    fn check_database(&self) -> Option<Database> {
         match Database::new(&self.db_path) {
-            Ok(_) => {
+            Ok(db) => {
                 info!("✅ Database health check passed");
-                true
+                Some(db)
             }
             Err(e) => {
                 error!("❌ Database health check failed: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Check the database accepts writes and the disk has room
+    ///
+    /// Reads can keep working on a full or read-only disk, so opening the
+    /// database alone isn't enough.
+    fn check_disk(&self, db: &Database, free_disk_bytes: Option<u64>) -> bool {
+        let writable = match probe_write(db.connection()) {
+            Ok(()) => true,
+            Err(e) => {
+                error!("❌ Database write check failed: {}", e);
+                false
+            }
+        };
+
+        let enough_space = match free_disk_bytes {
+            Some(free) if free < self.thresholds.min_free_disk_bytes => {
+                warn!("Low disk space: {} bytes free", free);
                 false
             }
+            _ => true,
+        };
+
+        writable && enough_space
+    }
+
+    /// Run health checks on a background thread
+    ///
+    /// Checks run immediately and then every `interval`, passing each result
+    /// to `on_result` on the background thread. Each check opens its own
+    /// database connection, so keep the interval in seconds, not milliseconds,
+    /// outside of tests. Checks stop when the handle is stopped or dropped.
+    pub fn spawn_periodic(
+        self,
+        interval: Duration,
+        on_result: impl Fn(HealthCheck) + Send + 'static,
+    ) -> HealthMonitorHandle {
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+
+        let thread = thread::spawn(move || loop {
+            on_result(self.check_health());
+
+            match stop_rx.recv_timeout(interval) {
+                Err(RecvTimeoutError::Timeout) => continue,
+                // Stop requested or handle dropped
+                Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+            }
+        });
+
+        info!("Periodic health checks every {:?}", interval);
+
+        HealthMonitorHandle {
+            stop_tx: Some(stop_tx),
+            thread: Some(thread),
         }
     }
 
@@ -169,9 +321,69 @@ impl HealthChecker {
     }
 }
 
+/// Handle to periodic health checks started by `HealthChecker::spawn_periodic`
+///
+/// Dropping the handle stops the checks too.
+pub struct HealthMonitorHandle {
+    stop_tx: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl HealthMonitorHandle {
+    /// Stop the checks and wait for the background thread to exit
+    ///
+    /// A check already in progress finishes first.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        if let Some(stop_tx) = self.stop_tx.take() {
+            let _ = stop_tx.send(());
+        }
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                error!("Health monitor thread panicked");
+            }
+        }
+    }
+}
+
+impl Drop for HealthMonitorHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Write a row inside a transaction and roll it back
+fn probe_write(conn: &Connection) -> rusqlite::Result<()> {
+    let result = conn.execute_batch(
+        "BEGIN IMMEDIATE;
+         INSERT OR REPLACE INTO settings (key, value) VALUES ('__health_check', '1');
+         ROLLBACK;",
+    );
+
+    // Leave the connection usable if the insert failed mid-transaction
+    if result.is_err() && !conn.is_autocommit() {
+        let _ = conn.execute_batch("ROLLBACK");
+    }
+
+    result
+}
+
+/// Free space on the filesystem holding `db_path`
+fn free_space(db_path: &Path) -> Option<u64> {
+    let dir = match db_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs2::available_space(dir).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::database::AppDatabase;
     use tempfile::NamedTempFile;
 
     #[test]
@@ -187,6 +399,8 @@ mod tests {
 
         assert_eq!(health.status, HealthStatus::Healthy);
         assert!(health.database_healthy);
+        assert!(health.disk_healthy);
+        assert!(health.free_disk_bytes.is_some());
         assert!(health.metrics_healthy);
         assert!(health.is_ready());
         assert!(health.is_alive());
@@ -203,6 +417,76 @@ mod tests {
         assert_ne!(health.status, HealthStatus::Healthy);
     }
 
+    #[test]
+    fn test_health_check_with_fallback_database() {
+        let metrics = Metrics::new();
+        let database = AppDatabase::open(Path::new("/nonexistent/dir/browser.db")).unwrap();
+        let mut checker = HealthChecker::new(database.path().to_path_buf(), metrics);
+        checker.set_database_fallback(database.is_fallback());
+
+        let health = checker.check_health();
+
+        assert!(!health.database_healthy);
+        assert!(!health.disk_healthy);
+        assert!(health.metrics_healthy);
+        assert_eq!(health.status, HealthStatus::Degraded);
+        assert!(health.message.contains("nothing is being saved"));
+        assert!(health.is_alive());
+    }
+
+    #[test]
+    fn test_probe_write_on_writable_database() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+
+        assert!(probe_write(db.connection()).is_ok());
+
+        // Probe row is rolled back
+        let count: i64 = db
+            .connection()
+            .query_row(
+                "SELECT COUNT(*) FROM settings WHERE key = '__health_check'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_probe_write_on_read_only_database() {
+        let temp_file = NamedTempFile::new().unwrap();
+        Database::new(temp_file.path()).unwrap();
+
+        let conn = Connection::open_with_flags(
+            temp_file.path(),
+            rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+        )
+        .unwrap();
+
+        assert!(probe_write(&conn).is_err());
+    }
+
+    #[test]
+    fn test_low_disk_space_is_unhealthy() {
+        let temp_file = NamedTempFile::new().unwrap();
+        Database::new(temp_file.path()).unwrap();
+
+        let checker = HealthChecker::with_thresholds(
+            temp_file.path().to_path_buf(),
+            Metrics::new(),
+            HealthThresholds {
+                min_free_disk_bytes: u64::MAX,
+                ..Default::default()
+            },
+        );
+
+        let health = checker.check_health();
+        assert!(health.database_healthy);
+        assert!(!health.disk_healthy);
+        assert_eq!(health.status, HealthStatus::Degraded);
+    }
+
     #[test]
     fn test_liveness_always_true() {
         let metrics = Metrics::new();
@@ -231,6 +515,118 @@ mod tests {
         assert_eq!(checker.get_status_code(), 200);
     }
 
+    #[test]
+    fn test_configurable_thresholds() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let metrics = Metrics::new();
+        Database::new(temp_file.path()).unwrap();
+
+        // 10% error rate
+        for _ in 0..9 {
+            metrics.record_navigation(true, Duration::from_millis(100));
+        }
+        metrics.record_navigation(false, Duration::ZERO);
+
+        let strict = HealthChecker::with_thresholds(
+            temp_file.path().to_path_buf(),
+            metrics.clone(),
+            HealthThresholds {
+                max_error_rate: 0.01,
+                ..Default::default()
+            },
+        );
+        let lenient = HealthChecker::with_thresholds(
+            temp_file.path().to_path_buf(),
+            metrics,
+            HealthThresholds {
+                max_error_rate: 0.5,
+                ..Default::default()
+            },
+        );
+
+        let strict_health = strict.check_health();
+        assert!(!strict_health.metrics_healthy);
+        assert_eq!(strict_health.status, HealthStatus::Degraded);
+
+        let lenient_health = lenient.check_health();
+        assert!(lenient_health.metrics_healthy);
+        assert_eq!(lenient_health.status, HealthStatus::Healthy);
+    }
+
+    #[test]
+    fn test_default_thresholds() {
+        let thresholds = HealthThresholds::default();
+        assert_eq!(thresholds.max_error_rate, 0.05);
+        assert_eq!(thresholds.error_recency, Duration::from_secs(300));
+    }
+
+    #[test]
+    fn test_periodic_checks_fire_and_stop() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let temp_file = NamedTempFile::new().unwrap();
+        Database::new(temp_file.path()).unwrap();
+        let checker = HealthChecker::new(temp_file.path().to_path_buf(), Metrics::new());
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let seen = count.clone();
+        let handle = checker.spawn_periodic(Duration::from_millis(10), move |health| {
+            assert_eq!(health.status, HealthStatus::Healthy);
+            seen.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while count.load(Ordering::SeqCst) < 3 && std::time::Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        handle.stop();
+
+        let stopped_at = count.load(Ordering::SeqCst);
+        assert!(stopped_at >= 3);
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(count.load(Ordering::SeqCst), stopped_at);
+    }
+
+    #[test]
+    fn test_health_check_includes_metrics() {
+        let temp_file = NamedTempFile::new().unwrap();
+        Database::new(temp_file.path()).unwrap();
+        let metrics = Metrics::new();
+        metrics.record_navigation(true, Duration::from_millis(120));
+        metrics.record_navigation(false, Duration::ZERO);
+
+        let checker = HealthChecker::new(temp_file.path().to_path_buf(), metrics);
+        let health = checker.check_health();
+
+        assert_eq!(health.metrics.total_navigations, 2);
+        assert_eq!(health.metrics.failed_navigations, 1);
+        assert_eq!(health.metrics.error_rate, 0.5);
+        assert!(health.metrics.p95_load_time_ms > 0.0);
+    }
+
+    #[test]
+    fn test_recent_checks_capped() {
+        let temp_file = NamedTempFile::new().unwrap();
+        Database::new(temp_file.path()).unwrap();
+        let metrics = Metrics::new();
+        let checker = HealthChecker::new(temp_file.path().to_path_buf(), metrics.clone());
+        assert!(checker.recent_checks().is_empty());
+
+        for _ in 0..HEALTH_HISTORY_SIZE + 3 {
+            metrics.record_navigation(true, Duration::from_millis(10));
+            checker.check_health();
+        }
+
+        let recent = checker.recent_checks();
+        assert_eq!(recent.len(), HEALTH_HISTORY_SIZE);
+        // The three oldest checks (1-3 navigations) were dropped
+        assert_eq!(recent[0].metrics.total_navigations, 4);
+        assert_eq!(
+            recent.last().unwrap().metrics.total_navigations,
+            (HEALTH_HISTORY_SIZE + 3) as u64
+        );
+    }
+
     #[test]
     fn test_health_status_is_operational() {
         assert!(HealthStatus::Healthy.is_operational());
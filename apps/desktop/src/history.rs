@@ -5,7 +5,7 @@
 //!
 //! # Architecture
 //!
-//! - HistoryEntry: Single navigation entry (URL, title, timestamp)
+//! - HistoryEntry: Single navigation entry (URL, title, timestamp, scroll)
 //! - TabHistory: Manages history stack with current position
 //!
 //! # Navigation Behavior
@@ -14,20 +14,37 @@
 //! - Go back: Decrement current_index
 //! - Go forward: Increment current_index
 //!
+//! Before navigating away, save the page's scroll offset on the current
+//! entry (`save_scroll_position`); the entry returned when it's revisited
+//! carries it, to restore once the page has loaded.
+//!
+//! A tab's WebView reports finished loads with `commit_load`, which adds
+//! entries for new pages and hands back the saved offset when a back or
+//! forward step completes.
+//!
 //! # Example
 //!
 //! ```
+//! # use desktop::history::TabHistory;
 //! let mut history = TabHistory::new();
-//! history.push("https://example.com", Some("Example"));
-//! history.push("https://wikipedia.org", Some("Wikipedia"));
+//! history.push(
+//!     "https://example.com".to_string(),
+//!     Some("Example".to_string()),
+//! );
+//! history.save_scroll_position(0.0, 640.0);
+//! history.push(
+//!     "https://wikipedia.org".to_string(),
+//!     Some("Wikipedia".to_string()),
+//! );
 //!
 //! // Now at wikipedia.org
 //! assert!(history.can_go_back());
 //! assert!(!history.can_go_forward());
 //!
-//! // Go back to example.com
+//! // Go back to example.com, where we'd scrolled to
 //! let entry = history.go_back().unwrap();
 //! assert_eq!(entry.url, "https://example.com");
+//! assert_eq!(entry.scroll_position, Some((0.0, 640.0)));
 //!
 //! // Now can go forward
 //! assert!(history.can_go_forward());
@@ -45,6 +62,8 @@ pub struct HistoryEntry {
     /// When this entry was created (for future persistence in Milestone 1.6)
     #[allow(dead_code)]
     pub visit_time: Instant,
+    /// Scroll offset when the page was left, in CSS pixels
+    pub scroll_position: Option<(f64, f64)>,
 }
 
 impl HistoryEntry {
@@ -54,6 +73,7 @@ impl HistoryEntry {
             url,
             title,
             visit_time: Instant::now(),
+            scroll_position: None,
         }
     }
 }
@@ -69,6 +89,8 @@ pub struct TabHistory {
     /// Index of current entry (0-based)
     /// Invariant: if entries is non-empty, current_index < entries.len()
     current_index: Option<usize>,
+    /// Next committed load completes a back/forward step
+    traversing: bool,
 }
 
 impl TabHistory {
@@ -77,6 +99,7 @@ impl TabHistory {
         Self {
             entries: Vec::new(),
             current_index: None,
+            traversing: false,
         }
     }
 
@@ -106,12 +129,39 @@ impl TabHistory {
         }
     }
 
+    /// Remember the current page's scroll offset on its entry
+    ///
+    /// Call before navigating away (push, back or forward). No-op when the
+    /// history is empty.
+    pub fn save_scroll_position(&mut self, x: f64, y: f64) {
+        if let Some(entry) = self.current_index.and_then(|idx| self.entries.get_mut(idx)) {
+            entry.scroll_position = Some((x, y));
+        }
+    }
+
+    /// Record a finished top-level load of `url`
+    ///
+    /// Completes a pending back/forward step and returns the revisited
+    /// entry's saved scroll offset. Otherwise a new URL is pushed and a
+    /// reload of the current one changes nothing.
+    pub fn commit_load(&mut self, url: &str) -> Option<(f64, f64)> {
+        if std::mem::take(&mut self.traversing) {
+            return self.current().and_then(|entry| entry.scroll_position);
+        }
+        if self.current_url() != Some(url) {
+            self.push(url.to_string(), None);
+        }
+        None
+    }
+
     /// Check if we can go back in history
+    #[allow(dead_code)]
     pub fn can_go_back(&self) -> bool {
         self.current_index.is_some_and(|idx| idx > 0)
     }
 
     /// Check if we can go forward in history
+    #[allow(dead_code)]
     pub fn can_go_forward(&self) -> bool {
         self.current_index
             .is_some_and(|idx| idx < self.entries.len().saturating_sub(1))
@@ -124,6 +174,7 @@ impl TabHistory {
         if let Some(index) = self.current_index {
             if index > 0 {
                 self.current_index = Some(index - 1);
+                self.traversing = true;
                 return self.entries.get(index - 1);
             }
         }
@@ -137,6 +188,7 @@ impl TabHistory {
         if let Some(index) = self.current_index {
             if index < self.entries.len() - 1 {
                 self.current_index = Some(index + 1);
+                self.traversing = true;
                 return self.entries.get(index + 1);
             }
         }
@@ -146,8 +198,7 @@ impl TabHistory {
     /// Get the current history entry
     #[allow(dead_code)]
     pub fn current(&self) -> Option<&HistoryEntry> {
-        self.current_index
-            .and_then(|idx| self.entries.get(idx))
+        self.current_index.and_then(|idx| self.entries.get(idx))
     }
 
     /// Get the current URL (convenience method)
@@ -159,8 +210,7 @@ impl TabHistory {
     /// Get the current title (convenience method)
     #[allow(dead_code)]
     pub fn current_title(&self) -> Option<&str> {
-        self.current()
-            .and_then(|entry| entry.title.as_deref())
+        self.current().and_then(|entry| entry.title.as_deref())
     }
 
     /// Get the total number of history entries
@@ -211,7 +261,10 @@ mod tests {
     #[test]
     fn test_push_first_entry() {
         let mut history = TabHistory::new();
-        history.push("https://example.com".to_string(), Some("Example".to_string()));
+        history.push(
+            "https://example.com".to_string(),
+            Some("Example".to_string()),
+        );
 
         assert_eq!(history.len(), 1);
         assert_eq!(history.current_url(), Some("https://example.com"));
@@ -223,8 +276,14 @@ mod tests {
     #[test]
     fn test_push_multiple_entries() {
         let mut history = TabHistory::new();
-        history.push("https://example.com".to_string(), Some("Example".to_string()));
-        history.push("https://wikipedia.org".to_string(), Some("Wikipedia".to_string()));
+        history.push(
+            "https://example.com".to_string(),
+            Some("Example".to_string()),
+        );
+        history.push(
+            "https://wikipedia.org".to_string(),
+            Some("Wikipedia".to_string()),
+        );
         history.push("https://github.com".to_string(), Some("GitHub".to_string()));
 
         assert_eq!(history.len(), 3);
@@ -402,4 +461,56 @@ mod tests {
         history.go_forward();
         assert_eq!(history.current_index(), Some(1));
     }
+
+    #[test]
+    fn test_commit_load() {
+        let mut history = TabHistory::new();
+        assert_eq!(history.commit_load("https://a.com"), None);
+        history.save_scroll_position(0.0, 900.0);
+        assert_eq!(history.commit_load("https://b.com"), None);
+        // A reload adds no entry
+        assert_eq!(history.commit_load("https://b.com"), None);
+        assert_eq!(history.len(), 2);
+
+        // Going back hands over a's offset once its page has loaded
+        history.go_back();
+        assert_eq!(history.commit_load("https://a.com"), Some((0.0, 900.0)));
+        assert_eq!(history.len(), 2);
+
+        // b was never scrolled
+        history.go_forward();
+        assert_eq!(history.commit_load("https://b.com"), None);
+        assert_eq!(history.current_index(), Some(1));
+    }
+
+    #[test]
+    fn test_scroll_position_restored() {
+        let mut history = TabHistory::new();
+        // Nothing to save to yet
+        history.save_scroll_position(0.0, 100.0);
+
+        history.push("https://a.com".to_string(), None);
+        assert_eq!(history.current().unwrap().scroll_position, None);
+        history.save_scroll_position(0.0, 1200.0);
+        history.push("https://b.com".to_string(), None);
+        history.save_scroll_position(40.0, 300.0);
+
+        let entry = history.go_back().unwrap();
+        assert_eq!(entry.url, "https://a.com");
+        assert_eq!(entry.scroll_position, Some((0.0, 1200.0)));
+
+        // Scrolling again before leaving replaces the saved offset
+        history.save_scroll_position(0.0, 50.0);
+        let entry = history.go_forward().unwrap();
+        assert_eq!(entry.scroll_position, Some((40.0, 300.0)));
+        history.go_back();
+        assert_eq!(
+            history.current().unwrap().scroll_position,
+            Some((0.0, 50.0))
+        );
+
+        // A new navigation starts at the top
+        history.push("https://c.com".to_string(), None);
+        assert_eq!(history.current().unwrap().scroll_position, None);
+    }
 }
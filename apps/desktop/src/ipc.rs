@@ -0,0 +1,636 @@
+//! IPC protocol between the chrome WebView and Rust
+//!
+//! The chrome UI posts JSON messages of the form
+//! `{"version": 1, "cmd": "Navigate", "data": {"url": "..."}}`. Messages are
+//! parsed into `IpcMessage` and dispatched to an `IpcHandler`; failures are
+//! reported back to the chrome as an `IpcErrorResponse`.
+//!
+//! Rust pushes state changes the other way as `ChromeEvent`s, delivered to
+//! the chrome as a `chrome-event` DOM `CustomEvent` whose `detail` is the
+//! event's JSON. Values are never spliced into script source by hand.
+//!
+//! # Versioning
+//!
+//! `IPC_VERSION` is bumped on incompatible protocol changes. Messages
+//! without a version are treated as version 1, which predates the field.
+
+use crate::error::{BrowserError, Result};
+use serde::{Deserialize, Serialize};
+use shared::js::to_js_literal;
+use shared::TabId;
+use tracing::info;
+
+/// Current protocol version
+pub const IPC_VERSION: u32 = 1;
+
+/// Commands the chrome WebView can send
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "cmd", content = "data")]
+pub enum IpcMessage {
+    Navigate {
+        url: String,
+    },
+    /// Search for URL bar input with the registered search engines
+    Search {
+        query: String,
+    },
+    GoBack,
+    GoForward,
+    Reload,
+    Stop,
+    Home,
+    /// Open a tab; without a `url` it opens the configured new-tab page
+    NewTab {
+        #[serde(default)]
+        url: Option<String>,
+    },
+    CloseTab {
+        id: TabId,
+    },
+    SwitchTab {
+        id: TabId,
+    },
+    ToggleBookmark {
+        url: String,
+    },
+    Zoom {
+        factor: f64,
+    },
+}
+
+impl IpcMessage {
+    /// Every command name `name` can return
+    pub const COMMANDS: &'static [&'static str] = &[
+        "Navigate",
+        "Search",
+        "GoBack",
+        "GoForward",
+        "Reload",
+        "Stop",
+        "Home",
+        "NewTab",
+        "CloseTab",
+        "SwitchTab",
+        "ToggleBookmark",
+        "Zoom",
+    ];
+
+    /// Command name, as sent in the `cmd` field
+    pub fn name(&self) -> &'static str {
+        match self {
+            IpcMessage::Navigate { .. } => "Navigate",
+            IpcMessage::Search { .. } => "Search",
+            IpcMessage::GoBack => "GoBack",
+            IpcMessage::GoForward => "GoForward",
+            IpcMessage::Reload => "Reload",
+            IpcMessage::Stop => "Stop",
+            IpcMessage::Home => "Home",
+            IpcMessage::NewTab { .. } => "NewTab",
+            IpcMessage::CloseTab { .. } => "CloseTab",
+            IpcMessage::SwitchTab { .. } => "SwitchTab",
+            IpcMessage::ToggleBookmark { .. } => "ToggleBookmark",
+            IpcMessage::Zoom { .. } => "Zoom",
+        }
+    }
+}
+
+/// Why an IPC message couldn't be handled
+#[derive(Debug, Clone, PartialEq)]
+pub enum IpcError {
+    /// Not valid JSON, or missing/invalid fields
+    Malformed(String),
+    /// Protocol version this build doesn't speak
+    UnsupportedVersion(u32),
+    /// `cmd` names no known command
+    UnknownCommand(String),
+    /// Command was understood but failed
+    CommandFailed { cmd: String, message: String },
+}
+
+impl IpcError {
+    /// Structured response for the chrome WebView
+    pub fn to_response(&self) -> IpcErrorResponse {
+        let (code, cmd, message) = match self {
+            IpcError::Malformed(message) => ("malformed", None, message.clone()),
+            IpcError::UnsupportedVersion(version) => (
+                "unsupported_version",
+                None,
+                format!(
+                    "Protocol version {} not supported (expected {})",
+                    version, IPC_VERSION
+                ),
+            ),
+            IpcError::UnknownCommand(cmd) => (
+                "unknown_command",
+                Some(cmd.clone()),
+                format!("Unknown command: {}", cmd),
+            ),
+            IpcError::CommandFailed { cmd, message } => {
+                ("command_failed", Some(cmd.clone()), message.clone())
+            }
+        };
+
+        IpcErrorResponse {
+            kind: "error",
+            version: IPC_VERSION,
+            code,
+            cmd,
+            message,
+        }
+    }
+}
+
+/// Error reply sent to the chrome WebView
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IpcErrorResponse {
+    /// Always `"error"`
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    /// Protocol version of this build
+    pub version: u32,
+    /// Machine-readable error code
+    pub code: &'static str,
+    /// Command that failed, if known
+    pub cmd: Option<String>,
+    /// Human-readable description
+    pub message: String,
+}
+
+/// DOM event name the chrome listens on for `ChromeEvent`s
+pub const CHROME_EVENT_NAME: &str = "chrome-event";
+
+/// DOM event name the chrome listens on for `IpcErrorResponse`s
+pub const IPC_RESPONSE_EVENT_NAME: &str = "ipc-response";
+
+/// Events pushed from Rust to the chrome WebView
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "event", content = "data")]
+pub enum ChromeEvent {
+    /// A tab was opened and made active; the chrome adds it to the strip
+    TabOpened { tab: TabId, url: String },
+    /// Active tab's URL changed; only sent for the active tab
+    UrlChanged { tab: TabId, url: String },
+    /// A tab's title changed, for its entry in the tab strip
+    TitleChanged { tab: TabId, title: String },
+    /// An operation failed; `message` is shown to the user
+    ShowError { error_type: String, message: String },
+}
+
+impl ChromeEvent {
+    /// Error toast for `error`, using its COE report's user-facing message
+    pub fn show_error(error: &BrowserError) -> Self {
+        let report = error.to_coe_report();
+        ChromeEvent::ShowError {
+            error_type: report.error_type,
+            message: report.user_message,
+        }
+    }
+
+    /// Script dispatching this event on the chrome's `window`
+    ///
+    /// # Errors
+    ///
+    /// Returns `BrowserError::IpcError` if the event can't be serialized
+    pub fn to_script(&self) -> Result<String> {
+        custom_event_script(CHROME_EVENT_NAME, self)
+    }
+}
+
+/// Script dispatching a DOM `CustomEvent` with `detail` as its payload
+///
+/// The payload is serialized to JSON and embedded as an escaped string
+/// literal for `JSON.parse`, so no value can break out of the script.
+///
+/// # Errors
+///
+/// Returns `BrowserError::IpcError` if `detail` can't be serialized
+pub fn custom_event_script<T: Serialize + ?Sized>(name: &str, detail: &T) -> Result<String> {
+    let detail = serde_json::to_string(detail)
+        .map_err(|e| BrowserError::IpcError(format!("Failed to serialize event: {}", e)))?;
+
+    Ok(format!(
+        "window.dispatchEvent(new CustomEvent({}, {{ detail: JSON.parse({}) }}))",
+        to_js_literal(name),
+        to_js_literal(&detail)
+    ))
+}
+
+/// Parse a raw IPC message body
+///
+/// # Errors
+///
+/// Returns `IpcError::UnsupportedVersion` for a newer protocol version,
+/// `IpcError::UnknownCommand` for an unrecognized `cmd`, and
+/// `IpcError::Malformed` for anything else that doesn't parse
+pub fn parse_message(body: &str) -> std::result::Result<IpcMessage, IpcError> {
+    let value: serde_json::Value =
+        serde_json::from_str(body).map_err(|e| IpcError::Malformed(e.to_string()))?;
+
+    let version = match value.get("version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| IpcError::Malformed(format!("Invalid version: {}", version)))?,
+    };
+    if version == 0 || version > IPC_VERSION {
+        return Err(IpcError::UnsupportedVersion(version));
+    }
+
+    let cmd = value
+        .get("cmd")
+        .and_then(|cmd| cmd.as_str())
+        .ok_or_else(|| IpcError::Malformed("Missing cmd".to_string()))?;
+    if !IpcMessage::COMMANDS.contains(&cmd) {
+        return Err(IpcError::UnknownCommand(cmd.to_string()));
+    }
+
+    serde_json::from_value(value).map_err(|e| IpcError::Malformed(e.to_string()))
+}
+
+/// Services behind each IPC command
+pub trait IpcHandler {
+    fn navigate(&mut self, url: &str) -> Result<()>;
+    fn search(&mut self, query: &str) -> Result<()>;
+    fn go_back(&mut self) -> Result<()>;
+    fn go_forward(&mut self) -> Result<()>;
+    fn reload(&mut self) -> Result<()>;
+    fn stop(&mut self) -> Result<()>;
+    fn home(&mut self) -> Result<()>;
+    fn new_tab(&mut self, url: Option<&str>) -> Result<()>;
+    fn close_tab(&mut self, id: TabId) -> Result<()>;
+    fn switch_tab(&mut self, id: TabId) -> Result<()>;
+    fn toggle_bookmark(&mut self, url: &str) -> Result<()>;
+    fn zoom(&mut self, factor: f64) -> Result<()>;
+}
+
+/// Run a parsed command against its handler
+///
+/// # Errors
+///
+/// Returns whatever the handler returns for the command
+pub fn dispatch<H: IpcHandler + ?Sized>(handler: &mut H, message: &IpcMessage) -> Result<()> {
+    info!("{} command", message.name());
+
+    match message {
+        IpcMessage::Navigate { url } => handler.navigate(url),
+        IpcMessage::Search { query } => handler.search(query),
+        IpcMessage::GoBack => handler.go_back(),
+        IpcMessage::GoForward => handler.go_forward(),
+        IpcMessage::Reload => handler.reload(),
+        IpcMessage::Stop => handler.stop(),
+        IpcMessage::Home => handler.home(),
+        IpcMessage::NewTab { url } => handler.new_tab(url.as_deref()),
+        IpcMessage::CloseTab { id } => handler.close_tab(*id),
+        IpcMessage::SwitchTab { id } => handler.switch_tab(*id),
+        IpcMessage::ToggleBookmark { url } => handler.toggle_bookmark(url),
+        IpcMessage::Zoom { factor } => handler.zoom(*factor),
+    }
+}
+
+/// Parse and dispatch a raw IPC message body
+///
+/// # Errors
+///
+/// Returns an `IpcError` describing a parse failure or the command's error
+#[allow(dead_code)]
+pub fn handle_message<H: IpcHandler + ?Sized>(
+    handler: &mut H,
+    body: &str,
+) -> std::result::Result<(), IpcError> {
+    let message = parse_message(body)?;
+    dispatch(handler, &message).map_err(|e| IpcError::CommandFailed {
+        cmd: message.name().to_string(),
+        message: e.to_string(),
+    })
+}
+
+/// Zoom factors accepted by the `Zoom` command
+pub const ZOOM_RANGE: std::ops::RangeInclusive<f64> = 0.25..=5.0;
+
+/// Check a zoom factor is within `ZOOM_RANGE`
+///
+/// # Errors
+///
+/// Returns `BrowserError::ConfigError` for factors outside the range
+pub fn validate_zoom(factor: f64) -> Result<f64> {
+    if ZOOM_RANGE.contains(&factor) {
+        Ok(factor)
+    } else {
+        Err(BrowserError::ConfigError(format!(
+            "Zoom factor {} outside {:?}",
+            factor, ZOOM_RANGE
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chrome_event_json() {
+        let event = ChromeEvent::TabOpened {
+            tab: TabId(3),
+            url: "about:blank".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({"event": "TabOpened", "data": {"tab": 3, "url": "about:blank"}})
+        );
+
+        let event = ChromeEvent::UrlChanged {
+            tab: TabId(1),
+            url: "https://example.com/".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({"event": "UrlChanged", "data": {"tab": 1, "url": "https://example.com/"}})
+        );
+
+        let event = ChromeEvent::TitleChanged {
+            tab: TabId(2),
+            title: "Example".to_string(),
+        };
+        assert_eq!(
+            event.to_script().unwrap(),
+            r#"window.dispatchEvent(new CustomEvent("chrome-event", { detail: JSON.parse("{\"event\":\"TitleChanged\",\"data\":{\"tab\":2,\"title\":\"Example\"}}") }))"#
+        );
+    }
+
+    #[test]
+    fn test_chrome_event_escapes_title() {
+        let title = "It's a \"test\" \\ </script>\n\u{2028}');alert(1);//";
+        let event = ChromeEvent::TitleChanged {
+            tab: TabId(0),
+            title: title.to_string(),
+        };
+        let script = event.to_script().unwrap();
+
+        // Nothing in the title can terminate the string, statement or tag
+        assert!(!script.chars().any(char::is_control));
+        assert!(!script.contains('\u{2028}'));
+        assert!(!script.contains("</"));
+
+        // The embedded JSON still round-trips to the original title
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(script.contains(&to_js_literal(&json)));
+        let parsed: ChromeEvent = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, event);
+    }
+
+    #[test]
+    fn test_failed_navigation_shows_error() {
+        use crate::metrics::Metrics;
+        use crate::navigation::NavigationService;
+        use shared::Blocklist;
+        use tempfile::NamedTempFile;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut service = NavigationService::new(temp_file.path().to_path_buf(), Metrics::new());
+        service.set_blocklist(Blocklist::from_patterns(["ads.example.com"]));
+        let error = service
+            .navigate("https://ads.example.com/banner")
+            .unwrap_err();
+
+        let event = ChromeEvent::show_error(&error);
+        assert_eq!(
+            event,
+            ChromeEvent::ShowError {
+                error_type: "navigation_blocked".to_string(),
+                message: error.user_message(),
+            }
+        );
+
+        let payload = serde_json::to_value(&event).unwrap();
+        assert_eq!(payload["event"], "ShowError");
+        assert_eq!(
+            payload["data"]["message"],
+            "https://ads.example.com/banner is blocked because ads.example.com is on your blocklist."
+        );
+        assert!(event
+            .to_script()
+            .unwrap()
+            .contains("ads.example.com is on your blocklist."));
+    }
+
+    #[test]
+    fn test_ipc_error_event_script() {
+        let response = IpcError::UnknownCommand("Teleport".to_string()).to_response();
+        let script = custom_event_script(IPC_RESPONSE_EVENT_NAME, &response).unwrap();
+
+        assert!(script.starts_with(r#"window.dispatchEvent(new CustomEvent("ipc-response""#));
+        assert!(script.contains(r#"\"code\":\"unknown_command\""#));
+    }
+
+    /// Records dispatched commands; fails `Stop` to exercise error paths
+    #[derive(Default)]
+    struct RecordingHandler {
+        calls: Vec<String>,
+    }
+
+    impl IpcHandler for RecordingHandler {
+        fn navigate(&mut self, url: &str) -> Result<()> {
+            self.calls.push(format!("navigate {}", url));
+            Ok(())
+        }
+        fn search(&mut self, query: &str) -> Result<()> {
+            self.calls.push(format!("search {}", query));
+            Ok(())
+        }
+        fn go_back(&mut self) -> Result<()> {
+            self.calls.push("go_back".to_string());
+            Ok(())
+        }
+        fn go_forward(&mut self) -> Result<()> {
+            self.calls.push("go_forward".to_string());
+            Ok(())
+        }
+        fn reload(&mut self) -> Result<()> {
+            self.calls.push("reload".to_string());
+            Ok(())
+        }
+        fn stop(&mut self) -> Result<()> {
+            Err(BrowserError::ConfigError("nothing loading".to_string()))
+        }
+        fn home(&mut self) -> Result<()> {
+            self.calls.push("home".to_string());
+            Ok(())
+        }
+        fn new_tab(&mut self, url: Option<&str>) -> Result<()> {
+            self.calls
+                .push(format!("new_tab {}", url.unwrap_or("<new tab page>")));
+            Ok(())
+        }
+        fn close_tab(&mut self, id: TabId) -> Result<()> {
+            self.calls.push(format!("close_tab {}", id.0));
+            Ok(())
+        }
+        fn switch_tab(&mut self, id: TabId) -> Result<()> {
+            self.calls.push(format!("switch_tab {}", id.0));
+            Ok(())
+        }
+        fn toggle_bookmark(&mut self, url: &str) -> Result<()> {
+            self.calls.push(format!("toggle_bookmark {}", url));
+            Ok(())
+        }
+        fn zoom(&mut self, factor: f64) -> Result<()> {
+            self.calls.push(format!("zoom {}", factor));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_parse_new_variants() {
+        let cases = [
+            (
+                r#"{"version":1,"cmd":"NewTab","data":{"url":"https://a.com"}}"#,
+                IpcMessage::NewTab {
+                    url: Some("https://a.com".to_string()),
+                },
+            ),
+            (
+                r#"{"version":1,"cmd":"NewTab","data":{}}"#,
+                IpcMessage::NewTab { url: None },
+            ),
+            (r#"{"version":1,"cmd":"Home"}"#, IpcMessage::Home),
+            (
+                r#"{"version":1,"cmd":"Search","data":{"query":"w cats"}}"#,
+                IpcMessage::Search {
+                    query: "w cats".to_string(),
+                },
+            ),
+            (
+                r#"{"version":1,"cmd":"CloseTab","data":{"id":3}}"#,
+                IpcMessage::CloseTab { id: TabId(3) },
+            ),
+            (
+                r#"{"version":1,"cmd":"SwitchTab","data":{"id":0}}"#,
+                IpcMessage::SwitchTab { id: TabId(0) },
+            ),
+            (
+                r#"{"version":1,"cmd":"ToggleBookmark","data":{"url":"https://b.com"}}"#,
+                IpcMessage::ToggleBookmark {
+                    url: "https://b.com".to_string(),
+                },
+            ),
+            (
+                r#"{"version":1,"cmd":"Zoom","data":{"factor":1.5}}"#,
+                IpcMessage::Zoom { factor: 1.5 },
+            ),
+        ];
+
+        for (body, expected) in cases {
+            assert_eq!(parse_message(body).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_without_version() {
+        // Messages from before the version field
+        assert_eq!(
+            parse_message(r#"{"cmd":"GoBack"}"#).unwrap(),
+            IpcMessage::GoBack
+        );
+    }
+
+    #[test]
+    fn test_known_commands_are_variants() {
+        // A listed name that isn't a variant would reach serde and come
+        // back as a malformed message instead of an unknown command
+        for &cmd in IpcMessage::COMMANDS {
+            let value = serde_json::json!({ "cmd": cmd });
+            if let Err(e) = serde_json::from_value::<IpcMessage>(value) {
+                assert!(
+                    !e.to_string().contains("unknown variant"),
+                    "{} isn't an IpcMessage variant",
+                    cmd
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_message(r#"{"version":1,"cmd":"Teleport"}"#),
+            Err(IpcError::UnknownCommand("Teleport".to_string()))
+        );
+        assert_eq!(
+            parse_message(r#"{"version":2,"cmd":"GoBack"}"#),
+            Err(IpcError::UnsupportedVersion(2))
+        );
+        assert!(matches!(
+            parse_message("not json"),
+            Err(IpcError::Malformed(_))
+        ));
+        assert!(matches!(
+            parse_message(r#"{"version":1,"cmd":"Navigate","data":{}}"#),
+            Err(IpcError::Malformed(_))
+        ));
+        assert!(matches!(
+            parse_message(r#"{"version":1}"#),
+            Err(IpcError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let mut handler = RecordingHandler::default();
+        let bodies = [
+            r#"{"version":1,"cmd":"Navigate","data":{"url":"https://a.com"}}"#,
+            r#"{"version":1,"cmd":"Search","data":{"query":"rust"}}"#,
+            r#"{"version":1,"cmd":"NewTab","data":{"url":"https://b.com"}}"#,
+            r#"{"version":1,"cmd":"NewTab","data":{}}"#,
+            r#"{"version":1,"cmd":"Home"}"#,
+            r#"{"version":1,"cmd":"SwitchTab","data":{"id":2}}"#,
+            r#"{"version":1,"cmd":"CloseTab","data":{"id":2}}"#,
+            r#"{"version":1,"cmd":"ToggleBookmark","data":{"url":"https://c.com"}}"#,
+            r#"{"version":1,"cmd":"Zoom","data":{"factor":2.0}}"#,
+        ];
+        for body in bodies {
+            handle_message(&mut handler, body).unwrap();
+        }
+
+        assert_eq!(
+            handler.calls,
+            vec![
+                "navigate https://a.com",
+                "search rust",
+                "new_tab https://b.com",
+                "new_tab <new tab page>",
+                "home",
+                "switch_tab 2",
+                "close_tab 2",
+                "toggle_bookmark https://c.com",
+                "zoom 2",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_responses() {
+        let mut handler = RecordingHandler::default();
+
+        let error = handle_message(&mut handler, r#"{"version":1,"cmd":"Teleport"}"#).unwrap_err();
+        let json = serde_json::to_value(error.to_response()).unwrap();
+        assert_eq!(json["type"], "error");
+        assert_eq!(json["code"], "unknown_command");
+        assert_eq!(json["cmd"], "Teleport");
+        assert_eq!(json["version"], IPC_VERSION);
+
+        let error = handle_message(&mut handler, r#"{"version":1,"cmd":"Stop"}"#).unwrap_err();
+        let response = error.to_response();
+        assert_eq!(response.code, "command_failed");
+        assert_eq!(response.cmd.as_deref(), Some("Stop"));
+        assert!(response.message.contains("nothing loading"));
+    }
+
+    #[test]
+    fn test_validate_zoom() {
+        assert_eq!(validate_zoom(1.0).unwrap(), 1.0);
+        assert!(validate_zoom(0.1).is_err());
+        assert!(validate_zoom(10.0).is_err());
+        assert!(validate_zoom(f64::NAN).is_err());
+    }
+}
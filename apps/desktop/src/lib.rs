@@ -3,9 +3,17 @@
 //! This library module exports the service components for integration testing.
 //! The main binary (main.rs) uses these same modules.
 
+pub mod cli;
+pub mod config;
+pub mod database;
 pub mod error;
 pub mod health;
+pub mod history;
+pub mod ipc;
 pub mod metrics;
 pub mod navigation;
+pub mod permissions;
+pub mod search;
 pub mod state;
 pub mod webview_manager;
+pub mod window_state;
@@ -15,43 +15,71 @@
 //! └── Metrics (DORA tracking)
 //! ```
 
+mod cli;
+mod config;
+mod database;
 mod error;
 mod health;
+mod history;
+mod ipc;
 mod metrics;
 mod navigation;
+mod permissions;
+mod search;
 mod state;
 mod webview_manager;
+mod window_state;
 
+use crate::cli::CliError;
+use crate::config::AppConfig;
+use crate::database::AppDatabase;
 use crate::error::{log_error_with_coe, BrowserError, Result};
-use crate::health::HealthChecker;
+use crate::health::{HealthChecker, HealthMonitorHandle};
+use crate::ipc::{ChromeEvent, IpcError, IpcHandler};
 use crate::metrics::Metrics;
-use crate::navigation::NavigationService;
+use crate::navigation::{
+    domain_of, save_visits, HistoryDebouncer, HttpsCheck, NavigationResult, NavigationService,
+    HISTORY_DEBOUNCE,
+};
+use crate::permissions::PermissionStore;
+use crate::search::SearchEngines;
 use crate::state::StateManager;
-use crate::webview_manager::{WebViewConfig, WebViewManager};
+use crate::webview_manager::{
+    emit_chrome_event_to, origin_of, tab_navigated, tab_title, tab_title_changed, WebViewConfig,
+    WebViewManager,
+};
+use crate::window_state::{ScreenRect, WindowGeometry};
 
-use serde::{Deserialize, Serialize};
+use shared::TabId;
 use std::cell::RefCell;
 use std::path::PathBuf;
 use std::rc::Rc;
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use storage::Database;
 use tao::{
     event::{Event, WindowEvent},
-    event_loop::{ControlFlow, EventLoop},
-    window::WindowBuilder,
+    event_loop::{ControlFlow, EventLoopBuilder, EventLoopProxy},
+    window::{Window, WindowBuilder},
 };
-use tracing::{error, info, Level};
-
-/// IPC message from JavaScript to Rust
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(tag = "cmd", content = "data")]
-enum IpcMessage {
-    Navigate { url: String },
-    GoBack,
-    GoForward,
-    Reload,
-    Stop,
+use tracing::{error, info, warn, Level};
+
+/// How often the process's memory use is sampled
+const MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often health checks run in the background
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Resident memory above which the app is under memory pressure
+const MEMORY_PRESSURE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Events sent to the event loop from worker threads
+#[derive(Debug)]
+enum AppEvent {
+    /// An HTTPS-first navigation's https URL was checked
+    HttpsChecked(HttpsCheck),
+    /// Resident memory crossed `MEMORY_PRESSURE_BYTES`
+    MemoryPressure(u64),
 }
 
 /// Browser application with service-oriented architecture
@@ -64,15 +92,37 @@ struct BrowserApp {
     /// Navigation service
     navigation_service: NavigationService,
 
-    /// State management service (will be integrated in Week 2)
-    #[allow(dead_code)]
-    state_manager: StateManager,
+    /// State management service, shared with WebView callbacks
+    state_manager: Rc<RefCell<StateManager>>,
 
     /// Metrics collector
     metrics: Arc<Metrics>,
 
-    /// Health checker (ORR pattern)
-    health_checker: HealthChecker,
+    /// Background health checks (ORR pattern), stopped with the app
+    _health_monitor: HealthMonitorHandle,
+
+    /// Main window, needed to create content WebViews for new tabs
+    window: Rc<Window>,
+
+    /// Database path for bookmarks and history
+    db_path: PathBuf,
+
+    /// Private mode: history isn't saved
+    private: bool,
+
+    /// Page visits waiting to settle before they're saved to history,
+    /// shared with WebView callbacks
+    history_debouncer: Rc<RefCell<HistoryDebouncer>>,
+
+    /// Search engines for URL bar searches
+    search_engines: SearchEngines,
+
+    /// Wakes the event loop with results from worker threads
+    event_proxy: EventLoopProxy<AppEvent>,
+
+    /// Database services open by `db_path`; kept open so an in-memory
+    /// fallback lasts for the session
+    _database: AppDatabase,
 }
 
 impl BrowserApp {
@@ -81,29 +131,65 @@ impl BrowserApp {
     /// # AWS Pattern
     /// - Services created independently and injected
     /// - Metrics shared across services via Arc
-    /// - Database path configurable for testing
-    fn new(window: &tao::window::Window) -> Result<Rc<RefCell<Option<Self>>>> {
+    /// - Database path, home page and search engine from `AppConfig`
+    fn new(
+        window: Rc<Window>,
+        config: &AppConfig,
+        event_proxy: EventLoopProxy<AppEvent>,
+    ) -> Result<Rc<RefCell<Option<Self>>>> {
         info!("Creating browser application (AWS service architecture)");
 
         // Initialize metrics (shared across services)
         let metrics = Metrics::new();
+        // Handled on the event loop, which owns the WebViews
+        let pressure_proxy = Mutex::new(event_proxy.clone());
+        metrics.on_memory_pressure(MEMORY_PRESSURE_BYTES, move |rss_bytes| {
+            let _ = pressure_proxy
+                .lock()
+                .unwrap()
+                .send_event(AppEvent::MemoryPressure(rss_bytes));
+        });
         info!("✅ Metrics system initialized");
 
-        // Initialize database
-        let db_path = PathBuf::from("browser.db");
-        let _db = Database::new(&db_path).map_err(|e| BrowserError::Database(e.to_string()))?;
+        // Initialize database, in memory if the file can't be opened
+        let database = AppDatabase::open(&config.db_path)?;
+        let db_path = database.path().to_path_buf();
+        if database.is_fallback() {
+            window.set_title("Browser MVP (not saving history or bookmarks)");
+        }
         info!("✅ Database initialized at {:?}", db_path);
 
         // Create navigation service with metrics
-        let navigation_service = NavigationService::new(db_path.clone(), metrics.clone());
+        let mut navigation_service = NavigationService::new(db_path.clone(), metrics.clone());
+        navigation_service.set_private(config.private);
+        if let Err(e) = navigation_service.load_https_first() {
+            warn!("Using default HTTPS-first setting: {}", e);
+        }
         info!("✅ Navigation service initialized");
 
+        let search_engines = load_search_engines(&db_path, &config.search_url);
+        info!(
+            "✅ Search engines loaded (default: {})",
+            search_engines.default_engine().name
+        );
+
         // Create state manager
-        let state_manager = StateManager::new();
+        let mut state_manager = StateManager::new();
+        state_manager.set_private(config.private);
+        let state_manager = Rc::new(RefCell::new(state_manager));
         info!("✅ State manager initialized");
 
         // Create health checker (ORR pattern)
-        let health_checker = HealthChecker::new(db_path, metrics.clone());
+        let mut health_checker = HealthChecker::new(db_path.clone(), metrics.clone());
+        health_checker.set_database_fallback(database.is_fallback());
+        let health_monitor = health_checker.spawn_periodic(HEALTH_CHECK_INTERVAL, |health| {
+            info!(
+                "Health check: {:?} - Readiness: {}, Liveness: {}",
+                health.status,
+                health.is_ready(),
+                health.is_alive()
+            );
+        });
         info!("✅ Health checker initialized");
 
         let html_ui = include_str!("ui.html");
@@ -112,70 +198,51 @@ impl BrowserApp {
         let app_clone = app_holder.clone();
 
         // WebView configuration
-        let webview_config = WebViewConfig::default();
+        let webview_config = WebViewConfig {
+            initial_url: config.home_page.clone(),
+            private: config.private,
+            ..WebViewConfig::default()
+        };
 
         // Create WebView manager with IPC handler
-        let webview_manager =
-            WebViewManager::new(window, webview_config, html_ui, move |message_body| {
+        let mut webview_manager =
+            WebViewManager::new(&window, webview_config, html_ui, move |message_body| {
                 info!("IPC message received: {:?}", message_body);
 
-                match serde_json::from_str::<IpcMessage>(message_body) {
-                    Ok(IpcMessage::Navigate { url }) => {
-                        info!("Navigate command: {}", url);
-                        if let Some(ref mut app) = *app_clone.borrow_mut() {
-                            if let Err(e) = app.handle_navigate(&url) {
-                                log_error_with_coe(&e);
-                                app.metrics
-                                    .record_error(&format!("Navigation failed: {}", e));
-                            }
-                        }
-                    }
-                    Ok(IpcMessage::GoBack) => {
-                        info!("Go back command");
-                        if let Some(ref app) = *app_clone.borrow() {
-                            if let Err(e) = app.handle_go_back() {
-                                log_error_with_coe(&e);
-                                app.metrics.record_error(&format!("Go back failed: {}", e));
-                            }
-                        }
-                    }
-                    Ok(IpcMessage::GoForward) => {
-                        info!("Go forward command");
-                        if let Some(ref app) = *app_clone.borrow() {
-                            if let Err(e) = app.handle_go_forward() {
-                                log_error_with_coe(&e);
-                                app.metrics
-                                    .record_error(&format!("Go forward failed: {}", e));
-                            }
-                        }
-                    }
-                    Ok(IpcMessage::Reload) => {
-                        info!("Reload command");
-                        if let Some(ref app) = *app_clone.borrow() {
-                            if let Err(e) = app.handle_reload() {
-                                log_error_with_coe(&e);
-                                app.metrics.record_error(&format!("Reload failed: {}", e));
-                            }
-                        }
-                    }
-                    Ok(IpcMessage::Stop) => {
-                        info!("Stop command");
-                        if let Some(ref app) = *app_clone.borrow() {
-                            if let Err(e) = app.handle_stop() {
-                                log_error_with_coe(&e);
-                                app.metrics.record_error(&format!("Stop failed: {}", e));
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        error!("Failed to parse IPC message: {}", e);
-                        if let Some(ref app) = *app_clone.borrow() {
-                            app.metrics.record_error(&format!("IPC parse error: {}", e));
-                        }
-                    }
+                if let Some(ref mut app) = *app_clone.borrow_mut() {
+                    app.handle_ipc(message_body);
                 }
             })?;
 
+        // Restore each site's saved zoom once its page has loaded
+        let zoom_db_path = db_path.clone();
+        webview_manager.set_zoom_lookup(move |url| {
+            origin_of(url)
+                .and_then(|origin| {
+                    Database::open_readonly(&zoom_db_path)
+                        .and_then(|db| db.get_zoom(&origin))
+                        .ok()
+                })
+                .unwrap_or(storage::DEFAULT_ZOOM)
+        });
+
+        // Page load phases as measured by each page, for the metrics
+        let timing_metrics = metrics.clone();
+        webview_manager.set_timing_handler(move |url, timing| {
+            if let Some(domain) = domain_of(url) {
+                timing_metrics.record_timing(&domain, timing);
+            }
+        });
+
+        // Ask once per site and capability; private windows ask every time
+        if config.private {
+            webview_manager.set_permission_handler(permissions::ask_user);
+        } else {
+            webview_manager.set_permission_handler(
+                PermissionStore::new(db_path.clone()).into_handler(permissions::ask_user),
+            );
+        }
+
         info!("✅ WebView manager initialized");
 
         // Create BrowserApp with injected services
@@ -184,9 +251,20 @@ impl BrowserApp {
             navigation_service,
             state_manager,
             metrics,
-            health_checker,
+            _health_monitor: health_monitor,
+            window,
+            db_path,
+            private: config.private,
+            history_debouncer: Rc::new(RefCell::new(HistoryDebouncer::new(HISTORY_DEBOUNCE))),
+            search_engines,
+            event_proxy,
+            _database: database,
         };
 
+        if config.private {
+            info!("🕶️ Private mode: history, cookies and cache won't be saved");
+        }
+
         *app_holder.borrow_mut() = Some(browser_app);
 
         info!("✅ Browser application ready (all services initialized)");
@@ -194,38 +272,89 @@ impl BrowserApp {
         Ok(app_holder)
     }
 
+    /// Parse and dispatch an IPC message from the chrome WebView
+    ///
+    /// Failures are logged, counted in metrics and reported back to the
+    /// chrome as a structured error; failed commands also show the user an
+    /// error toast.
+    fn handle_ipc(&mut self, message_body: &str) {
+        let error = match ipc::parse_message(message_body) {
+            Ok(message) => match ipc::dispatch(self, &message) {
+                Ok(()) => return,
+                Err(e) => {
+                    log_error_with_coe(&e);
+                    self.metrics
+                        .record_error(&format!("{} failed: {}", message.name(), e));
+                    if let Err(emit_error) = self
+                        .webview_manager
+                        .emit_chrome_event(&ChromeEvent::show_error(&e))
+                    {
+                        error!("Failed to show error: {}", emit_error);
+                    }
+                    IpcError::CommandFailed {
+                        cmd: message.name().to_string(),
+                        message: e.to_string(),
+                    }
+                }
+            },
+            Err(e) => {
+                error!("Failed to parse IPC message: {:?}", e);
+                self.metrics
+                    .record_error(&format!("IPC parse error: {:?}", e));
+                e
+            }
+        };
+
+        self.send_ipc_error(&error);
+    }
+
+    /// Report an IPC failure to the chrome WebView
+    fn send_ipc_error(&self, error: &IpcError) {
+        let result = ipc::custom_event_script(ipc::IPC_RESPONSE_EVENT_NAME, &error.to_response())
+            .and_then(|script| self.webview_manager.evaluate_chrome_script(&script));
+        if let Err(e) = result {
+            error!("Failed to send IPC error: {}", e);
+        }
+    }
+
     /// Handle navigate IPC command
     ///
-    /// Uses NavigationService for URL processing and metrics tracking
+    /// Uses NavigationService for URL processing and metrics tracking.
+    /// The new navigation supersedes the previous one: its page stops
+    /// loading and its background HTTPS check is dropped.
     fn handle_navigate(&mut self, url: &str) -> Result<()> {
         let start = Instant::now();
 
-        // Navigate via service (handles history, metrics)
+        // Navigate via service (handles blocklist, metrics, cancellation)
         let nav_result = self.navigation_service.navigate(url)?;
 
-        // Update chrome URL bar
-        let update_script = format!(
-            "document.getElementById('url-input').value = '{}'",
-            nav_result.url.replace('\'', "\\'")
-        );
-        self.webview_manager
-            .evaluate_chrome_script(&update_script)?;
-
-        // Update tab title
-        let title_script = format!(
-            "updateTabTitle('{}')",
-            nav_result.title.replace('\'', "\\'")
-        );
-        self.webview_manager.evaluate_chrome_script(&title_script)?;
+        // Update chrome URL bar and tab title
+        if let Some(tab) = self.state_manager.borrow().active_tab_id() {
+            self.webview_manager
+                .emit_chrome_event(&ChromeEvent::UrlChanged {
+                    tab,
+                    url: nav_result.url.clone(),
+                })?;
+            self.webview_manager
+                .emit_chrome_event(&ChromeEvent::TitleChanged {
+                    tab,
+                    title: nav_result.title.clone(),
+                })?;
+        }
 
         // Navigate content WebView
-        if let Some(content_webview) = self.webview_manager.content_webview() {
+        if let Some(content_webview) = self.webview_manager.active_content_webview() {
+            // Best effort: loading the new URL replaces the old load anyway
+            if let Err(e) = self.navigation_service.stop(content_webview) {
+                warn!("Failed to stop the superseded load: {}", e);
+            }
             content_webview.load_url(&nav_result.url).map_err(|e| {
                 BrowserError::NavigationFailed {
                     url: nav_result.url.clone(),
                     reason: e.to_string(),
                 }
             })?;
+            self.check_https(&nav_result);
 
             let duration = start.elapsed();
             self.metrics.record_navigation(true, duration);
@@ -241,8 +370,9 @@ impl BrowserApp {
 
     /// Handle go back IPC command
     fn handle_go_back(&self) -> Result<()> {
-        if let Some(content_webview) = self.webview_manager.content_webview() {
+        if let Some(content_webview) = self.webview_manager.active_content_webview() {
             self.navigation_service.go_back(content_webview)?;
+            self.webview_manager.begin_back();
             Ok(())
         } else {
             Err(BrowserError::ConfigError(
@@ -253,8 +383,9 @@ impl BrowserApp {
 
     /// Handle go forward IPC command
     fn handle_go_forward(&self) -> Result<()> {
-        if let Some(content_webview) = self.webview_manager.content_webview() {
+        if let Some(content_webview) = self.webview_manager.active_content_webview() {
             self.navigation_service.go_forward(content_webview)?;
+            self.webview_manager.begin_forward();
             Ok(())
         } else {
             Err(BrowserError::ConfigError(
@@ -265,7 +396,7 @@ impl BrowserApp {
 
     /// Handle reload IPC command
     fn handle_reload(&self) -> Result<()> {
-        if let Some(content_webview) = self.webview_manager.content_webview() {
+        if let Some(content_webview) = self.webview_manager.active_content_webview() {
             self.navigation_service.reload(content_webview)?;
             Ok(())
         } else {
@@ -277,7 +408,7 @@ impl BrowserApp {
 
     /// Handle stop IPC command
     fn handle_stop(&self) -> Result<()> {
-        if let Some(content_webview) = self.webview_manager.content_webview() {
+        if let Some(content_webview) = self.webview_manager.active_content_webview() {
             self.navigation_service.stop(content_webview)?;
             Ok(())
         } else {
@@ -287,46 +418,128 @@ impl BrowserApp {
         }
     }
 
-    /// Create content WebView
-    fn create_content_webview(&mut self, window: &tao::window::Window, url: &str) -> Result<()> {
-        let chrome_webview = self.webview_manager.chrome_webview().clone();
-        let db_path = PathBuf::from("browser.db");
-
-        self.webview_manager
-            .create_content_webview(window, url, move |url_str| {
-                info!("Navigation event: {}", url_str);
-
-                // Update URL bar
-                let update_script = format!(
-                    "document.getElementById('url-input').value = '{}'",
-                    url_str.replace('\'', "\\'")
-                );
-                if let Err(e) = chrome_webview.evaluate_script(&update_script) {
-                    error!("Failed to update URL bar: {}", e);
-                }
+    /// Homepage setting, or the default if the database can't be read
+    fn homepage(&self) -> String {
+        Database::open_readonly(&self.db_path)
+            .and_then(|db| db.homepage())
+            .unwrap_or_else(|e| {
+                warn!("Failed to read homepage setting: {}", e);
+                storage::DEFAULT_HOMEPAGE.to_string()
+            })
+    }
 
-                // Extract title from URL
-                let title = url_str.split('/').nth(2).unwrap_or("New Tab").to_string();
+    /// New-tab page setting, or the default if the database can't be read
+    fn new_tab_url(&self) -> String {
+        Database::open_readonly(&self.db_path)
+            .and_then(|db| db.new_tab_url())
+            .unwrap_or_else(|e| {
+                warn!("Failed to read new tab setting: {}", e);
+                storage::DEFAULT_NEW_TAB_URL.to_string()
+            })
+    }
 
-                // Update tab title
-                let title_script = format!("updateTabTitle('{}')", title.replace('\'', "\\'"));
-                if let Err(e) = chrome_webview.evaluate_script(&title_script) {
-                    error!("Failed to update tab title: {}", e);
-                }
+    /// Create a tab with its content WebView and make it active
+    fn create_content_webview(&mut self, url: &str) -> Result<TabId> {
+        // The new tab's WebView is shown, so it's the active tab
+        let tab_id = {
+            let mut state = self.state_manager.borrow_mut();
+            let tab_id = state.create_tab(url.to_string());
+            state
+                .switch_tab(tab_id)
+                .map_err(BrowserError::ConfigError)?;
+            tab_id
+        };
+        self.attach_content_webview(tab_id, url)?;
+
+        // Best effort: the chrome may still be loading for the first tab,
+        // which it adopts from its first UrlChanged instead
+        let opened = ChromeEvent::TabOpened {
+            tab: tab_id,
+            url: url.to_string(),
+        };
+        if let Err(e) = self.webview_manager.emit_chrome_event(&opened) {
+            warn!("Failed to add {} to the tab strip: {}", tab_id, e);
+        }
 
-                // Save to history
-                if let Ok(db) = Database::new(&db_path) {
-                    if let Err(e) = db.add_history(&url_str, Some(&title)) {
-                        error!("Failed to save history: {}", e);
-                    } else {
-                        info!("✅ Saved to history: {} - {}", url_str, title);
+        Ok(tab_id)
+    }
+
+    /// Create and show the content WebView for an existing tab
+    fn attach_content_webview(&mut self, tab_id: TabId, url: &str) -> Result<()> {
+        let chrome_webview = self.webview_manager.chrome_webview().clone();
+        let chrome_for_title = chrome_webview.clone();
+        let private = self.private;
+        let history_debouncer = self.history_debouncer.clone();
+        let state_for_nav = self.state_manager.clone();
+        let state_for_title = self.state_manager.clone();
+
+        let window = self.window.clone();
+        self.webview_manager.create_content_webview_with_id(
+            tab_id,
+            &window,
+            url,
+            move |url_str| {
+                info!("Navigation event ({}): {}", tab_id, url_str);
+
+                // URL bar if this is the active tab, and a provisional
+                // title until the page reports its own
+                if let Ok(mut state) = state_for_nav.try_borrow_mut() {
+                    for event in tab_navigated(&mut state, tab_id, &url_str) {
+                        if let Err(e) = emit_chrome_event_to(&chrome_webview, &event) {
+                            error!("Failed to update chrome for {}: {}", tab_id, e);
+                        }
                     }
                 }
+                let title = tab_title("", &url_str);
+
+                // Saved to history once the tab settles, so redirect
+                // hops aren't recorded (never in private mode)
+                if private {
+                    return true;
+                }
+                if let Ok(mut debouncer) = history_debouncer.try_borrow_mut() {
+                    debouncer.record(tab_id, &url_str, &title, Instant::now());
+                }
 
                 true // Allow navigation
-            })?;
+            },
+            move |page_title| {
+                let Ok(mut state) = state_for_title.try_borrow_mut() else {
+                    return;
+                };
+                let event = tab_title_changed(&mut state, tab_id, &page_title);
+                if let Err(e) = emit_chrome_event_to(&chrome_for_title, &event) {
+                    error!("Failed to update tab title: {}", e);
+                }
+            },
+        )
+    }
 
-        Ok(())
+    /// Show a tab, reloading its page if its WebView was discarded
+    fn show_tab(&mut self, tab_id: TabId) -> Result<()> {
+        if self.webview_manager.content_webview_for(tab_id).is_some() {
+            return self.webview_manager.show_tab(tab_id);
+        }
+        let url = self
+            .state_manager
+            .borrow()
+            .get_tab(tab_id)
+            .map(|tab| tab.url.clone())
+            .ok_or_else(|| BrowserError::ConfigError(format!("No such tab: {}", tab_id)))?;
+        info!("Reloading discarded {}: {}", tab_id, url);
+        self.attach_content_webview(tab_id, &url)
+    }
+
+    /// Free memory by destroying the WebViews of background tabs
+    ///
+    /// The tabs stay open and reload their page when next shown.
+    fn discard_background_tabs(&mut self, rss_bytes: u64) {
+        let discarded = self.webview_manager.discard_background_tabs();
+        warn!(
+            "High memory use ({} MiB); discarded {} background tab(s)",
+            rss_bytes / (1024 * 1024),
+            discarded.len()
+        );
     }
 
     /// Resize content WebView
@@ -334,48 +547,280 @@ impl BrowserApp {
         self.webview_manager.resize_content(new_width, new_height)
     }
 
+    /// Check an HTTPS-first navigation's https URL in the background;
+    /// the result comes back as `AppEvent::HttpsChecked`
+    fn check_https(&self, nav_result: &NavigationResult) {
+        let proxy = self.event_proxy.clone();
+        self.navigation_service
+            .check_https(nav_result, move |check| {
+                // Only fails once the event loop has exited
+                let _ = proxy.send_event(AppEvent::HttpsChecked(check));
+            });
+    }
+
+    /// Tell the user if a site they navigated to didn't answer over
+    /// HTTPS; they decide whether to load the http URL
+    fn https_checked(&self, check: &HttpsCheck) {
+        // A stale check is for a page that's no longer shown
+        let Ok(Some(message)) = self.navigation_service.finish_https_check(check) else {
+            return;
+        };
+        warn!("{}", message);
+        let event = ChromeEvent::ShowError {
+            error_type: "https_unavailable".to_string(),
+            message,
+        };
+        if let Err(e) = self.webview_manager.emit_chrome_event(&event) {
+            error!("Failed to show HTTPS warning: {}", e);
+        }
+    }
+
+    /// Save page visits that have settled, or every pending one with
+    /// `all` (on close)
+    ///
+    /// Returns when the next pending visit settles.
+    fn save_settled_history(&self, all: bool) -> Option<Instant> {
+        let visits = {
+            let mut debouncer = self.history_debouncer.borrow_mut();
+            if all {
+                debouncer.take_all()
+            } else {
+                debouncer.take_settled(Instant::now())
+            }
+        };
+        let duplicate_window = self.navigation_service.duplicate_window();
+        if let Err(e) = save_visits(&self.db_path, &visits, duplicate_window) {
+            error!("Failed to save history: {}", e);
+        }
+        self.history_debouncer.borrow().next_deadline()
+    }
+
     /// Log metrics summary
     fn log_metrics(&self) {
         self.metrics.log_summary();
     }
+}
 
-    /// Perform health check (ORR pattern)
-    fn check_health(&self) {
-        let health = self.health_checker.check_health();
-        info!(
-            "Health check: {:?} - Readiness: {}, Liveness: {}",
-            health.status,
-            health.is_ready(),
-            health.is_alive()
-        );
+impl IpcHandler for BrowserApp {
+    fn navigate(&mut self, url: &str) -> Result<()> {
+        self.handle_navigate(url)
+    }
+
+    fn go_back(&mut self) -> Result<()> {
+        self.handle_go_back()
+    }
+
+    fn go_forward(&mut self) -> Result<()> {
+        self.handle_go_forward()
+    }
+
+    fn reload(&mut self) -> Result<()> {
+        self.handle_reload()
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        self.handle_stop()
+    }
+
+    fn home(&mut self) -> Result<()> {
+        let homepage = self.homepage();
+        self.handle_navigate(&homepage)
+    }
+
+    fn search(&mut self, query: &str) -> Result<()> {
+        let url = self.search_engines.resolve_query(query);
+        self.handle_navigate(url.as_str())
+    }
+
+    fn new_tab(&mut self, url: Option<&str>) -> Result<()> {
+        let blank = url.is_none();
+        let url = url.map_or_else(|| self.new_tab_url(), str::to_string);
+
+        // Same validation and blocklist as the URL bar
+        let nav_result = self.navigation_service.navigate(&url)?;
+        let tab_id = self.create_content_webview(&nav_result.url)?;
+        info!("✅ Opened {} for {}", tab_id, nav_result.url);
+        self.check_https(&nav_result);
+
+        // A tab opened without an address is waiting for one to be typed
+        if blank {
+            self.webview_manager.focus_chrome()?;
+        }
+        Ok(())
+    }
+
+    fn close_tab(&mut self, id: TabId) -> Result<()> {
+        let next_active = {
+            let mut state = self.state_manager.borrow_mut();
+            state.close_tab(id).map_err(BrowserError::ConfigError)?;
+            state.active_tab_id()
+        };
+
+        // A discarded tab has no WebView left to close
+        if self.webview_manager.content_webview_for(id).is_some() {
+            self.webview_manager.close_content_tab(id)?;
+        }
+        if let Some(next) = next_active {
+            self.show_tab(next)?;
+        }
+        Ok(())
+    }
+
+    fn switch_tab(&mut self, id: TabId) -> Result<()> {
+        self.state_manager
+            .borrow_mut()
+            .switch_tab(id)
+            .map_err(BrowserError::ConfigError)?;
+        self.show_tab(id)
+    }
+
+    fn toggle_bookmark(&mut self, url: &str) -> Result<()> {
+        let db = Database::new(&self.db_path).map_err(|e| BrowserError::Database(e.to_string()))?;
+        let bookmarked = db
+            .is_bookmarked(url)
+            .map_err(|e| BrowserError::Database(e.to_string()))?;
+
+        if bookmarked {
+            db.remove_bookmark(url)
+                .map_err(|e| BrowserError::Database(e.to_string()))?;
+            info!("✅ Removed bookmark: {}", url);
+        } else {
+            db.add_bookmark(url, None, None)
+                .map_err(|e| BrowserError::Database(e.to_string()))?;
+            info!("✅ Added bookmark: {}", url);
+        }
+        Ok(())
+    }
+
+    fn zoom(&mut self, factor: f64) -> Result<()> {
+        let factor = ipc::validate_zoom(factor)?;
+        self.webview_manager.zoom_content(factor)?;
+
+        // Remember the zoom for the page's site, except in private mode
+        if self.private {
+            return Ok(());
+        }
+        if let Some(origin) = self
+            .webview_manager
+            .current_content_url()
+            .as_deref()
+            .and_then(origin_of)
+        {
+            Database::new(&self.db_path)
+                .and_then(|db| db.set_zoom(&origin, factor))
+                .map_err(|e| BrowserError::Database(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Saved search engines, or the defaults with `search_url` selected
+///
+/// Problems are logged; the URL bar can always search.
+fn load_search_engines(db_path: &std::path::Path, search_url: &str) -> SearchEngines {
+    match SearchEngines::load(db_path) {
+        Ok(Some(engines)) => return engines,
+        Ok(None) => {}
+        Err(e) => warn!("Using default search engines: {}", e),
+    }
+    SearchEngines::with_default_url(search_url).unwrap_or_else(|e| {
+        warn!("Ignoring configured search_url: {}", e);
+        SearchEngines::default()
+    })
+}
+
+/// The window's current size and position, for saving on close
+fn current_geometry(window: &Window) -> WindowGeometry {
+    let position = window.outer_position().unwrap_or_default();
+    let size = window.inner_size();
+    WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: window.is_maximized(),
     }
 }
 
 fn main() -> anyhow::Result<()> {
+    let args = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(CliError::Help) => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    // Load config before logging, which it configures
+    let (mut config, config_error) = AppConfig::load_or_default(args.config.as_deref());
+    config.private |= args.private;
+
     // Initialize logging
     tracing_subscriber::fmt()
-        .with_max_level(Level::INFO)
+        .with_max_level(config.log_level().unwrap_or(Level::INFO))
         .with_target(false)
         .init();
 
+    error::set_json_logging(config.json_logs);
+
     info!("Starting Browser MVP (AWS Service Architecture)");
+    if let Some(e) = config_error {
+        warn!("Using default config: {}", e);
+    }
 
-    let event_loop = EventLoop::new();
-    let window = WindowBuilder::new()
+    let event_loop = EventLoopBuilder::<AppEvent>::with_user_event().build();
+    let mut window_builder = WindowBuilder::new()
         .with_title("Browser MVP")
-        .with_inner_size(tao::dpi::LogicalSize::new(1024.0, 768.0))
-        .build(&event_loop)?;
+        .with_inner_size(tao::dpi::LogicalSize::new(
+            config.window.width,
+            config.window.height,
+        ));
+
+    // Reopen where the window was last closed, on a monitor that's present
+    let saved_geometry = WindowGeometry::load(&config.db_path).unwrap_or_else(|e| {
+        warn!("Ignoring saved window state: {}", e);
+        None
+    });
+    let restored = saved_geometry.map(|saved| {
+        let monitors: Vec<ScreenRect> = event_loop
+            .available_monitors()
+            .map(|monitor| ScreenRect {
+                x: monitor.position().x,
+                y: monitor.position().y,
+                width: monitor.size().width,
+                height: monitor.size().height,
+            })
+            .collect();
+        saved.clamp_to(&monitors)
+    });
+    if let Some(geometry) = restored {
+        info!("Restoring window geometry: {:?}", geometry);
+        window_builder = window_builder
+            .with_inner_size(tao::dpi::PhysicalSize::new(geometry.width, geometry.height))
+            .with_position(tao::dpi::PhysicalPosition::new(geometry.x, geometry.y))
+            .with_maximized(geometry.maximized);
+    }
+
+    let window = Rc::new(window_builder.build(&event_loop)?);
 
-    let app = BrowserApp::new(&window)?;
+    let app = BrowserApp::new(window.clone(), &config, event_loop.create_proxy())?;
 
     // Create initial content WebView
     {
         let mut app_guard = app.borrow_mut();
         if let Some(ref mut browser_app) = *app_guard {
-            browser_app.create_content_webview(&window, "https://example.com")?;
+            // Same validation, blocklist and HTTPS-first check as the URL bar
+            let initial_url = args.url.as_deref().unwrap_or(&config.home_page);
+            if let Err(e) = browser_app.new_tab(Some(initial_url)) {
+                error!("Failed to open {}: {}", initial_url, e);
+                browser_app.new_tab(None)?;
+            }
 
-            // Initial health check and metrics log
-            browser_app.check_health();
+            // Initial metrics log; health checks run in the background
             browser_app.log_metrics();
         }
     }
@@ -383,25 +828,75 @@ fn main() -> anyhow::Result<()> {
     info!("✅ Browser MVP ready (AWS service architecture)");
 
     let app_for_resize = app.clone();
+    let db_path = config.db_path.clone();
+    // Until the window moves, the restored geometry is the one to save;
+    // a window restored maximized reports its maximized size
+    let mut geometry = restored.unwrap_or_else(|| current_geometry(&window));
+    let mut next_memory_sample = Instant::now() + MEMORY_SAMPLE_INTERVAL;
 
     event_loop.run(move |event, _, control_flow| {
-        *control_flow = ControlFlow::Wait;
+        *control_flow = ControlFlow::WaitUntil(next_memory_sample);
+
+        if Instant::now() >= next_memory_sample {
+            if let Some(ref app) = *app.borrow() {
+                app.metrics.sample_memory();
+            }
+            next_memory_sample = Instant::now() + MEMORY_SAMPLE_INTERVAL;
+            *control_flow = ControlFlow::WaitUntil(next_memory_sample);
+        }
+
+        // Wake again when the next pending history visit settles
+        if let Some(ref app) = *app.borrow() {
+            if let Some(deadline) = app.save_settled_history(false) {
+                *control_flow = ControlFlow::WaitUntil(deadline.min(next_memory_sample));
+            }
+        }
+
+        match &event {
+            Event::UserEvent(AppEvent::HttpsChecked(check)) => {
+                if let Some(ref app) = *app.borrow() {
+                    app.https_checked(check);
+                }
+            }
+            Event::UserEvent(AppEvent::MemoryPressure(rss_bytes)) => {
+                if let Some(ref mut app) = *app.borrow_mut() {
+                    app.discard_background_tabs(*rss_bytes);
+                }
+            }
+            _ => {}
+        }
 
         if let Event::WindowEvent { event, .. } = event {
             match event {
                 WindowEvent::CloseRequested => {
                     info!("Close requested");
 
-                    // Log final metrics before exit
+                    geometry.maximized = window.is_maximized();
+                    if let Err(e) = geometry.save(&db_path) {
+                        warn!("Failed to save window state: {}", e);
+                    }
+
+                    // Save visits still settling, and log final metrics
                     if let Some(ref app) = *app.borrow() {
+                        app.save_settled_history(true);
                         app.log_metrics();
                     }
 
                     *control_flow = ControlFlow::Exit;
                 }
+                WindowEvent::Moved(_) => {
+                    if !window.is_maximized() {
+                        geometry = current_geometry(&window);
+                    }
+                }
                 WindowEvent::Resized(new_size) => {
                     info!("Window resized to {}x{}", new_size.width, new_size.height);
 
+                    // Keep the size to restore to, not the maximized one
+                    if !window.is_maximized() {
+                        geometry = current_geometry(&window);
+                    }
+
                     let mut app_guard = app_for_resize.borrow_mut();
                     if let Some(ref mut browser_app) = *app_guard {
                         if let Err(e) = browser_app.resize_content(new_size.width, new_size.height)
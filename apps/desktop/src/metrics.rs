@@ -7,30 +7,158 @@
 //!
 //! - **Navigation success rate**: Change failure rate analog
 //! - **Page load time**: p50, p95, p99 percentiles
-//! - **Error rate**: Last 5 minutes
+//! - **Phase timings**: DNS, connect, TLS, TTFB and total, p50/p95
+//! - **Error rate**: Last 100 navigations within the last 5 minutes
 //! - **MTTR**: Mean time to recovery (time between errors)
+//! - **Per-domain**: Navigations, errors and load time by host
+//! - **Memory**: Resident set size, with a pressure callback
 
 // Allow dead code temporarily - APIs will be integrated in Week 2
 #![allow(dead_code)]
 
+use crate::health::HealthThresholds;
+use std::collections::{HashMap, VecDeque};
 use std::sync::{
     atomic::{AtomicU64, Ordering},
     Arc, Mutex,
 };
 use std::time::{Duration, Instant};
-use tracing::info;
+use tracing::{info, warn};
+
+/// Maximum navigations considered for the error rate
+const ERROR_WINDOW_SIZE: usize = 100;
+
+/// Maximum age of navigations considered for the error rate
+const ERROR_WINDOW_DURATION: Duration = Duration::from_secs(300);
+
+/// Maximum number of domains tracked before evicting the least active
+const MAX_TRACKED_DOMAINS: usize = 100;
+
+/// Maximum navigation timings kept for phase percentiles
+const MAX_TIMING_SAMPLES: usize = 1000;
 
 /// Metrics snapshot for reporting
 #[derive(Debug, Clone)]
 pub struct MetricsSnapshot {
+    /// Lifetime navigation count
     pub total_navigations: u64,
+    /// Lifetime failure count
     pub failed_navigations: u64,
+    /// Error rate over the recent window (see `Metrics::get_error_rate`)
     pub error_rate: f64,
     pub avg_load_time_ms: f64,
     pub p95_load_time_ms: f64,
     pub p99_load_time_ms: f64,
     pub last_error: Option<String>,
     pub mttr_seconds: f64,
+    /// Latest resident memory sample, None before the first
+    pub memory_rss_bytes: Option<u64>,
+    /// Per-phase percentiles of recorded navigation timings
+    pub phase_timings: PhaseTimings,
+}
+
+/// How long each phase of a page load took
+///
+/// Taken from the page's Navigation Timing entry, so these are the
+/// engine's own measurements. A reused connection has no DNS, connect or
+/// TLS time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NavTiming {
+    /// Resolving the host name
+    pub dns: Duration,
+    /// Opening the TCP connection, not counting TLS
+    pub connect: Duration,
+    /// TLS handshake, None for plain http or a reused connection
+    pub tls: Option<Duration>,
+    /// From sending the request to the first byte of the response
+    pub ttfb: Duration,
+    /// Start of the navigation to the end of the load event
+    pub total: Duration,
+}
+
+/// Median and 95th percentile of one navigation phase
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PhasePercentiles {
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+}
+
+impl PhasePercentiles {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        Self {
+            p50_ms: ms(percentile(&samples, 0.5)),
+            p95_ms: ms(percentile(&samples, 0.95)),
+        }
+    }
+}
+
+/// Phase percentiles over recorded `NavTiming`s
+///
+/// TLS percentiles only count https navigations.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PhaseTimings {
+    /// Timings aggregated
+    pub samples: usize,
+    pub dns: PhasePercentiles,
+    pub connect: PhasePercentiles,
+    pub tls: PhasePercentiles,
+    pub ttfb: PhasePercentiles,
+    pub total: PhasePercentiles,
+}
+
+impl PhaseTimings {
+    fn from_timings<'a>(timings: impl Iterator<Item = &'a NavTiming> + Clone) -> Self {
+        let phase = |select: fn(&NavTiming) -> Option<Duration>| {
+            PhasePercentiles::from_samples(timings.clone().filter_map(select).collect())
+        };
+        Self {
+            samples: timings.clone().count(),
+            dns: phase(|t| Some(t.dns)),
+            connect: phase(|t| Some(t.connect)),
+            tls: phase(|t| t.tls),
+            ttfb: phase(|t| Some(t.ttfb)),
+            total: phase(|t| Some(t.total)),
+        }
+    }
+}
+
+/// Navigation statistics for a single domain
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DomainStats {
+    /// Navigation attempts
+    pub navigations: u64,
+    /// Failed navigations
+    pub errors: u64,
+    /// Average load time of successful navigations
+    pub avg_load_time_ms: f64,
+}
+
+impl DomainStats {
+    fn record(&mut self, success: bool, duration: Duration) {
+        self.navigations += 1;
+
+        if success {
+            // Running average over successful loads
+            let successes = (self.navigations - self.errors) as f64;
+            let load_ms = duration.as_secs_f64() * 1000.0;
+            self.avg_load_time_ms += (load_ms - self.avg_load_time_ms) / successes;
+        } else {
+            self.errors += 1;
+        }
+    }
+}
+
+/// Callback registered with `Metrics::on_memory_pressure`
+type MemoryPressureCallback = Box<dyn Fn(u64) + Send + Sync>;
+
+/// Memory threshold and its callback
+struct MemoryPressure {
+    threshold_bytes: u64,
+    callback: MemoryPressureCallback,
+    /// Above the threshold since the callback last fired
+    triggered: bool,
 }
 
 /// Metrics collector following AWS operational excellence patterns
@@ -39,12 +167,25 @@ pub struct Metrics {
     navigation_count: AtomicU64,
     navigation_errors: AtomicU64,
 
+    // Recent outcomes (timestamp, success) for the windowed error rate
+    recent_outcomes: Mutex<VecDeque<(Instant, bool)>>,
+
     // Performance metrics
     page_load_times: Mutex<Vec<Duration>>,
 
     // Error tracking for COE/MTTR
     last_error: Mutex<Option<(Instant, String)>>,
     last_recovery: Mutex<Option<Instant>>,
+
+    // Per-domain breakdown
+    domain_stats: Mutex<HashMap<String, DomainStats>>,
+
+    // Recent navigation phase timings, by domain
+    nav_timings: Mutex<VecDeque<(String, NavTiming)>>,
+
+    // Resident memory (0 = not sampled yet) and pressure signal
+    memory_rss_bytes: AtomicU64,
+    memory_pressure: Mutex<Option<MemoryPressure>>,
 }
 
 impl Metrics {
@@ -53,9 +194,14 @@ impl Metrics {
         Arc::new(Self {
             navigation_count: AtomicU64::new(0),
             navigation_errors: AtomicU64::new(0),
+            recent_outcomes: Mutex::new(VecDeque::with_capacity(ERROR_WINDOW_SIZE)),
             page_load_times: Mutex::new(Vec::new()),
             last_error: Mutex::new(None),
             last_recovery: Mutex::new(None),
+            domain_stats: Mutex::new(HashMap::new()),
+            nav_timings: Mutex::new(VecDeque::new()),
+            memory_rss_bytes: AtomicU64::new(0),
+            memory_pressure: Mutex::new(None),
         })
     }
 
@@ -66,6 +212,7 @@ impl Metrics {
     /// * `duration` - Time taken for navigation
     pub fn record_navigation(&self, success: bool, duration: Duration) {
         self.navigation_count.fetch_add(1, Ordering::Relaxed);
+        self.record_outcome(success);
 
         if success {
             // Record successful load time
@@ -85,19 +232,79 @@ impl Metrics {
         }
     }
 
+    /// Record a navigation attempt attributed to a domain
+    ///
+    /// Updates the overall metrics as `record_navigation` does, plus the
+    /// per-domain breakdown. When more than 100 domains are tracked the
+    /// one with the fewest navigations is evicted.
+    pub fn record_navigation_for(&self, domain: &str, success: bool, duration: Duration) {
+        self.record_navigation(success, duration);
+
+        let mut domains = self.domain_stats.lock().unwrap();
+        if !domains.contains_key(domain) && domains.len() >= MAX_TRACKED_DOMAINS {
+            let least_active = domains
+                .iter()
+                .min_by_key(|(_, stats)| stats.navigations)
+                .map(|(name, _)| name.clone());
+            if let Some(name) = least_active {
+                domains.remove(&name);
+            }
+        }
+
+        domains
+            .entry(domain.to_string())
+            .or_default()
+            .record(success, duration);
+    }
+
+    /// Get per-domain statistics, most navigated first
+    pub fn get_domain_stats(&self) -> Vec<(String, DomainStats)> {
+        let domains = self.domain_stats.lock().unwrap();
+
+        let mut stats: Vec<_> = domains
+            .iter()
+            .map(|(name, stats)| (name.clone(), stats.clone()))
+            .collect();
+        stats.sort_by(|a, b| b.1.navigations.cmp(&a.1.navigations).then(a.0.cmp(&b.0)));
+        stats
+    }
+
     /// Record an error for COE analysis
     pub fn record_error(&self, error: &str) {
         *self.last_error.lock().unwrap() = Some((Instant::now(), error.to_string()));
         self.navigation_errors.fetch_add(1, Ordering::Relaxed);
+        self.record_outcome(false);
         info!("[METRICS] Error recorded: {}", error);
     }
 
+    /// Push an outcome into the error rate window, dropping the oldest
+    fn record_outcome(&self, success: bool) {
+        let mut outcomes = self.recent_outcomes.lock().unwrap();
+        if outcomes.len() == ERROR_WINDOW_SIZE {
+            outcomes.pop_front();
+        }
+        outcomes.push_back((Instant::now(), success));
+    }
+
     /// Get error rate (DORA: change failure rate analog)
     ///
-    /// Returns ratio of failed navigations to total navigations
+    /// Returns ratio of failures to outcomes over the last 100 navigations
+    /// within the last 5 minutes, so old failures stop counting once they
+    /// age out. Lifetime totals are in `get_stats()`.
     pub fn get_error_rate(&self) -> f64 {
-        let total = self.navigation_count.load(Ordering::Relaxed);
-        let errors = self.navigation_errors.load(Ordering::Relaxed);
+        self.error_rate_at(Instant::now())
+    }
+
+    /// Windowed error rate as seen at `now`
+    fn error_rate_at(&self, now: Instant) -> f64 {
+        let outcomes = self.recent_outcomes.lock().unwrap();
+
+        let (total, errors) = outcomes
+            .iter()
+            .filter(|(time, _)| now.saturating_duration_since(*time) < ERROR_WINDOW_DURATION)
+            .fold((0u64, 0u64), |(total, errors), (_, success)| {
+                (total + 1, errors + u64::from(!success))
+            });
 
         if total == 0 {
             0.0
@@ -131,16 +338,21 @@ impl Metrics {
     /// - Error rate < 5% (last 100 navigations)
     /// - No errors in last 5 minutes
     pub fn is_healthy(&self) -> bool {
+        self.is_healthy_with(&HealthThresholds::default())
+    }
+
+    /// Check if service is healthy against custom thresholds
+    pub fn is_healthy_with(&self, thresholds: &HealthThresholds) -> bool {
         let error_rate = self.get_error_rate();
 
         // Check error rate threshold
-        if error_rate > 0.05 {
+        if error_rate > thresholds.max_error_rate {
             return false;
         }
 
         // Check last error time
         if let Some((error_time, _)) = *self.last_error.lock().unwrap() {
-            if error_time.elapsed() < Duration::from_secs(300) {
+            if error_time.elapsed() < thresholds.error_recency {
                 return false;
             }
         }
@@ -148,6 +360,81 @@ impl Metrics {
         true
     }
 
+    /// Record the phase timings of a navigation to `domain`
+    ///
+    /// The last 1000 timings are kept for the percentiles in
+    /// `MetricsSnapshot::phase_timings` and `get_phase_timings_for`.
+    pub fn record_timing(&self, domain: &str, timing: NavTiming) {
+        let mut timings = self.nav_timings.lock().unwrap();
+        if timings.len() >= MAX_TIMING_SAMPLES {
+            timings.pop_front();
+        }
+        timings.push_back((domain.to_string(), timing));
+    }
+
+    /// Phase percentiles of the recent navigations to `domain`
+    pub fn get_phase_timings_for(&self, domain: &str) -> PhaseTimings {
+        let timings = self.nav_timings.lock().unwrap();
+        PhaseTimings::from_timings(
+            timings
+                .iter()
+                .filter(|(name, _)| name == domain)
+                .map(|(_, timing)| timing),
+        )
+    }
+
+    /// Record a sample of the process's resident memory
+    ///
+    /// Fires the `on_memory_pressure` callback when the sample crosses
+    /// the threshold. It fires once per crossing: memory has to drop back
+    /// below the threshold before it can fire again.
+    pub fn record_memory(&self, rss_bytes: u64) {
+        self.memory_rss_bytes.store(rss_bytes, Ordering::Relaxed);
+
+        let mut pressure = self.memory_pressure.lock().unwrap();
+        let Some(pressure) = pressure.as_mut() else {
+            return;
+        };
+        if rss_bytes < pressure.threshold_bytes {
+            pressure.triggered = false;
+        } else if !pressure.triggered {
+            pressure.triggered = true;
+            warn!(
+                "[METRICS] Memory pressure: {} bytes resident (threshold {})",
+                rss_bytes, pressure.threshold_bytes
+            );
+            (pressure.callback)(rss_bytes);
+        }
+    }
+
+    /// Sample resident memory, web-content processes included, and record it
+    ///
+    /// Returns the sample, or None where it can't be read (see
+    /// `process_rss_bytes`).
+    pub fn sample_memory(&self) -> Option<u64> {
+        let rss_bytes = process_rss_bytes()?;
+        self.record_memory(rss_bytes);
+        Some(rss_bytes)
+    }
+
+    /// Call `callback` when resident memory crosses `threshold_bytes`
+    ///
+    /// The app uses this to discard background tabs. Replaces any
+    /// earlier callback. The callback runs on whichever thread recorded
+    /// the sample, with the metrics' memory lock held, so it must not
+    /// record memory itself.
+    pub fn on_memory_pressure(
+        &self,
+        threshold_bytes: u64,
+        callback: impl Fn(u64) + Send + Sync + 'static,
+    ) {
+        *self.memory_pressure.lock().unwrap() = Some(MemoryPressure {
+            threshold_bytes,
+            callback: Box::new(callback),
+            triggered: false,
+        });
+    }
+
     /// Get complete metrics snapshot
     pub fn get_stats(&self) -> MetricsSnapshot {
         let total = self.navigation_count.load(Ordering::Relaxed);
@@ -164,11 +451,8 @@ impl Metrics {
             let mut sorted = times.clone();
             sorted.sort();
 
-            let p95_idx = (sorted.len() as f64 * 0.95) as usize;
-            let p99_idx = (sorted.len() as f64 * 0.99) as usize;
-
-            let p95 = sorted.get(p95_idx).unwrap_or(&Duration::ZERO).as_millis() as f64;
-            let p99 = sorted.get(p99_idx).unwrap_or(&Duration::ZERO).as_millis() as f64;
+            let p95 = percentile(&sorted, 0.95).as_millis() as f64;
+            let p99 = percentile(&sorted, 0.99).as_millis() as f64;
 
             (avg, p95, p99)
         };
@@ -191,9 +475,179 @@ impl Metrics {
             p99_load_time_ms: p99_ms,
             last_error,
             mttr_seconds: mttr,
+            memory_rss_bytes: match self.memory_rss_bytes.load(Ordering::Relaxed) {
+                0 => None,
+                rss_bytes => Some(rss_bytes),
+            },
+            phase_timings: PhaseTimings::from_timings(
+                self.nav_timings
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .map(|(_, timing)| timing),
+            ),
+        }
+    }
+
+    /// Reset all counters and buffers to their initial state
+    pub fn reset(&self) {
+        self.navigation_count.store(0, Ordering::Relaxed);
+        self.navigation_errors.store(0, Ordering::Relaxed);
+        self.recent_outcomes.lock().unwrap().clear();
+        self.page_load_times.lock().unwrap().clear();
+        *self.last_error.lock().unwrap() = None;
+        *self.last_recovery.lock().unwrap() = None;
+        self.domain_stats.lock().unwrap().clear();
+        self.nav_timings.lock().unwrap().clear();
+        self.memory_rss_bytes.store(0, Ordering::Relaxed);
+        if let Some(pressure) = self.memory_pressure.lock().unwrap().as_mut() {
+            pressure.triggered = false;
+        }
+        info!("[METRICS] Reset");
+    }
+
+    /// Get the change since a previous snapshot
+    ///
+    /// Counters are differences and `error_rate` is the failure ratio within
+    /// the interval. Load times, last error and MTTR can't be diffed, so
+    /// they are the current values. If metrics were reset after `prev`
+    /// was taken, counters saturate at 0.
+    pub fn delta_since(&self, prev: &MetricsSnapshot) -> MetricsSnapshot {
+        let current = self.get_stats();
+
+        let total = current
+            .total_navigations
+            .saturating_sub(prev.total_navigations);
+        let failed = current
+            .failed_navigations
+            .saturating_sub(prev.failed_navigations);
+        let error_rate = if total == 0 {
+            0.0
+        } else {
+            (failed as f64) / (total as f64)
+        };
+
+        MetricsSnapshot {
+            total_navigations: total,
+            failed_navigations: failed,
+            error_rate,
+            ..current
         }
     }
 
+    /// Export metrics in the Prometheus text exposition format
+    ///
+    /// Page load times are exported as a summary over the retained samples
+    /// (last 1000 successful loads).
+    pub fn to_prometheus(&self) -> String {
+        let stats = self.get_stats();
+        let mut out = String::new();
+
+        write_metric(
+            &mut out,
+            "browser_navigations_total",
+            "counter",
+            "Total navigation attempts",
+            &[("", stats.total_navigations as f64)],
+        );
+        write_metric(
+            &mut out,
+            "browser_navigation_errors_total",
+            "counter",
+            "Total failed navigations and recorded errors",
+            &[("", stats.failed_navigations as f64)],
+        );
+        write_metric(
+            &mut out,
+            "browser_error_rate",
+            "gauge",
+            "Error rate over the last 100 navigations within 5 minutes",
+            &[("", stats.error_rate)],
+        );
+
+        {
+            let times = self.page_load_times.lock().unwrap();
+            let mut sorted = times.clone();
+            sorted.sort();
+            let sum: Duration = sorted.iter().sum();
+
+            out.push_str(
+                "# HELP browser_page_load_seconds Page load time of successful navigations\n",
+            );
+            out.push_str("# TYPE browser_page_load_seconds summary\n");
+            for quantile in [0.5, 0.95, 0.99] {
+                out.push_str(&format!(
+                    "browser_page_load_seconds{{quantile=\"{}\"}} {}\n",
+                    quantile,
+                    percentile(&sorted, quantile).as_secs_f64()
+                ));
+            }
+            out.push_str(&format!(
+                "browser_page_load_seconds_sum {}\n",
+                sum.as_secs_f64()
+            ));
+            out.push_str(&format!(
+                "browser_page_load_seconds_count {}\n",
+                sorted.len()
+            ));
+        }
+
+        write_metric(
+            &mut out,
+            "browser_mttr_seconds",
+            "gauge",
+            "Time between the last error and the following recovery",
+            &[("", stats.mttr_seconds)],
+        );
+        write_metric(
+            &mut out,
+            "browser_healthy",
+            "gauge",
+            "Whether the metrics health check passes (1) or not (0)",
+            &[("", if self.is_healthy() { 1.0 } else { 0.0 })],
+        );
+
+        if let Some(rss_bytes) = stats.memory_rss_bytes {
+            write_metric(
+                &mut out,
+                "browser_memory_rss_bytes",
+                "gauge",
+                "Resident memory of the browser process",
+                &[("", rss_bytes as f64)],
+            );
+        }
+
+        let domains = self.get_domain_stats();
+        let labels: Vec<String> = domains
+            .iter()
+            .map(|(name, _)| format!("{{domain=\"{}\"}}", escape_label(name)))
+            .collect();
+        write_metric(
+            &mut out,
+            "browser_domain_navigations_total",
+            "counter",
+            "Navigation attempts per domain",
+            &labels
+                .iter()
+                .zip(&domains)
+                .map(|(label, (_, stats))| (label.as_str(), stats.navigations as f64))
+                .collect::<Vec<_>>(),
+        );
+        write_metric(
+            &mut out,
+            "browser_domain_navigation_errors_total",
+            "counter",
+            "Failed navigations per domain",
+            &labels
+                .iter()
+                .zip(&domains)
+                .map(|(label, (_, stats))| (label.as_str(), stats.errors as f64))
+                .collect::<Vec<_>>(),
+        );
+
+        out
+    }
+
     /// Print metrics summary to logs
     pub fn log_summary(&self) {
         let stats = self.get_stats();
@@ -206,6 +660,9 @@ impl Metrics {
         info!("  P95 load time: {:.2}ms", stats.p95_load_time_ms);
         info!("  P99 load time: {:.2}ms", stats.p99_load_time_ms);
         info!("  MTTR: {:.2}s", stats.mttr_seconds);
+        if let Some(rss_bytes) = stats.memory_rss_bytes {
+            info!("  Memory (RSS): {:.1} MiB", rss_bytes as f64 / MIB);
+        }
         info!("  Healthy: {}", self.is_healthy());
     }
 }
@@ -215,13 +672,115 @@ impl Default for Metrics {
         Self {
             navigation_count: AtomicU64::new(0),
             navigation_errors: AtomicU64::new(0),
+            recent_outcomes: Mutex::new(VecDeque::with_capacity(ERROR_WINDOW_SIZE)),
             page_load_times: Mutex::new(Vec::new()),
             last_error: Mutex::new(None),
             last_recovery: Mutex::new(None),
+            domain_stats: Mutex::new(HashMap::new()),
+            nav_timings: Mutex::new(VecDeque::new()),
+            memory_rss_bytes: AtomicU64::new(0),
+            memory_pressure: Mutex::new(None),
         }
     }
 }
 
+/// Bytes in a mebibyte, for logging memory
+const MIB: f64 = 1024.0 * 1024.0;
+
+/// Resident memory of this process and the processes it started
+///
+/// Counts the WebView's web-content and network processes, which hold
+/// most page memory. WebKitGTK may start them through a sandbox launcher,
+/// so every descendant is included. Read from `/proc`; None on other
+/// platforms or if it can't be read.
+pub fn process_rss_bytes() -> Option<u64> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let processes: Vec<ProcessMemory> = std::fs::read_dir("/proc")
+        .ok()?
+        .filter_map(|entry| {
+            let pid = entry.ok()?.file_name().to_str()?.parse().ok()?;
+            let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+            Some(ProcessMemory {
+                pid,
+                parent: parse_status_field(&status, "PPid:")?,
+                // Kernel threads have no resident set
+                rss_bytes: parse_vm_rss(&status).unwrap_or(0),
+            })
+        })
+        .collect();
+    tree_rss_bytes(std::process::id(), &processes)
+}
+
+/// One process's parent and resident memory, from `/proc/<pid>/status`
+struct ProcessMemory {
+    pid: u32,
+    parent: u32,
+    rss_bytes: u64,
+}
+
+/// Total resident memory of `root` and its descendants, None if `root`
+/// isn't listed
+fn tree_rss_bytes(root: u32, processes: &[ProcessMemory]) -> Option<u64> {
+    let mut total = processes.iter().find(|p| p.pid == root)?.rss_bytes;
+    let mut pending = vec![root];
+    while let Some(parent) = pending.pop() {
+        for child in processes.iter().filter(|p| p.parent == parent) {
+            total += child.rss_bytes;
+            pending.push(child.pid);
+        }
+    }
+    Some(total)
+}
+
+/// Numeric field from a `/proc/<pid>/status` file, e.g. `PPid:`
+fn parse_status_field(status: &str, name: &str) -> Option<u32> {
+    let line = status.lines().find(|line| line.starts_with(name))?;
+    line[name.len()..].trim().parse().ok()
+}
+
+/// `VmRSS` from a `/proc/<pid>/status` file, in bytes
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib: u64 = line["VmRSS:".len()..]
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kib * 1024)
+}
+
+/// Value at quantile `q` of sorted samples (zero when empty)
+fn percentile(sorted: &[Duration], q: f64) -> Duration {
+    let idx = (sorted.len() as f64 * q) as usize;
+    sorted
+        .get(idx.min(sorted.len().saturating_sub(1)))
+        .copied()
+        .unwrap_or(Duration::ZERO)
+}
+
+/// Append one Prometheus metric family
+///
+/// Each sample is a (label set, value) pair; the label set is either empty
+/// or a preformatted `{name="value"}` string.
+fn write_metric(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(&str, f64)]) {
+    out.push_str(&format!("# HELP {} {}\n", name, help));
+    out.push_str(&format!("# TYPE {} {}\n", name, kind));
+    for (labels, value) in samples {
+        out.push_str(&format!("{}{} {}\n", name, labels, value));
+    }
+}
+
+/// Escape a Prometheus label value
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -322,4 +881,312 @@ mod tests {
         assert_eq!(stats.last_error, Some("Test error".to_string()));
         assert_eq!(stats.failed_navigations, 1);
     }
+
+    #[test]
+    fn test_reset() {
+        let metrics = Metrics::new();
+
+        metrics.record_navigation(true, Duration::from_millis(100));
+        metrics.record_navigation(false, Duration::ZERO);
+        metrics.record_error("Test error");
+
+        metrics.reset();
+
+        let stats = metrics.get_stats();
+        assert_eq!(stats.total_navigations, 0);
+        assert_eq!(stats.failed_navigations, 0);
+        assert_eq!(stats.error_rate, 0.0);
+        assert_eq!(stats.avg_load_time_ms, 0.0);
+        assert_eq!(stats.last_error, None);
+        assert!(metrics.is_healthy());
+    }
+
+    #[test]
+    fn test_delta_since() {
+        let metrics = Metrics::new();
+
+        metrics.record_navigation(true, Duration::from_millis(100));
+        metrics.record_navigation(false, Duration::ZERO);
+        let before = metrics.get_stats();
+
+        metrics.record_navigation(true, Duration::from_millis(100));
+        metrics.record_navigation(true, Duration::from_millis(100));
+        metrics.record_navigation(true, Duration::from_millis(100));
+        metrics.record_navigation(false, Duration::ZERO);
+
+        let delta = metrics.delta_since(&before);
+        assert_eq!(delta.total_navigations, 4);
+        assert_eq!(delta.failed_navigations, 1);
+        assert!((delta.error_rate - 0.25).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_delta_since_after_reset() {
+        let metrics = Metrics::new();
+
+        metrics.record_navigation(true, Duration::from_millis(100));
+        metrics.record_navigation(true, Duration::from_millis(100));
+        let before = metrics.get_stats();
+
+        metrics.reset();
+        metrics.record_navigation(true, Duration::from_millis(100));
+
+        let delta = metrics.delta_since(&before);
+        assert_eq!(delta.total_navigations, 0);
+        assert_eq!(delta.error_rate, 0.0);
+    }
+
+    #[test]
+    fn test_domain_stats_aggregation() {
+        let metrics = Metrics::new();
+
+        metrics.record_navigation_for("example.com", true, Duration::from_millis(100));
+        metrics.record_navigation_for("example.com", true, Duration::from_millis(300));
+        metrics.record_navigation_for("example.com", false, Duration::ZERO);
+        metrics.record_navigation_for("github.com", true, Duration::from_millis(50));
+
+        let domains = metrics.get_domain_stats();
+        assert_eq!(domains.len(), 2);
+
+        let (name, example) = &domains[0];
+        assert_eq!(name, "example.com");
+        assert_eq!(example.navigations, 3);
+        assert_eq!(example.errors, 1);
+        assert!((example.avg_load_time_ms - 200.0).abs() < 0.01);
+
+        let (name, github) = &domains[1];
+        assert_eq!(name, "github.com");
+        assert_eq!(github.navigations, 1);
+        assert_eq!(github.errors, 0);
+        assert!((github.avg_load_time_ms - 50.0).abs() < 0.01);
+
+        // Overall metrics include per-domain navigations
+        let stats = metrics.get_stats();
+        assert_eq!(stats.total_navigations, 4);
+        assert_eq!(stats.failed_navigations, 1);
+    }
+
+    #[test]
+    fn test_domain_stats_evicts_least_active() {
+        let metrics = Metrics::new();
+
+        metrics.record_navigation_for("busy.com", true, Duration::from_millis(10));
+        metrics.record_navigation_for("busy.com", true, Duration::from_millis(10));
+        for i in 0..MAX_TRACKED_DOMAINS {
+            metrics.record_navigation_for(&format!("site{}.com", i), true, Duration::ZERO);
+        }
+
+        let domains = metrics.get_domain_stats();
+        assert_eq!(domains.len(), MAX_TRACKED_DOMAINS);
+        assert_eq!(domains[0].0, "busy.com");
+    }
+
+    #[test]
+    fn test_to_prometheus() {
+        let metrics = Metrics::new();
+
+        metrics.record_navigation_for("example.com", true, Duration::from_millis(100));
+        metrics.record_navigation_for("example.com", true, Duration::from_millis(200));
+        metrics.record_navigation(false, Duration::ZERO);
+
+        let output = metrics.to_prometheus();
+
+        assert!(output.contains("# TYPE browser_navigations_total counter"));
+        assert!(output.contains("\nbrowser_navigations_total 3\n"));
+        assert!(output.contains("\nbrowser_navigation_errors_total 1\n"));
+        assert!(output.contains("# TYPE browser_page_load_seconds summary"));
+        assert!(output.contains("browser_page_load_seconds{quantile=\"0.95\"}"));
+        assert!(output.contains("\nbrowser_page_load_seconds_count 2\n"));
+        assert!(output.contains("\nbrowser_healthy 0\n"));
+        assert!(output.contains("browser_domain_navigations_total{domain=\"example.com\"} 2"));
+    }
+
+    fn timing(dns: u64, connect: u64, tls: Option<u64>, ttfb: u64) -> NavTiming {
+        let ms = Duration::from_millis;
+        NavTiming {
+            dns: ms(dns),
+            connect: ms(connect),
+            tls: tls.map(ms),
+            ttfb: ms(ttfb),
+            total: ms(dns + connect + tls.unwrap_or(0) + ttfb),
+        }
+    }
+
+    #[test]
+    fn test_phase_timing_percentiles() {
+        let metrics = Metrics::new();
+        assert_eq!(metrics.get_stats().phase_timings, PhaseTimings::default());
+
+        for i in 1..=20 {
+            metrics.record_timing("example.com", timing(i, 2 * i, Some(10), 100 + i));
+        }
+        metrics.record_timing("plain.example", timing(1000, 1000, None, 1000));
+
+        let phases = metrics.get_stats().phase_timings;
+        assert_eq!(phases.samples, 21);
+        assert_eq!(phases.dns.p50_ms, 11.0);
+        assert_eq!(phases.dns.p95_ms, 20.0);
+        assert_eq!(phases.connect.p50_ms, 22.0);
+        assert_eq!(phases.ttfb.p50_ms, 111.0);
+        assert_eq!(phases.total.p50_ms, 154.0);
+        // http navigations don't count towards TLS
+        assert_eq!(phases.tls.p50_ms, 10.0);
+        assert_eq!(phases.tls.p95_ms, 10.0);
+
+        let example = metrics.get_phase_timings_for("example.com");
+        assert_eq!(example.samples, 20);
+        assert_eq!(example.dns.p50_ms, 11.0);
+        assert_eq!(example.dns.p95_ms, 20.0);
+        assert_eq!(example.ttfb.p95_ms, 120.0);
+        assert_eq!(metrics.get_phase_timings_for("other.com").samples, 0);
+
+        metrics.reset();
+        assert_eq!(metrics.get_stats().phase_timings.samples, 0);
+    }
+
+    #[test]
+    fn test_phase_timings_bounded() {
+        let metrics = Metrics::new();
+        for _ in 0..MAX_TIMING_SAMPLES + 10 {
+            metrics.record_timing("example.com", timing(1, 1, None, 1));
+        }
+        assert_eq!(
+            metrics.get_stats().phase_timings.samples,
+            MAX_TIMING_SAMPLES
+        );
+    }
+
+    #[test]
+    fn test_record_memory() {
+        let metrics = Metrics::new();
+        assert_eq!(metrics.get_stats().memory_rss_bytes, None);
+        assert!(!metrics.to_prometheus().contains("browser_memory_rss_bytes"));
+
+        metrics.record_memory(300 * 1024 * 1024);
+        assert_eq!(
+            metrics.get_stats().memory_rss_bytes,
+            Some(300 * 1024 * 1024)
+        );
+        assert!(metrics
+            .to_prometheus()
+            .contains("\nbrowser_memory_rss_bytes 314572800\n"));
+
+        metrics.reset();
+        assert_eq!(metrics.get_stats().memory_rss_bytes, None);
+    }
+
+    #[test]
+    fn test_memory_pressure_fires_once_per_crossing() {
+        let metrics = Metrics::new();
+        let fired = Arc::new(Mutex::new(Vec::new()));
+        let fired_clone = fired.clone();
+        metrics.on_memory_pressure(1000, move |rss| fired_clone.lock().unwrap().push(rss));
+
+        metrics.record_memory(500);
+        metrics.record_memory(1200);
+        metrics.record_memory(1500);
+        assert_eq!(*fired.lock().unwrap(), vec![1200]);
+
+        // Re-armed once memory drops back
+        metrics.record_memory(800);
+        metrics.record_memory(1000);
+        assert_eq!(*fired.lock().unwrap(), vec![1200, 1000]);
+    }
+
+    #[test]
+    fn test_tree_rss_includes_descendants() {
+        let process = |pid, parent, rss_bytes| ProcessMemory {
+            pid,
+            parent,
+            rss_bytes,
+        };
+        let processes = [
+            process(1, 0, 10),
+            process(100, 1, 500),
+            // Sandbox launcher and the web process it started
+            process(101, 100, 1),
+            process(102, 101, 900),
+            process(103, 100, 200),
+            // Unrelated
+            process(200, 1, 7000),
+        ];
+        assert_eq!(tree_rss_bytes(100, &processes), Some(1601));
+        assert_eq!(tree_rss_bytes(102, &processes), Some(900));
+        assert_eq!(tree_rss_bytes(999, &processes), None);
+    }
+
+    #[test]
+    fn test_parse_vm_rss() {
+        let status = "Name:\tbrowser\nVmPeak:\t  300000 kB\nVmRSS:\t  123456 kB\nThreads:\t4\n";
+        assert_eq!(parse_vm_rss(status), Some(123456 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tbrowser\n"), None);
+        assert_eq!(parse_status_field("PPid:\t4242\n", "PPid:"), Some(4242));
+
+        if cfg!(target_os = "linux") {
+            let metrics = Metrics::new();
+            let rss = metrics.sample_memory().unwrap();
+            assert!(rss > 0);
+            assert_eq!(metrics.get_stats().memory_rss_bytes, Some(rss));
+        }
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label("plain.com"), "plain.com");
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+
+    #[test]
+    fn test_early_failures_age_out_by_count() {
+        let metrics = Metrics::new();
+
+        for _ in 0..10 {
+            metrics.record_navigation(false, Duration::ZERO);
+        }
+        assert_eq!(metrics.get_error_rate(), 1.0);
+
+        for _ in 0..ERROR_WINDOW_SIZE {
+            metrics.record_navigation(true, Duration::from_millis(100));
+        }
+        assert_eq!(metrics.get_error_rate(), 0.0);
+
+        // Lifetime totals still include the early failures
+        let stats = metrics.get_stats();
+        assert_eq!(stats.total_navigations, 110);
+        assert_eq!(stats.failed_navigations, 10);
+        assert_eq!(stats.error_rate, 0.0);
+    }
+
+    #[test]
+    fn test_early_failures_age_out_by_time() {
+        let metrics = Metrics::new();
+
+        for _ in 0..5 {
+            metrics.record_navigation(false, Duration::ZERO);
+        }
+        for _ in 0..5 {
+            metrics.record_navigation(true, Duration::from_millis(100));
+        }
+
+        let now = Instant::now();
+        assert!((metrics.error_rate_at(now) - 0.5).abs() < 0.01);
+
+        // Once the window has passed nothing is counted
+        let later = now + ERROR_WINDOW_DURATION;
+        assert_eq!(metrics.error_rate_at(later), 0.0);
+    }
+
+    #[test]
+    fn test_error_rate_window_is_bounded() {
+        let metrics = Metrics::new();
+
+        for _ in 0..(ERROR_WINDOW_SIZE * 3) {
+            metrics.record_navigation(true, Duration::from_millis(1));
+        }
+
+        assert_eq!(
+            metrics.recent_outcomes.lock().unwrap().len(),
+            ERROR_WINDOW_SIZE
+        );
+    }
 }
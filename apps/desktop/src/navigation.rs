@@ -8,7 +8,12 @@
 //! # Responsibilities
 //!
 //! - URL validation and loading
-//! - History persistence (database)
+//! - Host blocklist enforcement
+//! - Cancelling navigations superseded by a newer one
+//! - HTTPS-first scheme choice for typed addresses
+//! - History persistence (database), skipped in private mode: page
+//!   navigations are debounced so redirect chains save one visit, and
+//!   `HistoryDebouncer` is the only writer
 //! - Back/forward navigation via JavaScript
 //! - Page reload and stop
 //! - Metrics tracking for operational excellence
@@ -18,13 +23,205 @@
 
 use crate::error::{BrowserError, Result};
 use crate::metrics::Metrics;
-use std::path::PathBuf;
+use shared::{Blocklist, TabId};
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use storage::Database;
 use tracing::{info, warn};
+use url::Url;
 use wry::WebView;
 
+/// Settings key holding the blocklist as a JSON array of host patterns
+pub const BLOCKLIST_SETTING: &str = "blocklist";
+
+/// Settings key for HTTPS-first (`"true"` or `"false"`, on when unset)
+pub const HTTPS_FIRST_SETTING: &str = "https_first";
+
+/// How long to wait for a site to answer over HTTPS before warning that
+/// it didn't
+const HTTPS_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Repeat visits to the latest history entry within this long are
+/// collapsed into it (reloads, double clicks)
+pub const DUPLICATE_VISIT_WINDOW: Duration = Duration::from_secs(2);
+
+/// How long a tab must stay on a URL before it's saved to history, so a
+/// redirect chain records only where it ends up
+pub const HISTORY_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Check whether a URL answers; run off the UI thread by `check_https`
+type HttpsProbe = Arc<dyn Fn(String) -> Pin<Box<dyn Future<Output = bool> + Send>> + Send + Sync>;
+
+/// URL to load for typed input, and where to go if it doesn't answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemeChoice {
+    /// URL to try first
+    pub url: String,
+    /// Plain-HTTP URL to use if `url` is unreachable
+    pub fallback: Option<String>,
+}
+
+/// Pick the scheme for an address as typed
+///
+/// - An explicit scheme (`http://`, `https://`, `about:`, ...) is kept
+/// - Local hosts (localhost, `.local`, single-label names, loopback and
+///   private IPs) use http, since they rarely have certificates
+/// - Other bare addresses use https with an http fallback when
+///   `https_first` is on, plain http when it's off
+///
+/// Input that isn't a host (e.g. contains spaces) is returned unchanged.
+pub fn choose_scheme(input: &str, https_first: bool) -> SchemeChoice {
+    let input = input.trim();
+    let as_is = || SchemeChoice {
+        url: input.to_string(),
+        fallback: None,
+    };
+
+    if has_explicit_scheme(input) {
+        return as_is();
+    }
+    let Ok(http) = Url::parse(&format!("http://{}", input)) else {
+        return as_is();
+    };
+    if input.contains(char::is_whitespace) || http.host().is_none() {
+        return as_is();
+    }
+
+    if !https_first || is_local_host(&http) {
+        return SchemeChoice {
+            url: http.into(),
+            fallback: None,
+        };
+    }
+
+    let mut https = http.clone();
+    // http -> https is always a valid scheme change
+    let _ = https.set_scheme("https");
+    SchemeChoice {
+        url: https.into(),
+        fallback: Some(http.into()),
+    }
+}
+
+/// Whether the input names its scheme
+///
+/// `localhost:3000` parses as a URL with scheme `localhost`, so only
+/// `://` and the known opaque schemes count.
+fn has_explicit_scheme(input: &str) -> bool {
+    const OPAQUE_SCHEMES: [&str; 6] = [
+        "about:",
+        "data:",
+        "javascript:",
+        "blob:",
+        "mailto:",
+        "view-source:",
+    ];
+    let lower = input.to_ascii_lowercase();
+    lower.contains("://")
+        || OPAQUE_SCHEMES
+            .iter()
+            .any(|scheme| lower.starts_with(scheme))
+}
+
+/// Hosts that are served over plain HTTP in practice
+fn is_local_host(url: &Url) -> bool {
+    match url.host() {
+        Some(url::Host::Domain(domain)) => {
+            domain == "localhost"
+                || domain.ends_with(".localhost")
+                || domain.ends_with(".local")
+                || !domain.contains('.')
+        }
+        Some(url::Host::Ipv4(ip)) => is_local_ip(IpAddr::V4(ip)),
+        Some(url::Host::Ipv6(ip)) => is_local_ip(IpAddr::V6(ip)),
+        None => false,
+    }
+}
+
+fn is_local_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified()
+        }
+        IpAddr::V6(ip) => {
+            let first = ip.segments()[0];
+            ip.is_loopback()
+                || ip.is_unspecified()
+                // Unique local (fc00::/7) and link-local (fe80::/10)
+                || first & 0xfe00 == 0xfc00
+                || first & 0xffc0 == 0xfe80
+        }
+    }
+}
+
+/// Ask the site whether it answers over HTTPS
+///
+/// If the check itself can't run, HTTPS is assumed and the WebView gets
+/// to try.
+async fn probe_https(url: String) -> bool {
+    let Ok(url) = Url::parse(&url) else {
+        return false;
+    };
+    match network::HttpClient::new() {
+        Ok(client) => client.probe(url, HTTPS_PROBE_TIMEOUT).await,
+        Err(e) => {
+            warn!("HTTPS probe unavailable: {}", e);
+            true
+        }
+    }
+}
+
+/// Whether an HTTPS-first navigation's https URL answered
+///
+/// The browser never falls back to http by itself: a failed check may be
+/// a timeout, a captive portal or someone dropping port 443, so the user
+/// is told and can choose the http URL.
+#[derive(Debug, Clone)]
+pub struct HttpsCheck {
+    /// The https URL that was loaded
+    pub url: String,
+    /// Plain-HTTP URL the user can enter instead
+    pub fallback: String,
+    /// Whether the site answered over HTTPS
+    pub reachable: bool,
+    /// The checked navigation's token
+    token: NavigationToken,
+}
+
+impl HttpsCheck {
+    /// Message telling the user the site didn't answer over HTTPS
+    pub fn warning(&self) -> Option<String> {
+        if self.reachable {
+            return None;
+        }
+        let host = domain_of(&self.url).unwrap_or_else(|| self.url.clone());
+        Some(format!(
+            "{} didn't answer over HTTPS. To load it without encryption, enter {}",
+            host, self.fallback
+        ))
+    }
+}
+
+/// Handle on one navigation, cancelled once a newer one starts
+#[derive(Debug, Clone)]
+pub struct NavigationToken {
+    generation: u64,
+    latest: Arc<AtomicU64>,
+}
+
+impl NavigationToken {
+    /// Whether a newer navigation has started since this one
+    pub fn is_cancelled(&self) -> bool {
+        self.latest.load(Ordering::SeqCst) != self.generation
+    }
+}
+
 /// Navigation result returned after URL load
 #[derive(Debug, Clone)]
 pub struct NavigationResult {
@@ -36,13 +233,20 @@ pub struct NavigationResult {
 
     /// Whether to add to history
     pub should_add_history: bool,
+
+    /// For an HTTPS-first URL, the http URL to offer if https doesn't
+    /// answer (see `NavigationService::check_https`)
+    pub http_fallback: Option<String>,
+
+    /// Cancelled once a newer navigation starts
+    pub token: NavigationToken,
 }
 
 /// Navigation Service following AWS service patterns
 ///
 /// Manages URL navigation with:
 /// - Metrics tracking (DORA: navigation success rate)
-/// - History persistence
+/// - History settings (private mode, duplicate window) for `save_visits`
 /// - Error handling with COE reports
 pub struct NavigationService {
     /// Database path for history
@@ -51,8 +255,26 @@ pub struct NavigationService {
     /// Current URL (if any)
     current_url: Option<String>,
 
+    /// Generation of the latest navigation, see `NavigationToken`
+    generation: Arc<AtomicU64>,
+
     /// Metrics collector
     metrics: Arc<Metrics>,
+
+    /// Blocked host patterns
+    blocklist: Blocklist,
+
+    /// Private mode: navigations aren't saved to history
+    private: bool,
+
+    /// Try https before http for addresses typed without a scheme
+    https_first: bool,
+
+    /// Reachability check for the HTTPS-first fallback
+    https_probe: HttpsProbe,
+
+    /// Repeat visits within this window aren't added to history
+    duplicate_window: Duration,
 }
 
 impl NavigationService {
@@ -65,7 +287,187 @@ impl NavigationService {
         Self {
             db_path,
             current_url: None,
+            generation: Arc::new(AtomicU64::new(0)),
             metrics,
+            blocklist: Blocklist::new(),
+            private: false,
+            https_first: true,
+            https_probe: Arc::new(|url| Box::pin(probe_https(url))),
+            duplicate_window: DUPLICATE_VISIT_WINDOW,
+        }
+    }
+
+    /// Enable or disable private mode
+    pub fn set_private(&mut self, private: bool) {
+        self.private = private;
+    }
+
+    /// Whether navigations are kept out of history
+    pub fn is_private(&self) -> bool {
+        self.private
+    }
+
+    /// Set how soon a repeat visit to the latest history entry is
+    /// collapsed into it; zero records every visit
+    pub fn set_duplicate_window(&mut self, window: Duration) {
+        self.duplicate_window = window;
+    }
+
+    /// Window within which repeat visits aren't added to history
+    pub fn duplicate_window(&self) -> Duration {
+        self.duplicate_window
+    }
+
+    /// Replace the host blocklist
+    pub fn set_blocklist(&mut self, blocklist: Blocklist) {
+        self.blocklist = blocklist;
+    }
+
+    /// Load the host blocklist from the `blocklist` setting
+    ///
+    /// The setting is a JSON array of host patterns, e.g.
+    /// `["ads.example.com", "*.tracker.net"]`. A missing setting clears
+    /// the blocklist.
+    ///
+    /// # Errors
+    /// Returns `BrowserError::Database` if the setting can't be read, or
+    /// `BrowserError::ConfigError` if it isn't a JSON array of strings
+    pub fn load_blocklist(&mut self) -> Result<()> {
+        let db = Database::open_readonly(&self.db_path)
+            .map_err(|e| BrowserError::Database(e.to_string()))?;
+        let setting = db
+            .get_setting(BLOCKLIST_SETTING)
+            .map_err(|e| BrowserError::Database(e.to_string()))?;
+
+        let patterns: Vec<String> = match setting {
+            Some(json) => serde_json::from_str(&json).map_err(|e| {
+                BrowserError::ConfigError(format!("Invalid {} setting: {}", BLOCKLIST_SETTING, e))
+            })?,
+            None => Vec::new(),
+        };
+
+        self.blocklist = Blocklist::from_patterns(&patterns);
+        info!("Loaded blocklist with {} patterns", self.blocklist.len());
+        Ok(())
+    }
+
+    /// Enable or disable HTTPS-first for typed addresses (on by default)
+    pub fn set_https_first(&mut self, https_first: bool) {
+        self.https_first = https_first;
+    }
+
+    /// Whether bare addresses try https first
+    pub fn is_https_first(&self) -> bool {
+        self.https_first
+    }
+
+    /// Replace the check deciding whether a site answers over HTTPS
+    ///
+    /// By default a HEAD request with a 3 second timeout.
+    pub fn set_https_probe<F, Fut>(&mut self, probe: F)
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = bool> + Send + 'static,
+    {
+        self.https_probe = Arc::new(move |url| Box::pin(probe(url)));
+    }
+
+    /// Check whether an HTTPS-first navigation's https URL answers
+    ///
+    /// Does nothing for navigations without an `http_fallback`. The
+    /// probe runs on a worker thread, so the UI never waits on it;
+    /// `done` is called there with the result, which goes back to the
+    /// UI thread for `finish_https_check`. Nothing is downgraded.
+    pub fn check_https<F>(&self, result: &NavigationResult, done: F)
+    where
+        F: FnOnce(HttpsCheck) + Send + 'static,
+    {
+        let Some(fallback) = result.http_fallback.clone() else {
+            return;
+        };
+        let probe = Arc::clone(&self.https_probe);
+        let url = result.url.clone();
+        let token = result.token.clone();
+
+        let spawned = std::thread::Builder::new()
+            .name("https-probe".to_string())
+            .spawn(move || {
+                let reachable = match tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                {
+                    Ok(runtime) => runtime.block_on(probe(url.clone())),
+                    Err(e) => {
+                        warn!("HTTPS probe unavailable: {}", e);
+                        true
+                    }
+                };
+                if !reachable {
+                    info!("No HTTPS response from {}", url);
+                }
+                done(HttpsCheck {
+                    url,
+                    fallback,
+                    reachable,
+                    token,
+                });
+            });
+        if let Err(e) = spawned {
+            warn!("HTTPS probe unavailable: {}", e);
+        }
+    }
+
+    /// Warning to show for a finished `check_https`, if any
+    ///
+    /// # Errors
+    /// Returns `BrowserError::NavigationCancelled` if a newer navigation
+    /// has started since, making the result stale
+    pub fn finish_https_check(&self, check: &HttpsCheck) -> Result<Option<String>> {
+        if check.token.is_cancelled() {
+            info!("Dropping HTTPS check for superseded {}", check.url);
+            return Err(BrowserError::NavigationCancelled {
+                url: check.url.clone(),
+            });
+        }
+        Ok(check.warning())
+    }
+
+    /// Load HTTPS-first from the `https_first` setting
+    ///
+    /// A missing setting turns it on.
+    ///
+    /// # Errors
+    /// Returns `BrowserError::Database` if the setting can't be read, or
+    /// `BrowserError::ConfigError` if it isn't `true` or `false`
+    pub fn load_https_first(&mut self) -> Result<()> {
+        let db = Database::open_readonly(&self.db_path)
+            .map_err(|e| BrowserError::Database(e.to_string()))?;
+        let setting = db
+            .get_setting(HTTPS_FIRST_SETTING)
+            .map_err(|e| BrowserError::Database(e.to_string()))?;
+
+        self.https_first = match setting.as_deref().map(str::trim) {
+            None | Some("true") => true,
+            Some("false") => false,
+            Some(other) => {
+                return Err(BrowserError::ConfigError(format!(
+                    "Invalid {} setting: {:?}",
+                    HTTPS_FIRST_SETTING, other
+                )))
+            }
+        };
+        info!(
+            "HTTPS-first {}",
+            if self.https_first { "on" } else { "off" }
+        );
+        Ok(())
+    }
+
+    /// Start a navigation, cancelling any still in progress
+    pub fn begin_navigation(&self) -> NavigationToken {
+        NavigationToken {
+            generation: self.generation.fetch_add(1, Ordering::SeqCst) + 1,
+            latest: Arc::clone(&self.generation),
         }
     }
 
@@ -79,35 +481,80 @@ impl NavigationService {
     ///
     /// # Metrics
     /// Records navigation attempt (success/failure) with duration
+    ///
+    /// Addresses typed without a scheme get one (see `choose_scheme`).
+    /// Otherwise the URL is loaded as typed, fragment included; only the
+    /// history and bookmark keys are normalized, by storage.
+    ///
+    /// An HTTPS-first URL is returned without probing it, with its
+    /// `http_fallback`; pass the result to `check_https` to find out
+    /// whether to warn the user.
+    ///
+    /// Starting a navigation cancels the previous one's token, so its
+    /// background check is dropped.
+    ///
+    /// # Errors
+    /// Returns `BrowserError::NavigationBlocked` if the host is blocklisted
     pub fn navigate(&mut self, url: &str) -> Result<NavigationResult> {
         let start = Instant::now();
+        let token = self.begin_navigation();
+        let choice = choose_scheme(url, self.https_first);
+        let mut result = self.commit_navigation(&choice.url, start, token)?;
+        result.http_fallback = choice.fallback;
+        Ok(result)
+    }
+
+    /// Record a navigation to `url`
+    ///
+    /// # Errors
+    /// Returns `BrowserError::NavigationBlocked` if the host is blocklisted,
+    /// or `BrowserError::NavigationCancelled` if `token` was superseded, in
+    /// which case no metric is recorded
+    fn commit_navigation(
+        &mut self,
+        url: &str,
+        start: Instant,
+        token: NavigationToken,
+    ) -> Result<NavigationResult> {
+        if token.is_cancelled() {
+            info!("Dropping superseded navigation to {}", url);
+            return Err(BrowserError::NavigationCancelled {
+                url: url.to_string(),
+            });
+        }
+
+        if let Ok(parsed) = Url::parse(url) {
+            if self.blocklist.is_blocked(&parsed) {
+                warn!("Blocked navigation to: {}", url);
+                return Err(BrowserError::NavigationBlocked {
+                    url: url.to_string(),
+                    host: parsed.host_str().unwrap_or_default().to_string(),
+                });
+            }
+        }
 
         info!("Navigating to: {}", url);
 
         // Extract title from URL (domain as fallback)
         let title = url.split('/').nth(2).unwrap_or("New Tab").to_string();
 
-        // Save to history
-        match self.save_to_history(url, Some(&title)) {
-            Ok(_) => {
-                let duration = start.elapsed();
-                self.metrics.record_navigation(true, duration);
-                info!("✅ Navigation successful: {} ({:?})", url, duration);
-            }
-            Err(e) => {
-                self.metrics
-                    .record_error(&format!("History save failed: {}", e));
-                warn!("Failed to save history: {}", e);
-                // Continue navigation even if history save fails
-            }
+        // History is saved once the content WebView settles on a page,
+        // see `HistoryDebouncer`
+        let duration = start.elapsed();
+        match domain_of(url) {
+            Some(domain) => self.metrics.record_navigation_for(&domain, true, duration),
+            None => self.metrics.record_navigation(true, duration),
         }
+        info!("✅ Navigation successful: {} ({:?})", url, duration);
 
         self.current_url = Some(url.to_string());
 
         Ok(NavigationResult {
             url: url.to_string(),
             title,
-            should_add_history: true,
+            should_add_history: !self.private,
+            http_fallback: None,
+            token,
         })
     }
 
@@ -176,30 +623,120 @@ impl NavigationService {
         self.current_url.as_deref()
     }
 
-    /// Save URL to history database
-    ///
-    /// # Arguments
-    /// * `url` - URL to save
-    /// * `title` - Page title (optional)
-    ///
-    /// # Errors
-    /// Returns `BrowserError::Database` if save fails
-    fn save_to_history(&self, url: &str, title: Option<&str>) -> Result<()> {
-        let db = Database::new(&self.db_path).map_err(|e| BrowserError::Database(e.to_string()))?;
+    /// Get reference to metrics
+    pub fn metrics(&self) -> &Arc<Metrics> {
+        &self.metrics
+    }
+}
 
-        db.add_history(url, title)
-            .map_err(|e| BrowserError::Database(e.to_string()))?;
+/// A page visit waiting to be saved to history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingVisit {
+    pub url: String,
+    pub title: String,
+    /// When the tab navigated here
+    pub at: Instant,
+}
 
-        info!("✅ Saved to history: {} - {:?}", url, title);
-        Ok(())
+/// Holds each tab's latest navigation until it settles
+///
+/// Client-side redirects fire the navigation handler once per hop. Each
+/// navigation replaces the tab's pending visit, and a visit is only
+/// handed out once the tab has stayed on it for the debounce delay.
+#[derive(Debug)]
+pub struct HistoryDebouncer {
+    delay: Duration,
+    pending: HashMap<TabId, PendingVisit>,
+}
+
+impl HistoryDebouncer {
+    /// Create a debouncer that waits `delay` (see `HISTORY_DEBOUNCE`)
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            pending: HashMap::new(),
+        }
     }
 
-    /// Get reference to metrics
-    pub fn metrics(&self) -> &Arc<Metrics> {
-        &self.metrics
+    /// Note that `tab` navigated to `url` at `at`, superseding any visit
+    /// still pending for it
+    pub fn record(&mut self, tab: TabId, url: &str, title: &str, at: Instant) {
+        self.pending.insert(
+            tab,
+            PendingVisit {
+                url: url.to_string(),
+                title: title.to_string(),
+                at,
+            },
+        );
+    }
+
+    /// Remove and return the visits that have settled by `now`, oldest
+    /// first
+    pub fn take_settled(&mut self, now: Instant) -> Vec<PendingVisit> {
+        let settled: Vec<TabId> = self
+            .pending
+            .iter()
+            .filter(|(_, visit)| now.saturating_duration_since(visit.at) >= self.delay)
+            .map(|(tab, _)| *tab)
+            .collect();
+        let mut visits: Vec<PendingVisit> = settled
+            .into_iter()
+            .filter_map(|tab| self.pending.remove(&tab))
+            .collect();
+        visits.sort_by_key(|visit| visit.at);
+        visits
+    }
+
+    /// Remove and return every pending visit, settled or not, oldest
+    /// first; for shutdown
+    pub fn take_all(&mut self) -> Vec<PendingVisit> {
+        let mut visits: Vec<PendingVisit> = self.pending.drain().map(|(_, visit)| visit).collect();
+        visits.sort_by_key(|visit| visit.at);
+        visits
+    }
+
+    /// When the next pending visit settles, if any are pending
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending
+            .values()
+            .map(|visit| visit.at + self.delay)
+            .min()
     }
 }
 
+/// Save debounced visits to history, collapsing repeats of the latest
+/// entry within `duplicate_window`
+///
+/// # Errors
+/// Returns `BrowserError::Database` if the database can't be opened or
+/// written; visits after the failing one aren't saved
+pub fn save_visits(
+    db_path: &Path,
+    visits: &[PendingVisit],
+    duplicate_window: Duration,
+) -> Result<()> {
+    if visits.is_empty() {
+        return Ok(());
+    }
+    let db = Database::new(db_path).map_err(|e| BrowserError::Database(e.to_string()))?;
+
+    for visit in visits {
+        let saved = db
+            .add_history_collapsing(&visit.url, Some(&visit.title), duplicate_window)
+            .map_err(|e| BrowserError::Database(e.to_string()))?;
+        if saved.is_some() {
+            info!("✅ Saved to history: {} - {}", visit.url, visit.title);
+        }
+    }
+    Ok(())
+}
+
+/// Extract the host of a URL for per-domain metrics
+pub fn domain_of(url: &str) -> Option<String> {
+    Url::parse(url).ok()?.host_str().map(str::to_string)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -244,23 +781,128 @@ mod tests {
     }
 
     #[test]
-    fn test_history_persistence() {
+    fn test_navigate_leaves_history_to_debouncer() {
         let temp_file = NamedTempFile::new().unwrap();
         let temp_path = temp_file.path().to_path_buf();
+        let mut service = NavigationService::new(temp_path.clone(), Metrics::new());
 
-        {
-            let metrics = Metrics::new();
-            let mut service = NavigationService::new(temp_path.clone(), metrics);
-            service.navigate("https://example.com").unwrap();
-            service.navigate("https://github.com").unwrap();
-        }
+        // A typed URL is saved once its page settles, not when typed, so
+        // a redirect doesn't add a second visit
+        service.navigate("https://example.com").unwrap();
+        service.navigate("https://github.com").unwrap();
 
-        // Verify history persisted
         let db = Database::new(&temp_path).unwrap();
-        let history = db.get_recent_history(10).unwrap();
-        assert_eq!(history.len(), 2);
-        assert_eq!(history[0].url, "https://github.com");
-        assert_eq!(history[1].url, "https://example.com");
+        assert!(db.get_recent_history(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_visits_collapsed() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut service = NavigationService::new(temp_file.path().to_path_buf(), Metrics::new());
+        let db = Database::new(temp_file.path()).unwrap();
+        let save = |service: &NavigationService, url: &str| {
+            let visit = PendingVisit {
+                url: url.to_string(),
+                title: "Example".to_string(),
+                at: Instant::now(),
+            };
+            save_visits(temp_file.path(), &[visit], service.duplicate_window()).unwrap();
+        };
+
+        save(&service, "https://example.com");
+        save(&service, "https://example.com/");
+        assert_eq!(db.get_recent_history(10).unwrap().len(), 1);
+
+        // Only the latest entry counts as a duplicate
+        save(&service, "https://github.com");
+        save(&service, "https://example.com");
+        assert_eq!(db.get_recent_history(10).unwrap().len(), 3);
+
+        // Once the window has passed, the same URL is a new visit
+        service.set_duplicate_window(Duration::from_millis(50));
+        std::thread::sleep(Duration::from_millis(100));
+        save(&service, "https://example.com");
+        assert_eq!(db.get_recent_history(10).unwrap().len(), 4);
+
+        service.set_duplicate_window(Duration::ZERO);
+        save(&service, "https://example.com");
+        assert_eq!(db.get_recent_history(10).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_redirect_chain_saves_one_visit() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut debouncer = HistoryDebouncer::new(HISTORY_DEBOUNCE);
+        let tab = TabId(1);
+        let start = Instant::now();
+
+        // Three hops, each within the debounce window of the last
+        debouncer.record(tab, "https://example.com/", "example.com", start);
+        let hop = start + Duration::from_millis(150);
+        debouncer.record(tab, "https://www.example.com/", "www.example.com", hop);
+        let last = start + Duration::from_millis(300);
+        debouncer.record(tab, "https://www.example.com/home", "www.example.com", last);
+
+        assert!(debouncer
+            .take_settled(last + Duration::from_millis(100))
+            .is_empty());
+        assert_eq!(debouncer.next_deadline(), Some(last + HISTORY_DEBOUNCE));
+
+        let settled = debouncer.take_settled(last + HISTORY_DEBOUNCE);
+        save_visits(temp_file.path(), &settled, DUPLICATE_VISIT_WINDOW).unwrap();
+
+        let history = Database::new(temp_file.path())
+            .unwrap()
+            .get_recent_history(10)
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].url, "https://www.example.com/home");
+        assert_eq!(debouncer.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_debounce_is_per_tab() {
+        let mut debouncer = HistoryDebouncer::new(HISTORY_DEBOUNCE);
+        let start = Instant::now();
+
+        debouncer.record(TabId(1), "https://example.com/", "example.com", start);
+        let later = start + Duration::from_millis(400);
+        debouncer.record(TabId(2), "https://github.com/", "github.com", later);
+
+        // Another tab navigating doesn't hold back the first
+        let settled = debouncer.take_settled(start + HISTORY_DEBOUNCE);
+        assert_eq!(settled.len(), 1);
+        assert_eq!(settled[0].url, "https://example.com/");
+
+        // Shutdown takes visits that haven't settled yet
+        let remaining = debouncer.take_all();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].url, "https://github.com/");
+        assert!(debouncer.take_all().is_empty());
+    }
+
+    #[test]
+    fn test_private_mode_skips_history() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let metrics = Metrics::new();
+        let mut service = NavigationService::new(temp_file.path().to_path_buf(), metrics.clone());
+
+        service.set_private(true);
+        assert!(service.is_private());
+        let result = service.navigate("https://secret.example.com").unwrap();
+        assert!(!result.should_add_history);
+        assert_eq!(service.current_url(), Some("https://secret.example.com"));
+
+        // Navigation still counts
+        assert_eq!(metrics.get_stats().total_navigations, 1);
+
+        service.set_private(false);
+        assert!(
+            service
+                .navigate("https://example.com")
+                .unwrap()
+                .should_add_history
+        );
     }
 
     #[test]
@@ -275,6 +917,299 @@ mod tests {
         let stats = metrics.get_stats();
         assert_eq!(stats.total_navigations, 2);
         assert_eq!(stats.failed_navigations, 0);
+
+        let domains = metrics.get_domain_stats();
+        assert_eq!(domains.len(), 2);
+        assert!(domains.iter().any(|(name, _)| name == "github.com"));
+    }
+
+    #[test]
+    fn test_navigate_loads_url_as_typed() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let metrics = Metrics::new();
+        let mut service = NavigationService::new(temp_file.path().to_path_buf(), metrics);
+
+        // The fragment survives so the page can scroll to it
+        let typed = "HTTPS://Example.com:443/Docs?q=1#intro";
+        let result = service.navigate(typed).unwrap();
+        assert_eq!(result.url, typed);
+        assert_eq!(service.current_url(), Some(typed));
+    }
+
+    #[test]
+    fn test_navigate_blocked_host() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let metrics = Metrics::new();
+        let mut service = NavigationService::new(temp_file.path().to_path_buf(), metrics);
+        service.set_blocklist(Blocklist::from_patterns(["*.example.com"]));
+
+        let err = service
+            .navigate("https://www.example.com/page")
+            .unwrap_err();
+        match err {
+            BrowserError::NavigationBlocked { url, host } => {
+                assert_eq!(url, "https://www.example.com/page");
+                assert_eq!(host, "www.example.com");
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert!(service.current_url().is_none());
+
+        // Bare domain isn't covered by the wildcard
+        assert!(service.navigate("https://example.com").is_ok());
+    }
+
+    #[test]
+    fn test_load_blocklist_from_settings() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+        db.set_setting(BLOCKLIST_SETTING, r#"["blocked.com", "*.ads.net"]"#)
+            .unwrap();
+
+        let metrics = Metrics::new();
+        let mut service = NavigationService::new(temp_file.path().to_path_buf(), metrics);
+        service.load_blocklist().unwrap();
+
+        assert!(service.navigate("https://blocked.com").is_err());
+        assert!(service.navigate("https://cdn.ads.net/x.js").is_err());
+        assert!(service.navigate("https://github.com").is_ok());
+
+        db.set_setting(BLOCKLIST_SETTING, "not json").unwrap();
+        assert!(matches!(
+            service.load_blocklist(),
+            Err(BrowserError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_choose_scheme_bare_domain() {
+        assert_eq!(
+            choose_scheme("example.com", true),
+            SchemeChoice {
+                url: "https://example.com/".to_string(),
+                fallback: Some("http://example.com/".to_string()),
+            }
+        );
+        assert_eq!(
+            choose_scheme("  news.example.co.uk/path?q=1 ", true),
+            SchemeChoice {
+                url: "https://news.example.co.uk/path?q=1".to_string(),
+                fallback: Some("http://news.example.co.uk/path?q=1".to_string()),
+            }
+        );
+
+        // Setting off: plain http
+        assert_eq!(
+            choose_scheme("example.com", false),
+            SchemeChoice {
+                url: "http://example.com/".to_string(),
+                fallback: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_choose_scheme_explicit() {
+        for input in [
+            "http://example.com",
+            "https://example.com",
+            "HTTP://Example.com/",
+            "about:blank",
+            "file:///tmp/index.html",
+        ] {
+            assert_eq!(
+                choose_scheme(input, true),
+                SchemeChoice {
+                    url: input.to_string(),
+                    fallback: None,
+                },
+                "{:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_choose_scheme_localhost() {
+        for (input, expected) in [
+            ("localhost", "http://localhost/"),
+            ("localhost:3000", "http://localhost:3000/"),
+            ("app.localhost:8080/api", "http://app.localhost:8080/api"),
+            ("printer.local", "http://printer.local/"),
+            ("router/admin", "http://router/admin"),
+            ("127.0.0.1:8080", "http://127.0.0.1:8080/"),
+            ("192.168.1.1", "http://192.168.1.1/"),
+            ("[::1]:4000", "http://[::1]:4000/"),
+        ] {
+            assert_eq!(
+                choose_scheme(input, true),
+                SchemeChoice {
+                    url: expected.to_string(),
+                    fallback: None,
+                },
+                "{:?}",
+                input
+            );
+        }
+
+        // Public IPs are tried over https like any other host
+        assert_eq!(
+            choose_scheme("93.184.216.34", true).fallback.as_deref(),
+            Some("http://93.184.216.34/")
+        );
+    }
+
+    #[test]
+    fn test_navigate_https_first_fallback() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let metrics = Metrics::new();
+        let mut service = NavigationService::new(temp_file.path().to_path_buf(), metrics);
+
+        // Navigating doesn't wait for a probe
+        service.set_https_probe(|url| async move { panic!("unexpected probe of {}", url) });
+        let result = service.navigate("legacy.example.com").unwrap();
+        assert_eq!(result.url, "https://legacy.example.com/");
+        assert_eq!(
+            result.http_fallback.as_deref(),
+            Some("http://legacy.example.com/")
+        );
+
+        // Explicit and local addresses have no fallback to offer
+        let result = service.navigate("http://example.com").unwrap();
+        assert_eq!(result.url, "http://example.com");
+        assert_eq!(result.http_fallback, None);
+        let result = service.navigate("localhost:3000").unwrap();
+        assert_eq!(result.url, "http://localhost:3000/");
+        assert_eq!(result.http_fallback, None);
+    }
+
+    #[test]
+    fn test_check_https_warns_instead_of_downgrading() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut service = NavigationService::new(temp_file.path().to_path_buf(), Metrics::new());
+        service.set_https_probe(|url| async move { url != "https://legacy.example.com/" });
+
+        let mut check = |input: &str| {
+            let result = service.navigate(input).unwrap();
+            let (tx, rx) = std::sync::mpsc::channel();
+            service.check_https(&result, move |check| tx.send(check).unwrap());
+            let check = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+            (check.reachable, service.finish_https_check(&check).unwrap())
+        };
+
+        assert_eq!(check("example.com"), (true, None));
+
+        let (reachable, warning) = check("legacy.example.com");
+        assert!(!reachable);
+        let warning = warning.unwrap();
+        assert!(warning.starts_with("legacy.example.com didn't answer over HTTPS"));
+        assert!(warning.ends_with("http://legacy.example.com/"));
+    }
+
+    #[test]
+    fn test_load_https_first_from_settings() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+        let metrics = Metrics::new();
+        let mut service = NavigationService::new(temp_file.path().to_path_buf(), metrics);
+
+        service.load_https_first().unwrap();
+        assert!(service.is_https_first());
+
+        db.set_setting(HTTPS_FIRST_SETTING, "false").unwrap();
+        service.load_https_first().unwrap();
+        assert!(!service.is_https_first());
+        service.set_https_probe(|url| async move { panic!("unexpected probe of {}", url) });
+        assert_eq!(
+            service.navigate("example.com").unwrap().url,
+            "http://example.com/"
+        );
+
+        db.set_setting(HTTPS_FIRST_SETTING, "maybe").unwrap();
+        assert!(matches!(
+            service.load_https_first(),
+            Err(BrowserError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_superseded_navigation_cancelled() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let metrics = Metrics::new();
+        let mut service = NavigationService::new(temp_file.path().to_path_buf(), metrics.clone());
+
+        // Neither site answers over HTTPS; the first is slow to fail
+        service.set_https_probe(|url| async move {
+            if url.contains("slow") {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            false
+        });
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        let slow = service.navigate("slow.example.com").unwrap();
+        let slow_tx = tx.clone();
+        service.check_https(&slow, move |check| slow_tx.send(check).unwrap());
+        let fast = service.navigate("fast.example.com").unwrap();
+        service.check_https(&fast, move |check| tx.send(check).unwrap());
+        assert!(slow.token.is_cancelled());
+        assert!(!fast.token.is_cancelled());
+
+        // The fast check finishes first; the slow one arrives stale and
+        // is dropped rather than warning about a page no longer shown
+        let first = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(first.url, "https://fast.example.com/");
+        assert!(service.finish_https_check(&first).unwrap().is_some());
+
+        let second = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(
+            service.finish_https_check(&second),
+            Err(BrowserError::NavigationCancelled { ref url }) if url == "https://slow.example.com/"
+        ));
+        assert_eq!(service.current_url(), Some("https://fast.example.com/"));
+        assert_eq!(metrics.get_stats().failed_navigations, 0);
+    }
+
+    #[test]
+    fn test_superseded_commit_records_no_metric() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let metrics = Metrics::new();
+        let mut service = NavigationService::new(temp_file.path().to_path_buf(), metrics.clone());
+
+        let stale = service.begin_navigation();
+        service.begin_navigation();
+        let result = service.commit_navigation("https://example.com/", Instant::now(), stale);
+        assert!(matches!(
+            result,
+            Err(BrowserError::NavigationCancelled { ref url }) if url == "https://example.com/"
+        ));
+        assert!(service.current_url().is_none());
+        assert_eq!(metrics.get_stats().total_navigations, 0);
+    }
+
+    #[test]
+    fn test_navigation_token() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let service = NavigationService::new(temp_file.path().to_path_buf(), Metrics::new());
+
+        let first = service.begin_navigation();
+        assert!(!first.is_cancelled());
+        let second = service.begin_navigation();
+        assert!(first.is_cancelled());
+        assert!(!second.is_cancelled());
+    }
+
+    #[test]
+    fn test_domain_of() {
+        assert_eq!(
+            domain_of("https://www.github.com/user/repo"),
+            Some("www.github.com".to_string())
+        );
+        assert_eq!(
+            domain_of("http://localhost:3000"),
+            Some("localhost".to_string())
+        );
+        assert_eq!(domain_of("not a url"), None);
     }
 
     // Note: go_back/forward/reload/stop tests require WebView instance
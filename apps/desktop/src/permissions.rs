@@ -0,0 +1,229 @@
+//! Permission decisions remembered per origin
+//!
+//! Wraps the renderer's permission handler so a page is only asked about once
+//! per origin and capability. Decisions are stored in the settings table under
+//! `permission.<kind>.<origin>` with the value `allow` or `deny`.
+
+use crate::error::{BrowserError, Result};
+use renderer::{PermissionDecision, PermissionKind, PermissionRequest};
+use std::path::PathBuf;
+use storage::Database;
+use tracing::warn;
+
+/// Settings key prefix for stored permission decisions
+pub const PERMISSION_SETTING_PREFIX: &str = "permission";
+
+/// Settings key for a request's origin and capability
+pub fn permission_key(request: &PermissionRequest) -> String {
+    format!(
+        "{}.{}.{}",
+        PERMISSION_SETTING_PREFIX,
+        request.kind.as_str(),
+        request.origin
+    )
+}
+
+/// Per-origin permission decisions backed by the settings table
+pub struct PermissionStore {
+    /// Database path for settings
+    db_path: PathBuf,
+}
+
+impl PermissionStore {
+    /// Create a store using the database at `db_path`
+    pub fn new(db_path: PathBuf) -> Self {
+        Self { db_path }
+    }
+
+    /// Stored decision for a request, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns `BrowserError::Database` if the setting can't be read
+    pub fn get(&self, request: &PermissionRequest) -> Result<Option<PermissionDecision>> {
+        let db = self.open()?;
+        let value = db
+            .get_setting(&permission_key(request))
+            .map_err(|e| BrowserError::Database(e.to_string()))?;
+
+        Ok(match value.as_deref() {
+            Some("allow") => Some(PermissionDecision::Allow),
+            Some("deny") => Some(PermissionDecision::Deny),
+            Some(other) => {
+                warn!("Ignoring invalid permission setting: {}", other);
+                None
+            }
+            None => None,
+        })
+    }
+
+    /// Remember a decision for a request's origin and capability
+    ///
+    /// # Errors
+    ///
+    /// Returns `BrowserError::Database` if the setting can't be written
+    pub fn set(&self, request: &PermissionRequest, decision: PermissionDecision) -> Result<()> {
+        let value = match decision {
+            PermissionDecision::Allow => "allow",
+            PermissionDecision::Deny => "deny",
+        };
+        self.open()?
+            .set_setting(&permission_key(request), value)
+            .map_err(|e| BrowserError::Database(e.to_string()))
+    }
+
+    /// Stored decision, or ask `prompt` and remember its answer
+    ///
+    /// # Errors
+    ///
+    /// Returns `BrowserError::Database` if the settings table is unavailable
+    pub fn decide(
+        &self,
+        request: &PermissionRequest,
+        prompt: impl FnOnce(&PermissionRequest) -> PermissionDecision,
+    ) -> Result<PermissionDecision> {
+        if let Some(decision) = self.get(request)? {
+            return Ok(decision);
+        }
+
+        let decision = prompt(request);
+        self.set(request, decision)?;
+        Ok(decision)
+    }
+
+    /// Build a renderer permission handler around `prompt`
+    ///
+    /// Requests are denied if the settings table can't be used, so a
+    /// storage failure never grants a permission.
+    pub fn into_handler(
+        self,
+        prompt: impl Fn(&PermissionRequest) -> PermissionDecision + 'static,
+    ) -> impl Fn(&PermissionRequest) -> PermissionDecision + 'static {
+        move |request| {
+            self.decide(request, &prompt).unwrap_or_else(|e| {
+                warn!("Permission lookup failed, denying: {}", e);
+                PermissionDecision::Deny
+            })
+        }
+    }
+
+    fn open(&self) -> Result<Database> {
+        Database::new(&self.db_path).map_err(|e| BrowserError::Database(e.to_string()))
+    }
+}
+
+/// Ask the user about a permission request in a modal dialog
+///
+/// Closing the dialog denies. Only WebKitGTK hands permission requests to
+/// the embedder, so other platforms never get here and deny.
+pub fn ask_user(request: &PermissionRequest) -> PermissionDecision {
+    let question = match request.kind {
+        PermissionKind::Geolocation => format!("Allow {} to know your location?", request.origin),
+        PermissionKind::Notifications => format!("Allow {} to show notifications?", request.origin),
+    };
+
+    #[cfg(target_os = "linux")]
+    {
+        use gtk::prelude::*;
+
+        let dialog = gtk::MessageDialog::new(
+            None::<&gtk::Window>,
+            gtk::DialogFlags::MODAL,
+            gtk::MessageType::Question,
+            gtk::ButtonsType::YesNo,
+            &question,
+        );
+        let response = dialog.run();
+        dialog.close();
+        if response == gtk::ResponseType::Yes {
+            PermissionDecision::Allow
+        } else {
+            PermissionDecision::Deny
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        warn!("Unexpected permission request, denying: {}", question);
+        PermissionDecision::Deny
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use tempfile::NamedTempFile;
+
+    fn request(origin: &str, kind: PermissionKind) -> PermissionRequest {
+        PermissionRequest {
+            origin: origin.to_string(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_permission_key() {
+        assert_eq!(
+            permission_key(&request("https://example.com", PermissionKind::Geolocation)),
+            "permission.geolocation.https://example.com"
+        );
+    }
+
+    #[test]
+    fn test_stored_decision_is_reused() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let store = PermissionStore::new(temp_file.path().to_path_buf());
+        let geo = request("https://maps.example.com", PermissionKind::Geolocation);
+
+        let prompts = Cell::new(0);
+        let prompt = |_: &PermissionRequest| {
+            prompts.set(prompts.get() + 1);
+            PermissionDecision::Allow
+        };
+
+        assert_eq!(
+            store.decide(&geo, prompt).unwrap(),
+            PermissionDecision::Allow
+        );
+        assert_eq!(
+            store.decide(&geo, prompt).unwrap(),
+            PermissionDecision::Allow
+        );
+        assert_eq!(prompts.get(), 1);
+
+        // Decisions are per capability and per origin
+        let notify = request("https://maps.example.com", PermissionKind::Notifications);
+        assert_eq!(store.get(&notify).unwrap(), None);
+        let other = request("https://other.example.com", PermissionKind::Geolocation);
+        assert_eq!(store.get(&other).unwrap(), None);
+    }
+
+    #[test]
+    fn test_handler_remembers_denial() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let prompts = Rc::new(Cell::new(0));
+        let counter = prompts.clone();
+
+        let handler =
+            PermissionStore::new(temp_file.path().to_path_buf()).into_handler(move |_| {
+                counter.set(counter.get() + 1);
+                PermissionDecision::Deny
+            });
+
+        let notify = request("https://news.example.com", PermissionKind::Notifications);
+        assert_eq!(handler(&notify), PermissionDecision::Deny);
+        assert_eq!(handler(&notify), PermissionDecision::Deny);
+        assert_eq!(prompts.get(), 1);
+    }
+
+    #[test]
+    fn test_handler_denies_on_storage_error() {
+        let handler = PermissionStore::new(PathBuf::from("/nonexistent/dir/browser.db"))
+            .into_handler(|_| PermissionDecision::Allow);
+
+        let geo = request("https://example.com", PermissionKind::Geolocation);
+        assert_eq!(handler(&geo), PermissionDecision::Deny);
+    }
+}
@@ -0,0 +1,439 @@
+//! Search engine registry
+//!
+//! URL bar input that isn't an address is searched for with the default
+//! engine, unless it starts with an engine's keyword: with `w` registered
+//! for Wikipedia, `w cats` searches Wikipedia for "cats".
+//!
+//! The registry is saved as JSON in the `search_engines` setting. Without
+//! it, DuckDuckGo, Google and Bing are registered and the config's
+//! `search_url` picks the default.
+
+use crate::config::SEARCH_QUERY_PLACEHOLDER;
+use crate::error::{BrowserError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use storage::Database;
+use url::Url;
+
+/// Settings key holding the registry as JSON
+pub const SEARCH_ENGINES_SETTING: &str = "search_engines";
+
+/// A search engine
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchEngine {
+    /// Display name, unique in the registry
+    pub name: String,
+    /// Prefix selecting this engine in the URL bar; empty for none
+    #[serde(default)]
+    pub keyword: String,
+    /// Search URL template; `{query}` is replaced by the search terms
+    pub url: String,
+}
+
+impl SearchEngine {
+    /// Create an engine; see `SearchEngines::add` for validation
+    pub fn new(name: &str, keyword: &str, url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            keyword: keyword.to_string(),
+            url: url.to_string(),
+        }
+    }
+
+    /// Search URL for `query`, percent-encoded
+    fn search_url(&self, query: &str) -> String {
+        self.url
+            .replace(SEARCH_QUERY_PLACEHOLDER, &urlencoding::encode(query))
+    }
+
+    /// # Errors
+    /// Returns `BrowserError::ConfigError` for a blank name, a keyword
+    /// with whitespace, or a template without `{query}` or that isn't a
+    /// URL
+    fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(BrowserError::ConfigError(
+                "Search engine name is empty".to_string(),
+            ));
+        }
+        if self.keyword.contains(char::is_whitespace) {
+            return Err(BrowserError::ConfigError(format!(
+                "Search keyword {:?} contains whitespace",
+                self.keyword
+            )));
+        }
+        if !self.url.contains(SEARCH_QUERY_PLACEHOLDER) {
+            return Err(BrowserError::ConfigError(format!(
+                "Search URL for {} must contain {}: {}",
+                self.name, SEARCH_QUERY_PLACEHOLDER, self.url
+            )));
+        }
+        Url::parse(&self.search_url("test")).map_err(|e| {
+            BrowserError::ConfigError(format!("Invalid search URL for {}: {}", self.name, e))
+        })?;
+        Ok(())
+    }
+}
+
+/// Registered search engines and the one used by default
+///
+/// Always holds at least one engine, and the default is one of them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "StoredSearchEngines")]
+pub struct SearchEngines {
+    engines: Vec<SearchEngine>,
+    /// Name of the default engine
+    default: String,
+}
+
+/// `SearchEngines` as stored, before validation
+#[derive(Deserialize)]
+struct StoredSearchEngines {
+    engines: Vec<SearchEngine>,
+    default: String,
+}
+
+impl TryFrom<StoredSearchEngines> for SearchEngines {
+    type Error = BrowserError;
+
+    fn try_from(stored: StoredSearchEngines) -> Result<Self> {
+        let mut engines = Self {
+            engines: Vec::new(),
+            default: String::new(),
+        };
+        for engine in stored.engines {
+            engines.add(engine)?;
+        }
+        engines.set_default(&stored.default)?;
+        Ok(engines)
+    }
+}
+
+impl Default for SearchEngines {
+    fn default() -> Self {
+        Self {
+            engines: vec![
+                SearchEngine::new("DuckDuckGo", "d", "https://duckduckgo.com/?q={query}"),
+                SearchEngine::new("Google", "g", "https://www.google.com/search?q={query}"),
+                SearchEngine::new("Bing", "b", "https://www.bing.com/search?q={query}"),
+            ],
+            default: "Google".to_string(),
+        }
+    }
+}
+
+impl SearchEngines {
+    /// The default engines, with the one searching `url` as default
+    ///
+    /// A URL none of them uses is added as a "Custom" engine.
+    ///
+    /// # Errors
+    /// Returns `BrowserError::ConfigError` if `url` isn't a valid template
+    pub fn with_default_url(url: &str) -> Result<Self> {
+        let mut engines = Self::default();
+        let name = match engines.engines.iter().find(|engine| engine.url == url) {
+            Some(engine) => engine.name.clone(),
+            None => {
+                engines.add(SearchEngine::new("Custom", "", url))?;
+                "Custom".to_string()
+            }
+        };
+        engines.default = name;
+        Ok(engines)
+    }
+
+    /// Load the registry from the `search_engines` setting
+    ///
+    /// Returns None if it was never saved.
+    ///
+    /// # Errors
+    /// Returns `BrowserError::Database` if the setting can't be read, or
+    /// `BrowserError::ConfigError` if it isn't a valid registry
+    pub fn load(db_path: &Path) -> Result<Option<Self>> {
+        let db =
+            Database::open_readonly(db_path).map_err(|e| BrowserError::Database(e.to_string()))?;
+        let Some(json) = db
+            .get_setting(SEARCH_ENGINES_SETTING)
+            .map_err(|e| BrowserError::Database(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+
+        serde_json::from_str(&json).map(Some).map_err(|e| {
+            BrowserError::ConfigError(format!("Invalid {} setting: {}", SEARCH_ENGINES_SETTING, e))
+        })
+    }
+
+    /// Save the registry to the `search_engines` setting
+    ///
+    /// # Errors
+    /// Returns `BrowserError::Database` if the setting can't be written
+    #[allow(dead_code)]
+    pub fn save(&self, db_path: &Path) -> Result<()> {
+        let json = serde_json::to_string(self)
+            .map_err(|e| BrowserError::Unexpected(format!("Can't serialize engines: {}", e)))?;
+        let db = Database::new(db_path).map_err(|e| BrowserError::Database(e.to_string()))?;
+        db.set_setting(SEARCH_ENGINES_SETTING, &json)
+            .map_err(|e| BrowserError::Database(e.to_string()))
+    }
+
+    /// Registered engines, in order of registration
+    #[allow(dead_code)]
+    pub fn engines(&self) -> &[SearchEngine] {
+        &self.engines
+    }
+
+    /// Engine used for input without a keyword
+    pub fn default_engine(&self) -> &SearchEngine {
+        self.engines
+            .iter()
+            .find(|engine| engine.name == self.default)
+            .expect("default search engine is registered")
+    }
+
+    /// Engine registered for `keyword` (case-insensitive)
+    pub fn by_keyword(&self, keyword: &str) -> Option<&SearchEngine> {
+        if keyword.is_empty() {
+            return None;
+        }
+        self.engines
+            .iter()
+            .find(|engine| engine.keyword.eq_ignore_ascii_case(keyword))
+    }
+
+    /// Register an engine
+    ///
+    /// The first engine registered becomes the default.
+    ///
+    /// # Errors
+    /// Returns `BrowserError::ConfigError` if the engine is invalid or its
+    /// name or keyword is taken
+    pub fn add(&mut self, engine: SearchEngine) -> Result<()> {
+        engine.validate()?;
+        if self.engines.iter().any(|e| e.name == engine.name) {
+            return Err(BrowserError::ConfigError(format!(
+                "Search engine {} already exists",
+                engine.name
+            )));
+        }
+        if let Some(existing) = self.by_keyword(&engine.keyword) {
+            return Err(BrowserError::ConfigError(format!(
+                "Search keyword {:?} is taken by {}",
+                engine.keyword, existing.name
+            )));
+        }
+
+        if self.engines.is_empty() {
+            self.default = engine.name.clone();
+        }
+        self.engines.push(engine);
+        Ok(())
+    }
+
+    /// Unregister the engine called `name`
+    ///
+    /// If it was the default, the first remaining engine takes over.
+    ///
+    /// # Errors
+    /// Returns `BrowserError::ConfigError` if there's no such engine or
+    /// it's the only one
+    #[allow(dead_code)]
+    pub fn remove(&mut self, name: &str) -> Result<SearchEngine> {
+        let index = self
+            .engines
+            .iter()
+            .position(|engine| engine.name == name)
+            .ok_or_else(|| BrowserError::ConfigError(format!("No search engine {}", name)))?;
+        if self.engines.len() == 1 {
+            return Err(BrowserError::ConfigError(
+                "Can't remove the only search engine".to_string(),
+            ));
+        }
+
+        let removed = self.engines.remove(index);
+        if self.default == removed.name {
+            self.default = self.engines[0].name.clone();
+        }
+        Ok(removed)
+    }
+
+    /// Make the engine called `name` the default
+    ///
+    /// # Errors
+    /// Returns `BrowserError::ConfigError` if there's no such engine
+    pub fn set_default(&mut self, name: &str) -> Result<()> {
+        if !self.engines.iter().any(|engine| engine.name == name) {
+            return Err(BrowserError::ConfigError(format!(
+                "No search engine {}",
+                name
+            )));
+        }
+        self.default = name.to_string();
+        Ok(())
+    }
+
+    /// Search URL for URL bar input
+    ///
+    /// A leading keyword followed by search terms picks that keyword's
+    /// engine; anything else is searched for with the default engine.
+    pub fn resolve_query(&self, input: &str) -> Url {
+        let input = input.trim();
+        let (engine, query) = match input.split_once(char::is_whitespace) {
+            Some((keyword, rest)) if !rest.trim().is_empty() => match self.by_keyword(keyword) {
+                Some(engine) => (engine, rest.trim()),
+                None => (self.default_engine(), input),
+            },
+            _ => (self.default_engine(), input),
+        };
+
+        Url::parse(&engine.search_url(query)).expect("search engine URLs are validated")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn with_wikipedia() -> SearchEngines {
+        let mut engines = SearchEngines::default();
+        engines
+            .add(SearchEngine::new(
+                "Wikipedia",
+                "w",
+                "https://en.wikipedia.org/w/index.php?search={query}",
+            ))
+            .unwrap();
+        engines
+    }
+
+    #[test]
+    fn test_default_engine() {
+        let engines = SearchEngines::default();
+        assert_eq!(engines.engines().len(), 3);
+        assert_eq!(engines.default_engine().name, "Google");
+        assert_eq!(
+            engines.resolve_query("rust lifetimes").as_str(),
+            "https://www.google.com/search?q=rust%20lifetimes"
+        );
+    }
+
+    #[test]
+    fn test_keyword_prefix() {
+        let engines = with_wikipedia();
+        assert_eq!(
+            engines.resolve_query("w cats").as_str(),
+            "https://en.wikipedia.org/w/index.php?search=cats"
+        );
+        assert_eq!(
+            engines.resolve_query("  W   big cats ").as_str(),
+            "https://en.wikipedia.org/w/index.php?search=big%20cats"
+        );
+        assert!(engines
+            .resolve_query("d cats")
+            .as_str()
+            .starts_with("https://duckduckgo.com/"));
+
+        // A keyword alone, or an unknown one, is just a search
+        assert_eq!(
+            engines.resolve_query("w").as_str(),
+            "https://www.google.com/search?q=w"
+        );
+        assert_eq!(
+            engines.resolve_query("x cats").as_str(),
+            "https://www.google.com/search?q=x%20cats"
+        );
+    }
+
+    #[test]
+    fn test_query_encoding() {
+        let engines = SearchEngines::default();
+        assert_eq!(
+            engines.resolve_query("c++ & rust?=#1").as_str(),
+            "https://www.google.com/search?q=c%2B%2B%20%26%20rust%3F%3D%231"
+        );
+        assert_eq!(
+            engines.resolve_query("café").as_str(),
+            "https://www.google.com/search?q=caf%C3%A9"
+        );
+    }
+
+    #[test]
+    fn test_registry_validation() {
+        let mut engines = SearchEngines::default();
+
+        let invalid = [
+            SearchEngine::new("", "x", "https://x.com/?q={query}"),
+            SearchEngine::new("X", "x y", "https://x.com/?q={query}"),
+            SearchEngine::new("X", "x", "https://x.com/"),
+            SearchEngine::new("X", "x", "not a url {query}"),
+            SearchEngine::new("Google", "x", "https://x.com/?q={query}"),
+            SearchEngine::new("X", "G", "https://x.com/?q={query}"),
+        ];
+        for engine in invalid {
+            assert!(
+                matches!(
+                    engines.add(engine.clone()),
+                    Err(BrowserError::ConfigError(_))
+                ),
+                "{:?} accepted",
+                engine
+            );
+        }
+
+        assert!(engines.set_default("Nope").is_err());
+        engines.set_default("Bing").unwrap();
+        engines.remove("Bing").unwrap();
+        assert_eq!(engines.default_engine().name, "DuckDuckGo");
+        engines.remove("Google").unwrap();
+        assert!(engines.remove("DuckDuckGo").is_err());
+    }
+
+    #[test]
+    fn test_with_default_url() {
+        let engines = SearchEngines::with_default_url("https://duckduckgo.com/?q={query}").unwrap();
+        assert_eq!(engines.default_engine().name, "DuckDuckGo");
+        assert_eq!(engines.engines().len(), 3);
+
+        let engines = SearchEngines::with_default_url("https://search.example/?q={query}").unwrap();
+        assert_eq!(engines.default_engine().name, "Custom");
+        assert_eq!(
+            engines.resolve_query("cats").as_str(),
+            "https://search.example/?q=cats"
+        );
+
+        assert!(SearchEngines::with_default_url("https://search.example/").is_err());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+        assert_eq!(SearchEngines::load(temp_file.path()).unwrap(), None);
+
+        let mut engines = with_wikipedia();
+        engines.set_default("Wikipedia").unwrap();
+        engines.save(temp_file.path()).unwrap();
+        assert_eq!(
+            SearchEngines::load(temp_file.path()).unwrap(),
+            Some(engines)
+        );
+
+        // Stored registries are validated like added engines
+        db.set_setting(
+            SEARCH_ENGINES_SETTING,
+            r#"{"engines":[{"name":"A","url":"https://a.com/"}],"default":"A"}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            SearchEngines::load(temp_file.path()),
+            Err(BrowserError::ConfigError(_))
+        ));
+        db.set_setting(
+            SEARCH_ENGINES_SETTING,
+            r#"{"engines":[{"name":"A","url":"https://a.com/?q={query}"}],"default":"B"}"#,
+        )
+        .unwrap();
+        assert!(SearchEngines::load(temp_file.path()).is_err());
+    }
+}
@@ -7,18 +7,25 @@
 //!
 //! # Responsibilities
 //!
-//! - Tab lifecycle (create, close, switch)
-//! - Active tab tracking
+//! - Tab lifecycle (create, duplicate, close, switch, reopen closed)
+//! - Tab display order (reorder, move left/right, pinned tabs first)
+//! - Tab groups (contiguous in display order, close as a unit)
+//! - Active tab tracking (next/previous cycling, most-recently-used order)
 //! - Tab metadata (URL, title, navigation state)
 //! - Settings management (future)
+//! - Change notifications for UI subscribers (`subscribe`)
 
 // Allow dead code temporarily - APIs will be integrated in Week 2
 #![allow(dead_code)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
 
-/// Unique tab identifier
-pub type TabId = usize;
+pub use shared::TabId;
+use shared::TabIdGenerator;
+
+/// Maximum number of closed tabs remembered for reopening
+const MAX_CLOSED_TABS: usize = 10;
 
 /// Tab state information
 #[derive(Debug, Clone)]
@@ -40,6 +47,15 @@ pub struct TabState {
 
     /// Currently loading
     pub is_loading: bool,
+
+    /// Pinned tabs are listed first and need `force_close_tab()` to close
+    pub is_pinned: bool,
+
+    /// Tab group name, if grouped
+    pub group: Option<String>,
+
+    /// Private tabs don't persist history, cookies or cache
+    pub is_private: bool,
 }
 
 impl TabState {
@@ -52,6 +68,9 @@ impl TabState {
             can_go_back: false,
             can_go_forward: false,
             is_loading: false,
+            is_pinned: false,
+            group: None,
+            is_private: false,
         }
     }
 
@@ -72,6 +91,34 @@ impl TabState {
     }
 }
 
+/// State change notification for subscribers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateEvent {
+    /// Tab created (including duplicated and reopened tabs)
+    TabCreated(TabId),
+    /// Tab closed
+    TabClosed(TabId),
+    /// Active tab changed (None when the last tab closed)
+    ActiveChanged(Option<TabId>),
+    /// Tab title changed
+    TitleChanged(TabId),
+    /// Tab URL changed
+    UrlChanged(TabId),
+    /// Tab loading state changed
+    LoadingChanged(TabId),
+    /// Tab back/forward availability changed
+    NavStateChanged(TabId),
+    /// Tab display position changed
+    TabMoved(TabId),
+    /// Tab pinned or unpinned
+    PinnedChanged(TabId),
+    /// Tab group assignment changed
+    GroupChanged(TabId),
+}
+
+/// Callback receiving state change events
+type Subscriber = Box<dyn Fn(&StateEvent)>;
+
 /// State Manager - Single source of truth for browser state
 ///
 /// AWS pattern: Centralized state management for observability
@@ -80,72 +127,353 @@ pub struct StateManager {
     /// All tabs indexed by ID
     tabs: HashMap<TabId, TabState>,
 
+    /// Tab IDs in creation/arrangement order
+    ///
+    /// Display order is this order with pinned tabs moved ahead, so
+    /// unpinning a tab returns it to its previous place.
+    tab_order: Vec<TabId>,
+
     /// Active tab ID
     active_tab_id: Option<TabId>,
 
-    /// Next tab ID (monotonically increasing)
-    next_tab_id: TabId,
+    /// Tab IDs by most recent activation, most recent first
+    ///
+    /// Tabs that were never active are at the end in creation order.
+    mru: Vec<TabId>,
+
+    /// Tab ID allocator (may be shared with other components)
+    ids: Arc<TabIdGenerator>,
+
+    /// Recently closed tabs, most recent last
+    closed_tabs: VecDeque<TabState>,
 
     /// Application settings (future extension)
     settings: HashMap<String, String>,
+
+    /// Change event subscribers
+    subscribers: Vec<Subscriber>,
+
+    /// New tabs are private (per-session setting)
+    private: bool,
 }
 
 impl StateManager {
     /// Create new state manager
     pub fn new() -> Self {
+        Self::with_id_generator(Arc::new(TabIdGenerator::new()))
+    }
+
+    /// Create state manager allocating tab IDs from a shared generator
+    pub fn with_id_generator(ids: Arc<TabIdGenerator>) -> Self {
         Self {
             tabs: HashMap::new(),
+            tab_order: Vec::new(),
             active_tab_id: None,
-            next_tab_id: 0,
+            mru: Vec::new(),
+            ids,
+            closed_tabs: VecDeque::new(),
             settings: HashMap::new(),
+            subscribers: Vec::new(),
+            private: false,
+        }
+    }
+
+    /// Register a callback for state change events
+    ///
+    /// Callbacks run synchronously, in registration order, after the
+    /// change has been applied.
+    pub fn subscribe(&mut self, callback: impl Fn(&StateEvent) + 'static) {
+        self.subscribers.push(Box::new(callback));
+    }
+
+    fn emit(&self, event: StateEvent) {
+        for subscriber in &self.subscribers {
+            subscriber(&event);
         }
     }
 
+    /// Make tabs created from now on private (or not)
+    ///
+    /// Existing tabs keep their setting.
+    pub fn set_private(&mut self, private: bool) {
+        self.private = private;
+    }
+
+    /// Whether new tabs are private
+    pub fn is_private(&self) -> bool {
+        self.private
+    }
+
     /// Create new tab
     ///
+    /// New tabs are private if `set_private(true)` was called.
+    ///
     /// # Arguments
     /// * `url` - Initial URL for tab
     ///
     /// # Returns
     /// Tab ID of created tab
     pub fn create_tab(&mut self, url: String) -> TabId {
-        let tab_id = self.next_tab_id;
-        self.next_tab_id += 1;
+        let tab_id = self.ids.next_id();
 
-        let tab = TabState::new(tab_id, url);
+        let mut tab = TabState::new(tab_id, url);
+        tab.is_private = self.private;
         self.tabs.insert(tab_id, tab);
+        self.tab_order.push(tab_id);
+        self.mru.push(tab_id);
+        self.emit(StateEvent::TabCreated(tab_id));
 
         // Set as active if first tab
         if self.active_tab_id.is_none() {
-            self.active_tab_id = Some(tab_id);
+            self.set_active(tab_id);
         }
 
         tab_id
     }
 
+    /// Duplicate a tab
+    ///
+    /// The copy has the same URL, title and pinned state, fresh navigation
+    /// history, is placed right after the source and becomes active.
+    ///
+    /// # Returns
+    /// ID of the new tab, or None if the source doesn't exist
+    pub fn duplicate_tab(&mut self, id: TabId) -> Option<TabId> {
+        let source = self.tabs.get(&id)?;
+
+        let tab_id = self.ids.next_id();
+
+        let mut tab = TabState::new(tab_id, source.url.clone());
+        tab.title = source.title.clone();
+        tab.is_pinned = source.is_pinned;
+        tab.group = source.group.clone();
+        tab.is_private = source.is_private;
+        self.tabs.insert(tab_id, tab);
+
+        let position = self
+            .tab_order
+            .iter()
+            .position(|&tab_id| tab_id == id)
+            .expect("tab_order out of sync with tabs");
+        self.tab_order.insert(position + 1, tab_id);
+        self.emit(StateEvent::TabCreated(tab_id));
+        self.set_active(tab_id);
+
+        Some(tab_id)
+    }
+
     /// Close tab
     ///
     /// # Arguments
     /// * `id` - Tab ID to close
     ///
     /// # Returns
-    /// Ok if tab existed and was closed
+    /// Ok if tab existed and was closed, Err if it's missing or pinned
     ///
     /// # Side Effects
-    /// If closing active tab, switches to another tab
+    /// If closing active tab, switches to the most recently used remaining tab.
+    /// The closed tab is remembered for `reopen_last_closed()`.
     pub fn close_tab(&mut self, id: TabId) -> Result<(), String> {
-        if self.tabs.remove(&id).is_none() {
-            return Err(format!("Tab {} not found", id));
+        match self.tabs.get(&id) {
+            None => Err(format!("{} not found", id)),
+            Some(tab) if tab.is_pinned => Err(format!("{} is pinned", id)),
+            Some(_) => self.force_close_tab(id),
         }
+    }
 
-        // If closing active tab, switch to another
+    /// Close tab even if it's pinned
+    ///
+    /// Same as `close_tab()` otherwise.
+    pub fn force_close_tab(&mut self, id: TabId) -> Result<(), String> {
+        let tab = self
+            .tabs
+            .remove(&id)
+            .ok_or_else(|| format!("{} not found", id))?;
+
+        if self.closed_tabs.len() == MAX_CLOSED_TABS {
+            self.closed_tabs.pop_front();
+        }
+        self.closed_tabs.push_back(tab);
+        self.tab_order.retain(|&tab_id| tab_id != id);
+        self.mru.retain(|&tab_id| tab_id != id);
+        self.emit(StateEvent::TabClosed(id));
+
+        // If closing active tab, switch to the most recently used one
         if self.active_tab_id == Some(id) {
-            self.active_tab_id = self.tabs.keys().next().copied();
+            match self.mru.first() {
+                Some(&next) => self.set_active(next),
+                None => {
+                    self.active_tab_id = None;
+                    self.emit(StateEvent::ActiveChanged(None));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reopen the most recently closed tab
+    ///
+    /// The tab gets a fresh ID, keeps its URL and title, and becomes active.
+    ///
+    /// # Returns
+    /// ID of the reopened tab, or None if no closed tabs are remembered
+    pub fn reopen_last_closed(&mut self) -> Option<TabId> {
+        let mut tab = self.closed_tabs.pop_back()?;
+
+        let tab_id = self.ids.next_id();
+
+        tab.id = tab_id;
+        tab.is_loading = false;
+        self.tabs.insert(tab_id, tab);
+        self.tab_order.push(tab_id);
+        self.mru.push(tab_id);
+        self.emit(StateEvent::TabCreated(tab_id));
+        self.set_active(tab_id);
+
+        Some(tab_id)
+    }
+
+    /// Number of closed tabs available to reopen
+    pub fn closed_tab_count(&self) -> usize {
+        self.closed_tabs.len()
+    }
+
+    /// Move a tab to a new display position
+    ///
+    /// Pinned tabs stay within the pinned group and unpinned tabs after it.
+    ///
+    /// # Arguments
+    /// * `id` - Tab ID to move
+    /// * `new_index` - Target position (clamped to the tab's group)
+    pub fn reorder_tab(&mut self, id: TabId, new_index: usize) -> Result<(), String> {
+        let is_pinned = self
+            .tabs
+            .get(&id)
+            .ok_or_else(|| format!("{} not found", id))?
+            .is_pinned;
+
+        let mut order = self.display_order();
+        order.retain(|&tab_id| tab_id != id);
+
+        let pinned_count = order.iter().filter(|id| self.is_pinned(**id)).count();
+        let new_index = if is_pinned {
+            new_index.min(pinned_count)
+        } else {
+            new_index.clamp(pinned_count, order.len())
+        };
+
+        order.insert(new_index, id);
+        self.tab_order = order;
+        self.emit(StateEvent::TabMoved(id));
+        Ok(())
+    }
+
+    /// Pin or unpin a tab
+    ///
+    /// Pinned tabs are listed before unpinned ones; unpinning returns the
+    /// tab to its place among the unpinned tabs.
+    pub fn set_pinned(&mut self, id: TabId, pinned: bool) -> Result<(), String> {
+        let tab = self
+            .tabs
+            .get_mut(&id)
+            .ok_or_else(|| format!("{} not found", id))?;
+        tab.is_pinned = pinned;
+        self.emit(StateEvent::PinnedChanged(id));
+        Ok(())
+    }
+
+    /// Move a tab one position left (no-op at the start)
+    pub fn move_tab_left(&mut self, id: TabId) -> Result<(), String> {
+        let position = self
+            .position(id)
+            .ok_or_else(|| format!("{} not found", id))?;
+        self.reorder_tab(id, position.saturating_sub(1))
+    }
+
+    /// Move a tab one position right (no-op at the end)
+    pub fn move_tab_right(&mut self, id: TabId) -> Result<(), String> {
+        let position = self
+            .position(id)
+            .ok_or_else(|| format!("{} not found", id))?;
+        self.reorder_tab(id, position + 1)
+    }
+
+    /// Assign a tab to a group, or remove it from its group with None
+    ///
+    /// Joining a group that already has tabs moves the tab right after
+    /// the group's last tab so groups stay contiguous.
+    pub fn set_tab_group(&mut self, id: TabId, group: Option<String>) -> Result<(), String> {
+        let tab = self
+            .tabs
+            .get_mut(&id)
+            .ok_or_else(|| format!("{} not found", id))?;
+        tab.group = group.clone();
+        self.emit(StateEvent::GroupChanged(id));
+
+        let Some(group) = group else {
+            return Ok(());
+        };
+
+        let last_in_group = self.tab_order.iter().rposition(|&tab_id| {
+            tab_id != id && self.tabs[&tab_id].group.as_deref() == Some(group.as_str())
+        });
+
+        // First member of a group stays where it is
+        if let Some(last) = last_in_group {
+            let position = self
+                .tab_order
+                .iter()
+                .position(|&tab_id| tab_id == id)
+                .expect("tab_order out of sync with tabs");
+            self.tab_order.remove(position);
+            let target = if position < last { last } else { last + 1 };
+            self.tab_order.insert(target, id);
         }
 
         Ok(())
     }
 
+    /// Get the tabs in a group, in display order
+    pub fn tabs_in_group(&self, group: &str) -> Vec<&TabState> {
+        self.get_all_tabs()
+            .into_iter()
+            .filter(|tab| tab.group.as_deref() == Some(group))
+            .collect()
+    }
+
+    /// Close every tab in a group, including pinned ones
+    ///
+    /// If the active tab was in the group, the most recently used
+    /// remaining tab becomes active.
+    ///
+    /// # Returns
+    /// Number of tabs closed
+    pub fn close_group(&mut self, group: &str) -> usize {
+        let ids: Vec<TabId> = self.tabs_in_group(group).iter().map(|tab| tab.id).collect();
+        for &id in &ids {
+            self.force_close_tab(id)
+                .expect("tab listed in group must exist");
+        }
+        ids.len()
+    }
+
+    /// Display position of a tab
+    fn position(&self, id: TabId) -> Option<usize> {
+        self.display_order().iter().position(|&tab_id| tab_id == id)
+    }
+
+    /// Tab IDs in display order (pinned first, then unpinned)
+    fn display_order(&self) -> Vec<TabId> {
+        let (pinned, unpinned): (Vec<TabId>, Vec<TabId>) =
+            self.tab_order.iter().partition(|&&id| self.is_pinned(id));
+        pinned.into_iter().chain(unpinned).collect()
+    }
+
+    fn is_pinned(&self, id: TabId) -> bool {
+        self.tabs.get(&id).is_some_and(|tab| tab.is_pinned)
+    }
+
     /// Switch to tab
     ///
     /// # Arguments
@@ -155,13 +483,66 @@ impl StateManager {
     /// Ok if tab exists
     pub fn switch_tab(&mut self, id: TabId) -> Result<(), String> {
         if !self.tabs.contains_key(&id) {
-            return Err(format!("Tab {} not found", id));
+            return Err(format!("{} not found", id));
         }
 
-        self.active_tab_id = Some(id);
+        self.set_active(id);
         Ok(())
     }
 
+    /// Make a tab active and move it to the front of the MRU order
+    fn set_active(&mut self, id: TabId) {
+        if let Some(position) = self.mru.iter().position(|&tab_id| tab_id == id) {
+            self.mru.remove(position);
+        }
+        self.mru.insert(0, id);
+
+        if self.active_tab_id != Some(id) {
+            self.active_tab_id = Some(id);
+            self.emit(StateEvent::ActiveChanged(Some(id)));
+        }
+    }
+
+    /// Tab IDs by most recent activation, most recent first
+    ///
+    /// For an MRU tab switcher; tabs never activated come last.
+    pub fn mru_order(&self) -> &[TabId] {
+        &self.mru
+    }
+
+    /// Activate the next tab in display order, wrapping at the end
+    ///
+    /// # Returns
+    /// Newly active tab ID, or None with fewer than two tabs
+    pub fn activate_next_tab(&mut self) -> Option<TabId> {
+        self.activate_relative(1)
+    }
+
+    /// Activate the previous tab in display order, wrapping at the start
+    ///
+    /// # Returns
+    /// Newly active tab ID, or None with fewer than two tabs
+    pub fn activate_previous_tab(&mut self) -> Option<TabId> {
+        self.activate_relative(-1)
+    }
+
+    fn activate_relative(&mut self, offset: isize) -> Option<TabId> {
+        let order = self.display_order();
+        if order.len() < 2 {
+            return None;
+        }
+
+        let len = order.len() as isize;
+        let next = match self.active_tab_id.and_then(|id| self.position(id)) {
+            Some(position) => (position as isize + offset).rem_euclid(len),
+            None => 0,
+        };
+
+        let id = order[next as usize];
+        self.switch_tab(id).ok()?;
+        Some(id)
+    }
+
     /// Get active tab
     pub fn get_active_tab(&self) -> Option<&TabState> {
         self.active_tab_id.and_then(|id| self.tabs.get(&id))
@@ -182,9 +563,12 @@ impl StateManager {
         self.tabs.get_mut(&id)
     }
 
-    /// Get all tabs
+    /// Get all tabs in display order
     pub fn get_all_tabs(&self) -> Vec<&TabState> {
-        self.tabs.values().collect()
+        self.display_order()
+            .iter()
+            .filter_map(|id| self.tabs.get(id))
+            .collect()
     }
 
     /// Get tab count
@@ -201,6 +585,7 @@ impl StateManager {
     pub fn update_tab_title(&mut self, id: TabId, title: String) {
         if let Some(tab) = self.tabs.get_mut(&id) {
             tab.set_title(title);
+            self.emit(StateEvent::TitleChanged(id));
         }
     }
 
@@ -208,6 +593,7 @@ impl StateManager {
     pub fn update_tab_url(&mut self, id: TabId, url: String) {
         if let Some(tab) = self.tabs.get_mut(&id) {
             tab.set_url(url);
+            self.emit(StateEvent::UrlChanged(id));
         }
     }
 
@@ -215,6 +601,7 @@ impl StateManager {
     pub fn set_tab_loading(&mut self, id: TabId, loading: bool) {
         if let Some(tab) = self.tabs.get_mut(&id) {
             tab.is_loading = loading;
+            self.emit(StateEvent::LoadingChanged(id));
         }
     }
 
@@ -223,6 +610,7 @@ impl StateManager {
         if let Some(tab) = self.tabs.get_mut(&id) {
             tab.can_go_back = can_go_back;
             tab.can_go_forward = can_go_forward;
+            self.emit(StateEvent::NavStateChanged(id));
         }
     }
 
@@ -247,14 +635,41 @@ impl Default for StateManager {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_private_tabs() {
+        let mut state = StateManager::new();
+        let normal = state.create_tab("https://example.com".to_string());
+
+        state.set_private(true);
+        assert!(state.is_private());
+        let private = state.create_tab("https://example.org".to_string());
+        let copy = state.duplicate_tab(private).unwrap();
+
+        assert!(!state.get_tab(normal).unwrap().is_private);
+        assert!(state.get_tab(private).unwrap().is_private);
+        assert!(state.get_tab(copy).unwrap().is_private);
+    }
+
     #[test]
     fn test_create_tab() {
         let mut state = StateManager::new();
 
         let tab_id = state.create_tab("https://example.com".to_string());
-        assert_eq!(tab_id, 0);
+        assert_eq!(tab_id, TabId(0));
         assert_eq!(state.tab_count(), 1);
-        assert_eq!(state.active_tab_id(), Some(0));
+        assert_eq!(state.active_tab_id(), Some(TabId(0)));
+    }
+
+    #[test]
+    fn test_shared_id_generator() {
+        let ids = Arc::new(TabIdGenerator::new());
+        let mut state1 = StateManager::with_id_generator(ids.clone());
+        let mut state2 = StateManager::with_id_generator(ids.clone());
+
+        let tab1 = state1.create_tab("https://example.com".to_string());
+        let tab2 = state2.create_tab("https://github.com".to_string());
+        assert_ne!(tab1, tab2);
+        assert_eq!(ids.next_id(), TabId(2));
     }
 
     #[test]
@@ -266,9 +681,9 @@ mod tests {
         let tab3 = state.create_tab("https://google.com".to_string());
 
         assert_eq!(state.tab_count(), 3);
-        assert_eq!(tab1, 0);
-        assert_eq!(tab2, 1);
-        assert_eq!(tab3, 2);
+        assert_eq!(tab1, TabId(0));
+        assert_eq!(tab2, TabId(1));
+        assert_eq!(tab3, TabId(2));
     }
 
     #[test]
@@ -317,6 +732,55 @@ mod tests {
         assert_eq!(state.active_tab_id(), Some(tab2));
     }
 
+    #[test]
+    fn test_reopen_closed_tabs_lifo() {
+        let mut state = StateManager::new();
+
+        let tab1 = state.create_tab("https://example.com".to_string());
+        let tab2 = state.create_tab("https://github.com".to_string());
+        state.create_tab("https://google.com".to_string());
+        state.update_tab_title(tab2, "GitHub".to_string());
+
+        state.close_tab(tab1).unwrap();
+        state.close_tab(tab2).unwrap();
+        assert_eq!(state.closed_tab_count(), 2);
+
+        let reopened = state.reopen_last_closed().unwrap();
+        assert_ne!(reopened, tab2); // Fresh ID
+        assert_eq!(state.active_tab_id(), Some(reopened));
+        let tab = state.get_tab(reopened).unwrap();
+        assert_eq!(tab.id, reopened);
+        assert_eq!(tab.url, "https://github.com");
+        assert_eq!(tab.title, "GitHub");
+
+        let reopened = state.reopen_last_closed().unwrap();
+        assert_eq!(state.get_tab(reopened).unwrap().url, "https://example.com");
+        assert_eq!(state.tab_count(), 3);
+
+        assert_eq!(state.reopen_last_closed(), None);
+    }
+
+    #[test]
+    fn test_closed_tabs_cap_evicts_oldest() {
+        let mut state = StateManager::new();
+
+        let ids: Vec<_> = (0..MAX_CLOSED_TABS + 2)
+            .map(|i| state.create_tab(format!("https://site{}.com", i)))
+            .collect();
+        for id in ids {
+            state.close_tab(id).unwrap();
+        }
+        assert_eq!(state.closed_tab_count(), MAX_CLOSED_TABS);
+
+        let mut urls = vec![];
+        while let Some(id) = state.reopen_last_closed() {
+            urls.push(state.get_tab(id).unwrap().url.clone());
+        }
+        assert_eq!(urls.len(), MAX_CLOSED_TABS);
+        assert_eq!(urls.first().unwrap(), "https://site11.com");
+        assert_eq!(urls.last().unwrap(), "https://site2.com");
+    }
+
     #[test]
     fn test_update_tab_title() {
         let mut state = StateManager::new();
@@ -391,4 +855,271 @@ mod tests {
         let tabs = state.get_all_tabs();
         assert_eq!(tabs.len(), 3);
     }
+
+    fn tab_ids(state: &StateManager) -> Vec<TabId> {
+        state.get_all_tabs().iter().map(|tab| tab.id).collect()
+    }
+
+    #[test]
+    fn test_reorder_tabs() {
+        let mut state = StateManager::new();
+
+        let tab1 = state.create_tab("https://example.com".to_string());
+        let tab2 = state.create_tab("https://github.com".to_string());
+        let tab3 = state.create_tab("https://google.com".to_string());
+        assert_eq!(tab_ids(&state), vec![tab1, tab2, tab3]);
+
+        state.reorder_tab(tab3, 0).unwrap();
+        assert_eq!(tab_ids(&state), vec![tab3, tab1, tab2]);
+
+        // Index past the end moves to the last position
+        state.reorder_tab(tab3, 10).unwrap();
+        assert_eq!(tab_ids(&state), vec![tab1, tab2, tab3]);
+
+        assert!(state.reorder_tab(TabId(99), 0).is_err());
+    }
+
+    #[test]
+    fn test_move_tab_left_right() {
+        let mut state = StateManager::new();
+
+        let tab1 = state.create_tab("https://example.com".to_string());
+        let tab2 = state.create_tab("https://github.com".to_string());
+        let tab3 = state.create_tab("https://google.com".to_string());
+
+        state.move_tab_left(tab2).unwrap();
+        assert_eq!(tab_ids(&state), vec![tab2, tab1, tab3]);
+
+        // No-op at the edges
+        state.move_tab_left(tab2).unwrap();
+        assert_eq!(tab_ids(&state), vec![tab2, tab1, tab3]);
+        state.move_tab_right(tab3).unwrap();
+        assert_eq!(tab_ids(&state), vec![tab2, tab1, tab3]);
+
+        state.move_tab_right(tab2).unwrap();
+        assert_eq!(tab_ids(&state), vec![tab1, tab2, tab3]);
+    }
+
+    #[test]
+    fn test_pinned_tabs_sort_first() {
+        let mut state = StateManager::new();
+
+        let tab1 = state.create_tab("https://example.com".to_string());
+        let tab2 = state.create_tab("https://github.com".to_string());
+        let tab3 = state.create_tab("https://google.com".to_string());
+
+        state.set_pinned(tab2, true).unwrap();
+        assert!(state.get_tab(tab2).unwrap().is_pinned);
+        assert_eq!(tab_ids(&state), vec![tab2, tab1, tab3]);
+
+        state.set_pinned(tab2, false).unwrap();
+        assert_eq!(tab_ids(&state), vec![tab1, tab2, tab3]);
+
+        assert!(state.set_pinned(TabId(99), true).is_err());
+    }
+
+    #[test]
+    fn test_reorder_keeps_pinned_group() {
+        let mut state = StateManager::new();
+
+        let tab1 = state.create_tab("https://example.com".to_string());
+        let tab2 = state.create_tab("https://github.com".to_string());
+        let tab3 = state.create_tab("https://google.com".to_string());
+        state.set_pinned(tab3, true).unwrap();
+
+        // Unpinned tab can't move ahead of a pinned one
+        state.reorder_tab(tab2, 0).unwrap();
+        assert_eq!(tab_ids(&state), vec![tab3, tab2, tab1]);
+
+        // Pinned tab can't move behind unpinned ones
+        state.move_tab_right(tab3).unwrap();
+        assert_eq!(tab_ids(&state), vec![tab3, tab2, tab1]);
+    }
+
+    #[test]
+    fn test_pinned_tab_requires_force_close() {
+        let mut state = StateManager::new();
+
+        let tab1 = state.create_tab("https://example.com".to_string());
+        let tab2 = state.create_tab("https://github.com".to_string());
+        state.set_pinned(tab1, true).unwrap();
+
+        assert!(state.close_tab(tab1).is_err());
+        assert_eq!(state.tab_count(), 2);
+
+        state.force_close_tab(tab1).unwrap();
+        assert_eq!(state.tab_count(), 1);
+        assert_eq!(state.active_tab_id(), Some(tab2));
+    }
+
+    #[test]
+    fn test_duplicate_tab() {
+        let mut state = StateManager::new();
+
+        let tab1 = state.create_tab("https://example.com".to_string());
+        let tab2 = state.create_tab("https://github.com".to_string());
+        let tab3 = state.create_tab("https://google.com".to_string());
+        state.update_tab_title(tab2, "GitHub".to_string());
+        state.set_tab_nav_state(tab2, true, false);
+
+        let copy = state.duplicate_tab(tab2).unwrap();
+        assert_eq!(tab_ids(&state), vec![tab1, tab2, copy, tab3]);
+        assert_eq!(state.active_tab_id(), Some(copy));
+
+        let tab = state.get_tab(copy).unwrap();
+        assert_eq!(tab.url, "https://github.com");
+        assert_eq!(tab.title, "GitHub");
+        assert!(!tab.can_go_back);
+        assert!(!tab.can_go_forward);
+
+        assert_eq!(state.duplicate_tab(TabId(99)), None);
+    }
+
+    #[test]
+    fn test_activate_next_previous_wraps() {
+        let mut state = StateManager::new();
+
+        let tab1 = state.create_tab("https://example.com".to_string());
+        let tab2 = state.create_tab("https://github.com".to_string());
+        let tab3 = state.create_tab("https://google.com".to_string());
+        assert_eq!(state.active_tab_id(), Some(tab1));
+
+        assert_eq!(state.activate_next_tab(), Some(tab2));
+        assert_eq!(state.activate_next_tab(), Some(tab3));
+        assert_eq!(state.activate_next_tab(), Some(tab1));
+        assert_eq!(state.active_tab_id(), Some(tab1));
+
+        assert_eq!(state.activate_previous_tab(), Some(tab3));
+        assert_eq!(state.activate_previous_tab(), Some(tab2));
+        assert_eq!(state.activate_previous_tab(), Some(tab1));
+    }
+
+    #[test]
+    fn test_activate_next_previous_single_tab() {
+        let mut state = StateManager::new();
+        assert_eq!(state.activate_next_tab(), None);
+
+        let tab1 = state.create_tab("https://example.com".to_string());
+        assert_eq!(state.activate_next_tab(), None);
+        assert_eq!(state.activate_previous_tab(), None);
+        assert_eq!(state.active_tab_id(), Some(tab1));
+    }
+
+    #[test]
+    fn test_close_active_switches_to_most_recent() {
+        let mut state = StateManager::new();
+
+        let tab_a = state.create_tab("https://example.com".to_string());
+        let tab_b = state.create_tab("https://github.com".to_string());
+        let tab_c = state.create_tab("https://google.com".to_string());
+        let tab_d = state.create_tab("https://rust-lang.org".to_string());
+
+        state.switch_tab(tab_a).unwrap();
+        state.switch_tab(tab_b).unwrap();
+        state.switch_tab(tab_c).unwrap();
+        assert_eq!(state.mru_order(), &[tab_c, tab_b, tab_a, tab_d]);
+
+        state.close_tab(tab_c).unwrap();
+        assert_eq!(state.active_tab_id(), Some(tab_b));
+        assert_eq!(state.mru_order(), &[tab_b, tab_a, tab_d]);
+    }
+
+    #[test]
+    fn test_tab_groups() {
+        let mut state = StateManager::new();
+
+        let tab1 = state.create_tab("https://example.com".to_string());
+        let tab2 = state.create_tab("https://github.com".to_string());
+        let tab3 = state.create_tab("https://google.com".to_string());
+        let tab4 = state.create_tab("https://rust-lang.org".to_string());
+
+        state.set_tab_group(tab1, Some("dev".to_string())).unwrap();
+        state.set_tab_group(tab4, Some("dev".to_string())).unwrap();
+        state.set_tab_group(tab2, Some("news".to_string())).unwrap();
+
+        // Group members are kept contiguous
+        assert_eq!(tab_ids(&state), vec![tab1, tab4, tab2, tab3]);
+
+        // Joining from before the group lands after its last member
+        state.set_tab_group(tab1, Some("news".to_string())).unwrap();
+        assert_eq!(tab_ids(&state), vec![tab4, tab2, tab1, tab3]);
+        state.set_tab_group(tab1, Some("dev".to_string())).unwrap();
+        assert_eq!(tab_ids(&state), vec![tab4, tab1, tab2, tab3]);
+
+        let dev: Vec<TabId> = state.tabs_in_group("dev").iter().map(|t| t.id).collect();
+        assert_eq!(dev, vec![tab4, tab1]);
+        assert!(state.tabs_in_group("missing").is_empty());
+
+        state.set_tab_group(tab4, None).unwrap();
+        assert_eq!(state.tabs_in_group("dev").len(), 1);
+        assert!(state.set_tab_group(TabId(99), None).is_err());
+    }
+
+    #[test]
+    fn test_close_group_with_active_tab() {
+        let mut state = StateManager::new();
+
+        let tab1 = state.create_tab("https://example.com".to_string());
+        let tab2 = state.create_tab("https://github.com".to_string());
+        let tab3 = state.create_tab("https://google.com".to_string());
+        state.set_tab_group(tab2, Some("dev".to_string())).unwrap();
+        state.set_tab_group(tab3, Some("dev".to_string())).unwrap();
+
+        state.switch_tab(tab1).unwrap();
+        state.switch_tab(tab2).unwrap();
+        state.switch_tab(tab3).unwrap();
+
+        assert_eq!(state.close_group("dev"), 2);
+        assert_eq!(state.tab_count(), 1);
+        assert_eq!(state.active_tab_id(), Some(tab1));
+        assert_eq!(state.close_group("dev"), 0);
+    }
+
+    #[test]
+    fn test_state_events() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut state = StateManager::new();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let sink = events.clone();
+        state.subscribe(move |event| sink.borrow_mut().push(event.clone()));
+
+        let tab1 = state.create_tab("https://example.com".to_string());
+        let tab2 = state.create_tab("https://github.com".to_string());
+        state.switch_tab(tab2).unwrap();
+        state.switch_tab(tab2).unwrap(); // Already active: no event
+        state.update_tab_title(tab2, "GitHub".to_string());
+        state.close_tab(tab2).unwrap();
+        state.close_tab(tab1).unwrap();
+
+        assert_eq!(
+            *events.borrow(),
+            vec![
+                StateEvent::TabCreated(tab1),
+                StateEvent::ActiveChanged(Some(tab1)),
+                StateEvent::TabCreated(tab2),
+                StateEvent::ActiveChanged(Some(tab2)),
+                StateEvent::TitleChanged(tab2),
+                StateEvent::TabClosed(tab2),
+                StateEvent::ActiveChanged(Some(tab1)),
+                StateEvent::TabClosed(tab1),
+                StateEvent::ActiveChanged(None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_close_preserves_order() {
+        let mut state = StateManager::new();
+
+        let tab1 = state.create_tab("https://example.com".to_string());
+        let tab2 = state.create_tab("https://github.com".to_string());
+        let tab3 = state.create_tab("https://google.com".to_string());
+        let tab4 = state.create_tab("https://rust-lang.org".to_string());
+        state.reorder_tab(tab4, 0).unwrap();
+
+        state.close_tab(tab2).unwrap();
+        assert_eq!(tab_ids(&state), vec![tab4, tab1, tab3]);
+    }
 }
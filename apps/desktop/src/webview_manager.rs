@@ -9,16 +9,26 @@
 //!
 //! Manages dual WebView architecture:
 //! - Chrome WebView (88px top): HTML UI (tabs, URL bar, navigation)
-//! - Content WebView (below): Actual web pages
+//! - Content WebViews (below): Actual web pages, one per tab; only the
+//!   active tab's WebView is visible
 
 // Allow dead code temporarily - APIs will be integrated in Week 2
 #![allow(dead_code)]
 
 use crate::error::{BrowserError, Result};
-use std::rc::Rc;
+use crate::history::TabHistory;
+use crate::ipc::ChromeEvent;
+use crate::metrics::NavTiming;
+use crate::state::StateManager;
+use renderer::{PermissionDecision, PermissionRequest};
+use shared::{TabId, TabIdGenerator};
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+use std::time::Duration;
 use tao::window::Window;
-use tracing::{error, info};
-use wry::{Rect, WebView, WebViewBuilder};
+use tracing::{debug, error, info};
+use wry::{PageLoadEvent, Rect, WebView, WebViewBuilder};
 
 /// Configuration for WebView creation
 #[derive(Debug, Clone)]
@@ -29,6 +39,9 @@ pub struct WebViewConfig {
     pub devtools_enabled: bool,
     /// Initial URL for content WebView
     pub initial_url: String,
+    /// Private mode: content WebViews use an ephemeral data store, so
+    /// cookies, cache and local storage aren't persisted
+    pub private: bool,
 }
 
 impl Default for WebViewConfig {
@@ -37,10 +50,310 @@ impl Default for WebViewConfig {
             chrome_height: 88.0, // tab bar (40px) + URL bar (48px)
             devtools_enabled: cfg!(debug_assertions),
             initial_url: "https://example.com".to_string(),
+            private: false,
         }
     }
 }
 
+/// Title to show for a tab: the page title, else the URL's host
+pub fn tab_title(title: &str, url: &str) -> String {
+    let title = title.trim();
+    if !title.is_empty() {
+        return title.to_string();
+    }
+    url::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "New Tab".to_string())
+}
+
+/// Push an event to a chrome WebView held outside the manager
+///
+/// Used by WebView callbacks that only have a handle to the chrome WebView.
+///
+/// # Errors
+/// Returns `BrowserError::IpcError` if the event can't be delivered
+pub fn emit_chrome_event_to(chrome_webview: &WebView, event: &ChromeEvent) -> Result<()> {
+    chrome_webview
+        .evaluate_script(&event.to_script()?)
+        .map_err(|e| {
+            error!("Chrome event dispatch failed: {}", e);
+            BrowserError::IpcError(format!("Chrome event dispatch failed: {}", e))
+        })
+}
+
+/// Record a content tab's navigation, returning the chrome events for it
+///
+/// The tab gets `url` and a provisional title from it. Only the active
+/// tab moves the URL bar; a background tab just retitles its tab strip
+/// entry.
+pub fn tab_navigated(state: &mut StateManager, tab: TabId, url: &str) -> Vec<ChromeEvent> {
+    let title = tab_title("", url);
+    state.update_tab_url(tab, url.to_string());
+    state.update_tab_title(tab, title.clone());
+
+    let mut events = Vec::new();
+    if state.active_tab_id() == Some(tab) {
+        events.push(ChromeEvent::UrlChanged {
+            tab,
+            url: url.to_string(),
+        });
+    }
+    events.push(ChromeEvent::TitleChanged { tab, title });
+    events
+}
+
+/// Record a content tab's new page title, returning the chrome event
+/// retitling its tab strip entry
+pub fn tab_title_changed(state: &mut StateManager, tab: TabId, page_title: &str) -> ChromeEvent {
+    let url = state
+        .get_tab(tab)
+        .map(|tab| tab.url.clone())
+        .unwrap_or_default();
+    let title = tab_title(page_title, &url);
+    state.update_tab_title(tab, title.clone());
+    ChromeEvent::TitleChanged { tab, title }
+}
+
+/// Origin of a URL (`https://example.com:8080`), used to key per-site data
+///
+/// None for URLs without a tuple origin, like `about:blank` or `data:`.
+pub fn origin_of(url: &str) -> Option<String> {
+    let origin = url::Url::parse(url).ok()?.origin();
+    origin.is_tuple().then(|| origin.ascii_serialization())
+}
+
+/// Zoom factor to apply to a page once it has loaded, by URL
+pub type ZoomLookup = Rc<dyn Fn(&str) -> f64>;
+
+/// Receives a loaded page's navigation timing, with the page URL
+pub type TimingHandler = Rc<dyn Fn(&str, NavTiming)>;
+
+/// Decides a page's geolocation and notification permission requests
+pub type PermissionHandler = Rc<dyn Fn(&PermissionRequest) -> PermissionDecision>;
+
+/// Posts the page's Navigation Timing phases, in milliseconds, once its
+/// load event is over
+const NAV_TIMING_SCRIPT: &str = r#"(function() {
+    if (window.top !== window) return;
+    window.addEventListener('load', function() {
+        // loadEventEnd is only set once the load handlers have returned
+        setTimeout(function() {
+            try {
+                var t = performance.getEntriesByType('navigation')[0];
+                if (!t) return;
+                var secure = t.secureConnectionStart > 0;
+                window.ipc.postMessage(JSON.stringify({
+                    type: 'navtiming',
+                    dns: t.domainLookupEnd - t.domainLookupStart,
+                    connect: (secure ? t.secureConnectionStart : t.connectEnd) - t.connectStart,
+                    tls: secure ? t.connectEnd - t.secureConnectionStart : null,
+                    ttfb: t.responseStart - t.requestStart,
+                    total: t.loadEventEnd - t.startTime
+                }));
+            } catch (e) {}
+        }, 0);
+    });
+})();"#;
+
+/// Phase durations as posted by `NAV_TIMING_SCRIPT`
+#[derive(serde::Deserialize)]
+struct NavTimingMessage {
+    #[serde(rename = "type")]
+    kind: String,
+    dns: f64,
+    connect: f64,
+    tls: Option<f64>,
+    ttfb: f64,
+    total: f64,
+}
+
+/// Parse a content IPC message into a navigation timing, None for
+/// anything else
+fn parse_nav_timing(body: &str) -> Option<NavTiming> {
+    let message: NavTimingMessage = serde_json::from_str(body).ok()?;
+    if message.kind != "navtiming" {
+        return None;
+    }
+    // Negative values come from phases that didn't happen
+    let ms = |value: f64| Duration::try_from_secs_f64(value / 1000.0).unwrap_or_default();
+    Some(NavTiming {
+        dns: ms(message.dns),
+        connect: ms(message.connect),
+        tls: message.tls.map(ms),
+        ttfb: ms(message.ttfb),
+        total: ms(message.total),
+    })
+}
+
+/// Parse the JSON-encoded result of evaluating `document.title`
+fn parse_title_result(result: &str) -> Option<String> {
+    serde_json::from_str::<String>(result)
+        .ok()
+        .filter(|title| !title.trim().is_empty())
+}
+
+impl WebViewConfig {
+    /// Bounds of the content WebView for a window size
+    ///
+    /// Content fills the window below the chrome; a chrome height of 0
+    /// gives it the whole window.
+    pub fn content_bounds(&self, window_width: u32, window_height: u32) -> Rect {
+        let height = (window_height as f64 - self.chrome_height).max(0.0);
+        Rect {
+            position: tao::dpi::LogicalPosition::new(0.0, self.chrome_height).into(),
+            size: tao::dpi::LogicalSize::new(window_width as f64, height).into(),
+        }
+    }
+}
+
+/// Focuses the page when the platform has no native WebView focus
+const FOCUS_SCRIPT: &str = "window.focus();";
+
+/// Takes focus away from whatever element in the page has it
+const BLUR_SCRIPT: &str = "document.activeElement && document.activeElement.blur();";
+
+/// Focuses the chrome's URL bar with its text selected, ready to type over
+const FOCUS_URL_BAR_SCRIPT: &str = "(() => { \
+    const input = document.getElementById('url-input'); \
+    if (input) { input.focus(); input.select(); } \
+})();";
+
+/// Keyboard focus for a content view
+///
+/// A trait so the focus calls made on tab switches are testable without a
+/// window.
+trait Focusable {
+    /// Give the view keyboard focus
+    fn focus(&self) -> Result<()>;
+
+    /// Take focus away from the view's page
+    fn blur(&self) -> Result<()>;
+}
+
+impl Focusable for Rc<WebView> {
+    fn focus(&self) -> Result<()> {
+        focus_webview(self)
+    }
+
+    fn blur(&self) -> Result<()> {
+        self.evaluate_script(BLUR_SCRIPT)
+            .map_err(|e| BrowserError::WindowError(e.to_string()))
+    }
+}
+
+/// Focus a WebView natively, or by script where that isn't supported
+fn focus_webview(webview: &WebView) -> Result<()> {
+    webview
+        .focus()
+        .or_else(|e| {
+            debug!("Native focus failed ({}), focusing by script", e);
+            webview.evaluate_script(FOCUS_SCRIPT)
+        })
+        .map_err(|e| BrowserError::WindowError(e.to_string()))
+}
+
+/// Content views keyed by tab, with the active tab tracked
+///
+/// Generic over the view type so the bookkeeping is testable without a window.
+struct ContentViews<V> {
+    views: HashMap<TabId, V>,
+    active: Option<TabId>,
+    /// Content area shown; false while an overlay covers it
+    visible: Cell<bool>,
+}
+
+impl<V> ContentViews<V> {
+    fn new() -> Self {
+        Self {
+            views: HashMap::new(),
+            active: None,
+            visible: Cell::new(true),
+        }
+    }
+
+    fn set_visible(&self, visible: bool) {
+        self.visible.set(visible);
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible.get()
+    }
+
+    fn insert(&mut self, id: TabId, view: V) {
+        self.views.insert(id, view);
+    }
+
+    /// Make `id` active, returns false if there is no such view
+    fn activate(&mut self, id: TabId) -> bool {
+        if self.views.contains_key(&id) {
+            self.active = Some(id);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Remove a view; closing the active tab leaves no tab active
+    fn remove(&mut self, id: TabId) -> Option<V> {
+        let view = self.views.remove(&id)?;
+        if self.active == Some(id) {
+            self.active = None;
+        }
+        Some(view)
+    }
+
+    fn active(&self) -> Option<&V> {
+        self.active.and_then(|id| self.views.get(&id))
+    }
+
+    fn get(&self, id: TabId) -> Option<&V> {
+        self.views.get(&id)
+    }
+
+    fn len(&self) -> usize {
+        self.views.len()
+    }
+
+    /// Remove every view but the active one, returning their tabs
+    fn remove_inactive(&mut self) -> Vec<TabId> {
+        let mut removed: Vec<TabId> = self
+            .views
+            .keys()
+            .copied()
+            .filter(|id| Some(*id) != self.active)
+            .collect();
+        removed.sort_by_key(|id| id.0);
+        for id in &removed {
+            self.views.remove(id);
+        }
+        removed
+    }
+
+    /// Views other than the active one
+    fn inactive(&self) -> impl Iterator<Item = &V> {
+        self.views
+            .iter()
+            .filter(move |(id, _)| Some(**id) != self.active)
+            .map(|(_, view)| view)
+    }
+}
+
+impl<V: Focusable> ContentViews<V> {
+    /// Blur the inactive views, then focus the active one
+    ///
+    /// The active view isn't focused while content is hidden.
+    fn focus_active(&self) -> Result<()> {
+        for view in self.inactive() {
+            view.blur()?;
+        }
+        if let Some(view) = self.active().filter(|_| self.is_visible()) {
+            view.focus()?;
+        }
+        Ok(())
+    }
+}
+
 /// WebView Manager - Single-threaded ownership pattern
 ///
 /// Manages lifecycle of chrome + content WebViews following AWS
@@ -49,11 +362,31 @@ pub struct WebViewManager {
     /// Chrome WebView (top 88px) - HTML UI
     chrome_webview: Rc<WebView>,
 
-    /// Content WebView (below chrome) - Web pages
-    content_webview: Option<WebView>,
+    /// Content WebViews (below chrome) - Web pages, one per tab
+    ///
+    /// Shared so a WebView's page-load handler can reach it via a `Weak`.
+    content_webviews: ContentViews<Rc<WebView>>,
+
+    /// Each tab's back/forward entries, with their saved scroll offsets
+    histories: HashMap<TabId, Rc<RefCell<TabHistory>>>,
+
+    /// Allocates IDs for content tabs
+    tab_ids: TabIdGenerator,
 
     /// Configuration
     config: WebViewConfig,
+
+    /// Last known window size, used to reposition content
+    window_size: (u32, u32),
+
+    /// Zoom applied to content pages when they finish loading
+    zoom_lookup: Option<ZoomLookup>,
+
+    /// Receives content pages' navigation timings
+    timing_handler: Option<TimingHandler>,
+
+    /// Decides content pages' permission requests
+    permission_handler: Option<PermissionHandler>,
 }
 
 impl WebViewManager {
@@ -93,40 +426,113 @@ impl WebViewManager {
 
         Ok(Self {
             chrome_webview: Rc::new(chrome_webview),
-            content_webview: None,
+            content_webviews: ContentViews::new(),
+            histories: HashMap::new(),
+            tab_ids: TabIdGenerator::new(),
             config,
+            window_size: window.inner_size().into(),
+            zoom_lookup: None,
+            timing_handler: None,
+            permission_handler: None,
         })
     }
 
-    /// Create content WebView (positioned below chrome)
+    /// Apply a zoom factor to content pages whenever one finishes loading
+    ///
+    /// Affects WebViews created afterwards. `lookup` gets the page URL and
+    /// should return 1.0 for pages without a stored zoom, so the previous
+    /// page's zoom doesn't carry over.
+    pub fn set_zoom_lookup(&mut self, lookup: impl Fn(&str) -> f64 + 'static) {
+        self.zoom_lookup = Some(Rc::new(lookup));
+    }
+
+    /// Report each content page's navigation timing once it has loaded
+    ///
+    /// Affects WebViews created afterwards. Only top-level pages report,
+    /// and pages restored from the back/forward cache don't.
+    pub fn set_timing_handler(&mut self, handler: impl Fn(&str, NavTiming) + 'static) {
+        self.timing_handler = Some(Rc::new(handler));
+    }
+
+    /// Decide content pages' geolocation and notification requests
+    ///
+    /// Affects WebViews created afterwards. Without a handler the platform
+    /// default applies (WebKitGTK denies).
+    pub fn set_permission_handler(
+        &mut self,
+        handler: impl Fn(&PermissionRequest) -> PermissionDecision + 'static,
+    ) {
+        self.permission_handler = Some(Rc::new(handler));
+    }
+
+    /// Create a content WebView for a new tab (positioned below chrome)
+    ///
+    /// The new tab becomes the active one; other tabs' WebViews are hidden.
     ///
     /// # Arguments
     /// * `window` - tao window to attach content WebView to
     /// * `url` - Initial URL to load
     /// * `navigation_handler` - Closure called on URL navigation
+    /// * `title_handler` - Closure called when the page's `document.title` changes
+    ///
+    /// # Errors
+    /// Returns `BrowserError::WebViewCreation` if WebView creation fails
+    pub fn create_content_webview<F, G>(
+        &mut self,
+        window: &Window,
+        url: &str,
+        navigation_handler: F,
+        title_handler: G,
+    ) -> Result<TabId>
+    where
+        F: Fn(String) -> bool + 'static,
+        G: Fn(String) + 'static,
+    {
+        let tab_id = self.tab_ids.next_id();
+        self.create_content_webview_with_id(
+            tab_id,
+            window,
+            url,
+            navigation_handler,
+            title_handler,
+        )?;
+        Ok(tab_id)
+    }
+
+    /// Create a content WebView for a tab whose ID is allocated elsewhere
+    ///
+    /// Use when tab IDs come from `StateManager`, so both agree on IDs.
+    /// Don't mix with `create_content_webview()` on the same manager.
     ///
     /// # Errors
+    /// Returns `BrowserError::ConfigError` if the tab already has a WebView
     /// Returns `BrowserError::WebViewCreation` if WebView creation fails
-    pub fn create_content_webview<F>(
+    pub fn create_content_webview_with_id<F, G>(
         &mut self,
+        tab_id: TabId,
         window: &Window,
         url: &str,
         navigation_handler: F,
+        title_handler: G,
     ) -> Result<()>
     where
         F: Fn(String) -> bool + 'static,
+        G: Fn(String) + 'static,
     {
+        if self.content_webviews.get(tab_id).is_some() {
+            return Err(BrowserError::ConfigError(format!(
+                "{} already has a content WebView",
+                tab_id
+            )));
+        }
+
         let window_size = window.inner_size();
+        self.window_size = window_size.into();
 
         // Calculate content WebView bounds (below chrome)
-        let content_bounds = Rect {
-            position: tao::dpi::LogicalPosition::new(0.0, self.config.chrome_height).into(),
-            size: tao::dpi::LogicalSize::new(
-                window_size.width as f64,
-                window_size.height as f64 - self.config.chrome_height,
-            )
-            .into(),
-        };
+        let content_bounds = self
+            .config
+            .content_bounds(window_size.width, window_size.height);
 
         info!(
             "Creating content WebView at y={}, height={}",
@@ -134,34 +540,203 @@ impl WebViewManager {
             window_size.height as f64 - self.config.chrome_height
         );
 
-        let content_webview = WebViewBuilder::new()
+        // Filled in once built, for the page-load handler
+        let webview_slot: Rc<OnceCell<Weak<WebView>>> = Rc::new(OnceCell::new());
+        let slot = webview_slot.clone();
+        let zoom_lookup = self.zoom_lookup.clone();
+        let history = Rc::new(RefCell::new(TabHistory::new()));
+        let history_for_load = history.clone();
+
+        let mut builder = WebViewBuilder::new()
             .with_url(url)
             .with_bounds(content_bounds)
             .with_devtools(self.config.devtools_enabled)
+            .with_incognito(self.config.private)
             .with_navigation_handler(navigation_handler)
+            .with_document_title_changed_handler(title_handler)
+            .with_on_page_load_handler(move |event, url| {
+                if !matches!(event, PageLoadEvent::Finished) {
+                    return;
+                }
+                let saved_scroll = history_for_load.borrow_mut().commit_load(&url);
+                let Some(webview) = slot.get().and_then(Weak::upgrade) else {
+                    return;
+                };
+                if let Some(lookup) = &zoom_lookup {
+                    if let Err(e) = webview.zoom(lookup(&url)) {
+                        error!("Failed to apply zoom for {}: {}", url, e);
+                    }
+                }
+                // Back/forward returns to where the page was left
+                if let Some((x, y)) = saved_scroll {
+                    let script = format!("window.scrollTo({}, {});", x, y);
+                    if let Err(e) = webview.evaluate_script(&script) {
+                        error!("Failed to restore scroll for {}: {}", url, e);
+                    }
+                }
+            })
+            .with_initialization_script(renderer::SCROLL_TRACKING_SCRIPT);
+
+        let timing_handler = self.timing_handler.clone();
+        if timing_handler.is_some() {
+            builder = builder.with_initialization_script(NAV_TIMING_SCRIPT);
+        }
+        let history_for_scroll = history.clone();
+        builder = builder.with_ipc_handler(move |request| {
+            let page_url = request.uri().to_string();
+            if let Some((x, y)) = renderer::parse_scroll_message(request.body()) {
+                // Ignore the old page while a navigation away is loading
+                let mut history = history_for_scroll.borrow_mut();
+                if history.current_url() == Some(page_url.as_str()) {
+                    history.save_scroll_position(x, y);
+                }
+            } else if let (Some(handler), Some(timing)) =
+                (&timing_handler, parse_nav_timing(request.body()))
+            {
+                handler(&page_url, timing);
+            }
+        });
+
+        let content_webview = builder
             .build_as_child(window)
             .map_err(|e| BrowserError::WebViewCreation(e.to_string()))?;
 
-        self.content_webview = Some(content_webview);
+        if let Some(handler) = self.permission_handler.clone() {
+            renderer::connect_permission_handler(&content_webview, move |request| handler(request));
+        }
+
+        let content_webview = Rc::new(content_webview);
+        let _ = webview_slot.set(Rc::downgrade(&content_webview));
+        self.content_webviews.insert(tab_id, content_webview);
+        self.histories.insert(tab_id, history);
+
+        info!("✅ Content WebView created for {}: {}", tab_id, url);
+
+        self.show_tab(tab_id)
+    }
+
+    /// Show a tab's content WebView and hide the others
+    ///
+    /// The shown WebView is repositioned to the current content area, since
+    /// the window may have been resized while it was hidden, and given
+    /// keyboard focus so typing goes to the page on screen.
+    ///
+    /// # Errors
+    /// Returns `BrowserError::ConfigError` if the tab has no content WebView
+    /// Returns `BrowserError::WindowError` if bounds or visibility can't be set
+    pub fn show_tab(&mut self, tab_id: TabId) -> Result<()> {
+        if !self.content_webviews.activate(tab_id) {
+            return Err(BrowserError::ConfigError(format!(
+                "No content WebView for {}",
+                tab_id
+            )));
+        }
+
+        for webview in self.content_webviews.inactive() {
+            webview
+                .set_visible(false)
+                .map_err(|e| BrowserError::WindowError(e.to_string()))?;
+        }
+
+        let (width, height) = self.window_size;
+        self.resize_content(width, height)?;
+        if let Some(webview) = self.content_webviews.active() {
+            webview
+                .set_visible(self.content_webviews.is_visible())
+                .map_err(|e| BrowserError::WindowError(e.to_string()))?;
+        }
+
+        // Focus is best effort; the switch itself has happened
+        if let Err(e) = self.content_webviews.focus_active() {
+            error!("Failed to focus {}: {}", tab_id, e);
+        }
+
+        Ok(())
+    }
+
+    /// Give the URL bar keyboard focus, e.g. for a new blank tab
+    ///
+    /// # Errors
+    /// Returns `BrowserError::WindowError` if the chrome WebView can't be
+    /// focused
+    pub fn focus_chrome(&self) -> Result<()> {
+        focus_webview(&self.chrome_webview)?;
+        self.chrome_webview
+            .evaluate_script(FOCUS_URL_BAR_SCRIPT)
+            .map_err(|e| BrowserError::WindowError(e.to_string()))
+    }
+
+    /// Show or hide web content, e.g. while a chrome overlay is open
+    ///
+    /// Native WebViews draw above the chrome, so overlays must hide content.
+    /// Showing again restores the bounds computed from the current config
+    /// and window size. The setting also applies to tabs shown later.
+    ///
+    /// # Errors
+    /// Returns `BrowserError::WindowError` if bounds or visibility can't be set
+    pub fn set_content_visible(&self, visible: bool) -> Result<()> {
+        self.content_webviews.set_visible(visible);
+
+        if let Some(webview) = self.content_webviews.active() {
+            if visible {
+                let (width, height) = self.window_size;
+                webview
+                    .set_bounds(self.config.content_bounds(width, height))
+                    .map_err(|e| BrowserError::WindowError(e.to_string()))?;
+            }
+            webview
+                .set_visible(visible)
+                .map_err(|e| BrowserError::WindowError(e.to_string()))?;
+        }
+
+        info!("Content WebView visible: {}", visible);
+        Ok(())
+    }
+
+    /// Check if web content is shown (not hidden for an overlay)
+    pub fn is_content_visible(&self) -> bool {
+        self.content_webviews.is_visible()
+    }
 
-        info!("✅ Content WebView created for: {}", url);
+    /// Close a tab's content WebView
+    ///
+    /// Closing the active tab leaves none shown; call `show_tab()` with the
+    /// tab to switch to.
+    ///
+    /// # Errors
+    /// Returns `BrowserError::ConfigError` if the tab has no content WebView
+    pub fn close_content_tab(&mut self, tab_id: TabId) -> Result<()> {
+        // Dropping the WebView destroys it
+        self.content_webviews.remove(tab_id).ok_or_else(|| {
+            BrowserError::ConfigError(format!("No content WebView for {}", tab_id))
+        })?;
+        self.histories.remove(&tab_id);
 
+        info!("✅ Closed content WebView for {}", tab_id);
         Ok(())
     }
 
-    /// Resize content WebView to match window size
+    /// Destroy the content WebViews of every tab but the active one
+    ///
+    /// Frees their pages' memory under pressure. Returns the discarded
+    /// tabs, which have no WebView until one is created for them again.
+    pub fn discard_background_tabs(&mut self) -> Vec<TabId> {
+        let discarded = self.content_webviews.remove_inactive();
+        for tab_id in &discarded {
+            self.histories.remove(tab_id);
+        }
+        info!("Discarded content WebViews for {} tab(s)", discarded.len());
+        discarded
+    }
+
+    /// Resize the active content WebView to match window size
     ///
-    /// Called when window is resized.
+    /// Called when window is resized. Hidden tabs are repositioned when shown.
     pub fn resize_content(&mut self, new_width: u32, new_height: u32) -> Result<()> {
-        if let Some(ref content_webview) = self.content_webview {
-            let content_bounds = Rect {
-                position: tao::dpi::LogicalPosition::new(0.0, self.config.chrome_height).into(),
-                size: tao::dpi::LogicalSize::new(
-                    new_width as f64,
-                    new_height as f64 - self.config.chrome_height,
-                )
-                .into(),
-            };
+        self.window_size = (new_width, new_height);
+
+        if let Some(content_webview) = self.content_webviews.active() {
+            let content_bounds = self.config.content_bounds(new_width, new_height);
 
             content_webview
                 .set_bounds(content_bounds)
@@ -177,6 +752,27 @@ impl WebViewManager {
         Ok(())
     }
 
+    /// Change the chrome height and reposition the content WebView
+    ///
+    /// Used for a collapsible toolbar; 0 gives content the full window
+    /// (fullscreen).
+    ///
+    /// # Errors
+    /// Returns `BrowserError::ConfigError` if the height is negative or not finite
+    /// Returns `BrowserError::WindowError` if the content bounds can't be set
+    pub fn set_chrome_height(&mut self, height: f64) -> Result<()> {
+        if !height.is_finite() || height < 0.0 {
+            return Err(BrowserError::ConfigError(format!(
+                "Invalid chrome height: {}",
+                height
+            )));
+        }
+
+        self.config.chrome_height = height;
+        let (width, window_height) = self.window_size;
+        self.resize_content(width, window_height)
+    }
+
     /// Evaluate JavaScript in chrome WebView
     ///
     /// # Arguments
@@ -192,7 +788,15 @@ impl WebViewManager {
         Ok(())
     }
 
-    /// Evaluate JavaScript in content WebView
+    /// Push an event to the chrome WebView
+    ///
+    /// # Errors
+    /// Returns `BrowserError::IpcError` if the event can't be delivered
+    pub fn emit_chrome_event(&self, event: &ChromeEvent) -> Result<()> {
+        emit_chrome_event_to(&self.chrome_webview, event)
+    }
+
+    /// Evaluate JavaScript in the active content WebView
     ///
     /// # Arguments
     /// * `script` - JavaScript code to execute
@@ -201,7 +805,7 @@ impl WebViewManager {
     /// Returns `BrowserError::IpcError` if script evaluation fails
     /// Returns `BrowserError::NotInitialized` if content WebView not created
     pub fn evaluate_content_script(&self, script: &str) -> Result<()> {
-        if let Some(ref content_webview) = self.content_webview {
+        if let Some(content_webview) = self.content_webviews.active() {
             content_webview.evaluate_script(script).map_err(|e| {
                 error!("Content script eval failed: {}", e);
                 BrowserError::IpcError(format!("Content script eval failed: {}", e))
@@ -214,19 +818,100 @@ impl WebViewManager {
         }
     }
 
+    /// Set the zoom factor of the active content WebView (1.0 = 100%)
+    ///
+    /// # Errors
+    /// Returns `BrowserError::ConfigError` if content WebView not created
+    /// Returns `BrowserError::WindowError` if the platform rejects the zoom
+    pub fn zoom_content(&self, factor: f64) -> Result<()> {
+        let content_webview = self.content_webviews.active().ok_or_else(|| {
+            BrowserError::ConfigError("Content WebView not initialized".to_string())
+        })?;
+
+        content_webview
+            .zoom(factor)
+            .map_err(|e| BrowserError::WindowError(e.to_string()))
+    }
+
+    /// URL currently loaded in the active content WebView
+    pub fn current_content_url(&self) -> Option<String> {
+        self.content_webviews
+            .active()
+            .and_then(|webview| webview.url().ok())
+            .filter(|url| !url.is_empty())
+    }
+
+    /// Read `document.title` from the active content WebView
+    ///
+    /// The title arrives asynchronously; `callback` gets None for an empty
+    /// or unreadable title.
+    ///
+    /// # Errors
+    /// Returns `BrowserError::ConfigError` if content WebView not created
+    /// Returns `BrowserError::IpcError` if script evaluation fails
+    pub fn current_content_title<F>(&self, callback: F) -> Result<()>
+    where
+        F: Fn(Option<String>) + Send + 'static,
+    {
+        let content_webview = self.content_webviews.active().ok_or_else(|| {
+            BrowserError::ConfigError("Content WebView not initialized".to_string())
+        })?;
+
+        content_webview
+            .evaluate_script_with_callback("document.title", move |result| {
+                callback(parse_title_result(&result))
+            })
+            .map_err(|e| BrowserError::IpcError(format!("Content script eval failed: {}", e)))
+    }
+
     /// Get reference to chrome WebView
     pub fn chrome_webview(&self) -> &Rc<WebView> {
         &self.chrome_webview
     }
 
-    /// Get reference to content WebView if exists
-    pub fn content_webview(&self) -> Option<&WebView> {
-        self.content_webview.as_ref()
+    /// Get reference to the active tab's content WebView if exists
+    pub fn active_content_webview(&self) -> Option<&WebView> {
+        self.content_webviews.active().map(|webview| &**webview)
+    }
+
+    /// Get reference to a tab's content WebView
+    pub fn content_webview_for(&self, tab_id: TabId) -> Option<&WebView> {
+        self.content_webviews.get(tab_id).map(|webview| &**webview)
     }
 
-    /// Check if content WebView is created
+    /// Step the active tab's history back, before its WebView goes back
+    ///
+    /// Lets the finished load restore the entry's scroll offset.
+    pub fn begin_back(&self) {
+        if let Some(history) = self.active_history() {
+            history.borrow_mut().go_back();
+        }
+    }
+
+    /// Step the active tab's history forward, before its WebView goes forward
+    pub fn begin_forward(&self) {
+        if let Some(history) = self.active_history() {
+            history.borrow_mut().go_forward();
+        }
+    }
+
+    fn active_history(&self) -> Option<&Rc<RefCell<TabHistory>>> {
+        self.histories.get(&self.content_webviews.active?)
+    }
+
+    /// ID of the tab whose content is shown
+    pub fn active_tab(&self) -> Option<TabId> {
+        self.content_webviews.active
+    }
+
+    /// Number of content WebViews
+    pub fn content_tab_count(&self) -> usize {
+        self.content_webviews.len()
+    }
+
+    /// Check if an active content WebView exists
     pub fn has_content_webview(&self) -> bool {
-        self.content_webview.is_some()
+        self.content_webviews.active().is_some()
     }
 
     /// Get chrome height configuration
@@ -244,6 +929,26 @@ mod tests {
         let config = WebViewConfig::default();
         assert_eq!(config.chrome_height, 88.0);
         assert_eq!(config.initial_url, "https://example.com");
+        assert!(!config.private);
+    }
+
+    #[test]
+    fn test_origin_of() {
+        assert_eq!(
+            origin_of("https://Example.com/path?q=1").as_deref(),
+            Some("https://example.com")
+        );
+        assert_eq!(
+            origin_of("http://localhost:8080/").as_deref(),
+            Some("http://localhost:8080")
+        );
+        // Default ports are dropped, so both spellings share a zoom level
+        assert_eq!(
+            origin_of("https://example.com:443/"),
+            origin_of("https://example.com/")
+        );
+        assert_eq!(origin_of("about:blank"), None);
+        assert_eq!(origin_of("not a url"), None);
     }
 
     #[test]
@@ -252,11 +957,314 @@ mod tests {
             chrome_height: 100.0,
             devtools_enabled: true,
             initial_url: "https://custom.com".to_string(),
+            private: true,
         };
         assert_eq!(config.chrome_height, 100.0);
         assert!(config.devtools_enabled);
     }
 
+    fn logical_bounds(bounds: Rect) -> (f64, f64, f64, f64) {
+        match (bounds.position, bounds.size) {
+            (wry::dpi::Position::Logical(position), wry::dpi::Size::Logical(size)) => {
+                (position.x, position.y, size.width, size.height)
+            }
+            _ => panic!("expected logical bounds"),
+        }
+    }
+
+    #[test]
+    fn test_content_bounds_below_chrome() {
+        let config = WebViewConfig::default();
+        assert_eq!(
+            logical_bounds(config.content_bounds(1200, 800)),
+            (0.0, 88.0, 1200.0, 712.0)
+        );
+
+        // Window shorter than the chrome leaves no room, not negative height
+        assert_eq!(
+            logical_bounds(config.content_bounds(1200, 50)),
+            (0.0, 88.0, 1200.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_content_bounds_fullscreen() {
+        let mut config = WebViewConfig {
+            chrome_height: 40.0,
+            ..WebViewConfig::default()
+        };
+        assert_eq!(
+            logical_bounds(config.content_bounds(1200, 800)),
+            (0.0, 40.0, 1200.0, 760.0)
+        );
+
+        config.chrome_height = 0.0;
+        assert_eq!(
+            logical_bounds(config.content_bounds(1200, 800)),
+            (0.0, 0.0, 1200.0, 800.0)
+        );
+    }
+
+    #[test]
+    fn test_content_views_create_and_switch() {
+        let ids = TabIdGenerator::new();
+        let mut views = ContentViews::new();
+        let first = ids.next_id();
+        let second = ids.next_id();
+        views.insert(first, "first");
+        views.insert(second, "second");
+        assert_eq!(views.len(), 2);
+        assert_eq!(views.active(), None);
+
+        assert!(views.activate(first));
+        assert_eq!(views.active(), Some(&"first"));
+        assert_eq!(views.inactive().collect::<Vec<_>>(), vec![&"second"]);
+
+        assert!(views.activate(second));
+        assert_eq!(views.active(), Some(&"second"));
+        assert_eq!(views.inactive().collect::<Vec<_>>(), vec![&"first"]);
+
+        assert!(!views.activate(ids.next_id()));
+        assert_eq!(views.active, Some(second));
+    }
+
+    /// Records focus calls into a log shared by all views
+    struct RecordingView {
+        name: &'static str,
+        calls: Rc<std::cell::RefCell<Vec<String>>>,
+    }
+
+    impl Focusable for RecordingView {
+        fn focus(&self) -> Result<()> {
+            self.calls.borrow_mut().push(format!("focus {}", self.name));
+            Ok(())
+        }
+
+        fn blur(&self) -> Result<()> {
+            self.calls.borrow_mut().push(format!("blur {}", self.name));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_tab_switch_moves_focus() {
+        let ids = TabIdGenerator::new();
+        let calls = Rc::new(std::cell::RefCell::new(Vec::new()));
+        let mut views = ContentViews::new();
+        let tabs: Vec<TabId> = ["first", "second", "third"]
+            .into_iter()
+            .map(|name| {
+                let id = ids.next_id();
+                let calls = calls.clone();
+                views.insert(id, RecordingView { name, calls });
+                id
+            })
+            .collect();
+
+        views.activate(tabs[1]);
+        views.focus_active().unwrap();
+        let mut switched = calls.take();
+        // The active view is focused last, after the others are blurred
+        assert_eq!(switched.pop().as_deref(), Some("focus second"));
+        switched.sort();
+        assert_eq!(switched, vec!["blur first", "blur third"]);
+
+        // Hidden content keeps its focus off the page
+        views.set_visible(false);
+        views.activate(tabs[0]);
+        views.focus_active().unwrap();
+        assert!(calls.take().iter().all(|call| call.starts_with("blur")));
+    }
+
+    #[test]
+    fn test_content_views_close() {
+        let ids = TabIdGenerator::new();
+        let mut views = ContentViews::new();
+        let first = ids.next_id();
+        let second = ids.next_id();
+        views.insert(first, "first");
+        views.insert(second, "second");
+        views.activate(second);
+
+        // Closing a background tab keeps the active one
+        assert_eq!(views.remove(first), Some("first"));
+        assert_eq!(views.active, Some(second));
+
+        // Closing the active tab leaves nothing shown
+        assert_eq!(views.remove(second), Some("second"));
+        assert_eq!(views.active, None);
+        assert_eq!(views.len(), 0);
+        assert_eq!(views.remove(second), None);
+    }
+
+    #[test]
+    fn test_content_views_remove_inactive() {
+        let ids = TabIdGenerator::new();
+        let mut views = ContentViews::new();
+        let tabs: Vec<TabId> = (0..3).map(|_| ids.next_id()).collect();
+        for (id, name) in tabs.iter().zip(["first", "second", "third"]) {
+            views.insert(*id, name);
+        }
+        views.activate(tabs[1]);
+
+        assert_eq!(views.remove_inactive(), vec![tabs[0], tabs[2]]);
+        assert_eq!(views.len(), 1);
+        assert_eq!(views.active(), Some(&"second"));
+        assert!(views.remove_inactive().is_empty());
+    }
+
+    #[test]
+    fn test_content_visibility_tracking() {
+        let ids = TabIdGenerator::new();
+        let mut views = ContentViews::new();
+        assert!(views.is_visible());
+
+        views.set_visible(false);
+        assert!(!views.is_visible());
+
+        // Visibility belongs to the content area, not a tab
+        let tab = ids.next_id();
+        views.insert(tab, "tab");
+        views.activate(tab);
+        assert!(!views.is_visible());
+
+        views.set_visible(true);
+        assert!(views.is_visible());
+    }
+
+    #[test]
+    fn test_reshow_bounds_follow_config() {
+        // Bounds restored on show come from the config at that time
+        let mut config = WebViewConfig::default();
+        let before = logical_bounds(config.content_bounds(1000, 700));
+        config.chrome_height = 0.0;
+        let after = logical_bounds(config.content_bounds(1000, 700));
+        assert_eq!(before, (0.0, 88.0, 1000.0, 612.0));
+        assert_eq!(after, (0.0, 0.0, 1000.0, 700.0));
+    }
+
+    #[test]
+    fn test_tab_title() {
+        assert_eq!(
+            tab_title("Example Domain", "https://example.com/"),
+            "Example Domain"
+        );
+        assert_eq!(tab_title("  Padded  ", "https://example.com/"), "Padded");
+        assert_eq!(
+            tab_title("", "https://www.rust-lang.org/learn"),
+            "www.rust-lang.org"
+        );
+        assert_eq!(tab_title("   ", "about:blank"), "New Tab");
+    }
+
+    #[test]
+    fn test_parse_title_result() {
+        assert_eq!(
+            parse_title_result("\"Example Domain\"").as_deref(),
+            Some("Example Domain")
+        );
+        assert_eq!(parse_title_result("\"\""), None);
+        assert_eq!(parse_title_result("null"), None);
+    }
+
+    #[test]
+    fn test_parse_nav_timing() {
+        let timing = parse_nav_timing(
+            r#"{"type":"navtiming","dns":12.5,"connect":20,"tls":30,"ttfb":80,"total":450}"#,
+        )
+        .unwrap();
+        assert_eq!(timing.dns, Duration::from_micros(12_500));
+        assert_eq!(timing.connect, Duration::from_millis(20));
+        assert_eq!(timing.tls, Some(Duration::from_millis(30)));
+        assert_eq!(timing.ttfb, Duration::from_millis(80));
+        assert_eq!(timing.total, Duration::from_millis(450));
+
+        // Reused connection over plain http
+        let timing = parse_nav_timing(
+            r#"{"type":"navtiming","dns":0,"connect":-1,"tls":null,"ttfb":5,"total":40}"#,
+        )
+        .unwrap();
+        assert_eq!(timing.connect, Duration::ZERO);
+        assert_eq!(timing.tls, None);
+
+        assert!(parse_nav_timing(r#"{"type":"scroll","x":0,"y":0}"#).is_none());
+        assert!(parse_nav_timing("not json").is_none());
+    }
+
+    #[test]
+    fn test_title_updates_tab_state() {
+        let mut state = StateManager::new();
+        let tab = state.create_tab("https://example.com/".to_string());
+
+        tab_title_changed(&mut state, tab, "");
+        assert_eq!(state.get_tab(tab).unwrap().title, "example.com");
+        assert_eq!(
+            tab_title_changed(&mut state, tab, "Example Domain"),
+            ChromeEvent::TitleChanged {
+                tab,
+                title: "Example Domain".to_string()
+            }
+        );
+        assert_eq!(state.get_tab(tab).unwrap().title, "Example Domain");
+    }
+
+    #[test]
+    fn test_active_tab_navigation_updates_url_bar() {
+        let mut state = StateManager::new();
+        let tab = state.create_tab("https://example.com/".to_string());
+
+        assert_eq!(
+            tab_navigated(&mut state, tab, "https://rust-lang.org/"),
+            vec![
+                ChromeEvent::UrlChanged {
+                    tab,
+                    url: "https://rust-lang.org/".to_string()
+                },
+                ChromeEvent::TitleChanged {
+                    tab,
+                    title: "rust-lang.org".to_string()
+                },
+            ]
+        );
+        assert_eq!(state.get_tab(tab).unwrap().url, "https://rust-lang.org/");
+    }
+
+    #[test]
+    fn test_background_tab_leaves_url_bar_alone() {
+        let mut state = StateManager::new();
+        let background = state.create_tab("https://example.com/".to_string());
+        let active = state.create_tab("https://active.example/".to_string());
+        state.switch_tab(active).unwrap();
+
+        // A background page redirecting doesn't move the URL bar
+        let events = tab_navigated(&mut state, background, "https://example.org/");
+        assert!(events
+            .iter()
+            .all(|event| !matches!(event, ChromeEvent::UrlChanged { .. })));
+        assert_eq!(
+            events,
+            vec![ChromeEvent::TitleChanged {
+                tab: background,
+                title: "example.org".to_string()
+            }]
+        );
+
+        // Its title goes to its own tab only
+        let event = tab_title_changed(&mut state, background, "Example Org");
+        assert_eq!(
+            event,
+            ChromeEvent::TitleChanged {
+                tab: background,
+                title: "Example Org".to_string()
+            }
+        );
+        assert_eq!(state.get_tab(background).unwrap().title, "Example Org");
+        let active_tab = state.get_tab(active).unwrap();
+        assert_eq!(active_tab.url, "https://active.example/");
+        assert_ne!(active_tab.title, "Example Org");
+        assert_eq!(state.active_tab_id(), Some(active));
+    }
+
     // Note: WebView creation tests require a window, which needs event loop.
     // These would be integration tests in tests/integration/
 }
@@ -0,0 +1,233 @@
+//! Window geometry saved across runs
+//!
+//! The window's size, position and maximized state are saved as JSON in
+//! the `window_state` setting when it closes, and restored on the next
+//! start. Restored geometry is clamped to the monitors present, so a
+//! window last shown on a disconnected monitor opens on one that exists.
+//!
+//! Values are physical pixels, as reported by tao.
+
+use crate::error::{BrowserError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use storage::Database;
+
+/// Settings key holding the geometry as JSON
+pub const WINDOW_STATE_SETTING: &str = "window_state";
+
+/// Smallest size a restored window is given, monitor permitting
+pub const MIN_WINDOW_SIZE: (u32, u32) = (400, 300);
+
+/// Window size, position and maximized state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    /// Left edge of the window frame
+    pub x: i32,
+    /// Top edge of the window frame
+    pub y: i32,
+    /// Inner width
+    pub width: u32,
+    /// Inner height
+    pub height: u32,
+    /// Maximized; the other fields are the size to restore to
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+/// A monitor's area on the virtual desktop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScreenRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ScreenRect {
+    /// Area shared with `geometry`, zero if they don't overlap
+    fn overlap(&self, geometry: &WindowGeometry) -> u64 {
+        let overlap = |start: i32, len: u32, other_start: i32, other_len: u32| {
+            let end = (start as i64 + len as i64).min(other_start as i64 + other_len as i64);
+            (end - (start as i64).max(other_start as i64)).max(0) as u64
+        };
+        overlap(self.x, self.width, geometry.x, geometry.width)
+            * overlap(self.y, self.height, geometry.y, geometry.height)
+    }
+}
+
+impl WindowGeometry {
+    /// Fit the window on the monitor it overlaps most
+    ///
+    /// A window on none of `monitors` moves to the first one, which
+    /// should be the primary monitor. The window is shrunk to the
+    /// monitor if it's larger, then moved fully onto it. Without any
+    /// monitors the geometry is returned unchanged.
+    pub fn clamp_to(self, monitors: &[ScreenRect]) -> Self {
+        let Some(first) = monitors.first() else {
+            return self;
+        };
+        let monitor = monitors
+            .iter()
+            .max_by_key(|monitor| monitor.overlap(&self))
+            .filter(|monitor| monitor.overlap(&self) > 0)
+            .unwrap_or(first);
+
+        let fit = |len: u32, min: u32, available: u32| len.max(min.min(available)).min(available);
+        let width = fit(self.width, MIN_WINDOW_SIZE.0, monitor.width);
+        let height = fit(self.height, MIN_WINDOW_SIZE.1, monitor.height);
+
+        let place = |pos: i32, len: u32, start: i32, available: u32| {
+            let last = start as i64 + available as i64 - len as i64;
+            (pos as i64).clamp(start as i64, last) as i32
+        };
+        Self {
+            x: place(self.x, width, monitor.x, monitor.width),
+            y: place(self.y, height, monitor.y, monitor.height),
+            width,
+            height,
+            maximized: self.maximized,
+        }
+    }
+
+    /// Load the geometry saved in the `window_state` setting
+    ///
+    /// Returns None if none was saved or the database doesn't exist yet.
+    ///
+    /// # Errors
+    /// Returns `BrowserError::Database` if the setting can't be read, or
+    /// `BrowserError::ConfigError` if it isn't valid geometry
+    pub fn load(db_path: &Path) -> Result<Option<Self>> {
+        if !db_path.exists() {
+            return Ok(None);
+        }
+        let db =
+            Database::open_readonly(db_path).map_err(|e| BrowserError::Database(e.to_string()))?;
+        let Some(json) = db
+            .get_setting(WINDOW_STATE_SETTING)
+            .map_err(|e| BrowserError::Database(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+
+        serde_json::from_str(&json).map(Some).map_err(|e| {
+            BrowserError::ConfigError(format!("Invalid {} setting: {}", WINDOW_STATE_SETTING, e))
+        })
+    }
+
+    /// Save the geometry to the `window_state` setting
+    ///
+    /// # Errors
+    /// Returns `BrowserError::Database` if the setting can't be written
+    pub fn save(&self, db_path: &Path) -> Result<()> {
+        let json = serde_json::to_string(self)
+            .map_err(|e| BrowserError::Unexpected(format!("Can't serialize geometry: {}", e)))?;
+        let db = Database::new(db_path).map_err(|e| BrowserError::Database(e.to_string()))?;
+        db.set_setting(WINDOW_STATE_SETTING, &json)
+            .map_err(|e| BrowserError::Database(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    const LAPTOP: ScreenRect = ScreenRect {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+    };
+    const EXTERNAL: ScreenRect = ScreenRect {
+        x: 1920,
+        y: 0,
+        width: 2560,
+        height: 1440,
+    };
+
+    fn geometry(x: i32, y: i32, width: u32, height: u32) -> WindowGeometry {
+        WindowGeometry {
+            x,
+            y,
+            width,
+            height,
+            maximized: false,
+        }
+    }
+
+    #[test]
+    fn test_clamp_keeps_visible_window() {
+        let on_laptop = geometry(100, 50, 1280, 800);
+        assert_eq!(on_laptop.clamp_to(&[LAPTOP, EXTERNAL]), on_laptop);
+
+        let on_external = geometry(2200, 100, 2000, 1200);
+        assert_eq!(on_external.clamp_to(&[LAPTOP, EXTERNAL]), on_external);
+
+        // Nothing to clamp to
+        assert_eq!(on_external.clamp_to(&[]), on_external);
+    }
+
+    #[test]
+    fn test_clamp_moves_window_off_disconnected_monitor() {
+        let on_external = WindowGeometry {
+            maximized: true,
+            ..geometry(2200, 100, 2000, 1200)
+        };
+        assert_eq!(
+            on_external.clamp_to(&[LAPTOP]),
+            WindowGeometry {
+                maximized: true,
+                ..geometry(0, 0, 1920, 1080)
+            }
+        );
+
+        assert_eq!(
+            geometry(-5000, -5000, 800, 600).clamp_to(&[LAPTOP]),
+            geometry(0, 0, 800, 600)
+        );
+    }
+
+    #[test]
+    fn test_clamp_fits_partly_offscreen_window() {
+        // Hanging off the right and bottom edges
+        assert_eq!(
+            geometry(1500, 900, 800, 600).clamp_to(&[LAPTOP]),
+            geometry(1120, 480, 800, 600)
+        );
+
+        // Straddling two monitors: goes to the one with more of it
+        assert_eq!(
+            geometry(1800, 0, 1000, 700).clamp_to(&[LAPTOP, EXTERNAL]),
+            geometry(1920, 0, 1000, 700)
+        );
+
+        // Too small to use is grown, too big is shrunk
+        assert_eq!(
+            geometry(10, 10, 50, 5000).clamp_to(&[LAPTOP]),
+            geometry(10, 0, MIN_WINDOW_SIZE.0, 1080)
+        );
+    }
+
+    #[test]
+    fn test_geometry_round_trip() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+        assert_eq!(WindowGeometry::load(temp_file.path()).unwrap(), None);
+
+        let saved = WindowGeometry {
+            maximized: true,
+            ..geometry(-1200, 40, 1100, 700)
+        };
+        saved.save(temp_file.path()).unwrap();
+        assert_eq!(WindowGeometry::load(temp_file.path()).unwrap(), Some(saved));
+
+        db.set_setting(WINDOW_STATE_SETTING, r#"{"x":1}"#).unwrap();
+        assert!(matches!(
+            WindowGeometry::load(temp_file.path()),
+            Err(BrowserError::ConfigError(_))
+        ));
+
+        let missing = temp_file.path().with_extension("missing");
+        assert_eq!(WindowGeometry::load(&missing).unwrap(), None);
+    }
+}
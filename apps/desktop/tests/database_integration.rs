@@ -34,13 +34,13 @@ fn test_history_persistence_across_connections() {
         let history = db.get_recent_history(10).unwrap();
         assert_eq!(history.len(), 3);
 
-        assert_eq!(history[0].url, "https://google.com");
+        assert_eq!(history[0].url, "https://google.com/");
         assert_eq!(history[0].title, Some("Google".to_string()));
 
-        assert_eq!(history[1].url, "https://github.com");
+        assert_eq!(history[1].url, "https://github.com/");
         assert_eq!(history[1].title, Some("GitHub".to_string()));
 
-        assert_eq!(history[2].url, "https://example.com");
+        assert_eq!(history[2].url, "https://example.com/");
         assert_eq!(history[2].title, Some("Example Domain".to_string()));
     }
 }
@@ -104,11 +104,11 @@ fn test_history_limit_enforcement() {
     assert_eq!(history.len(), 5);
 
     // Verify newest entries returned (example19, example18, ..., example15)
-    assert_eq!(history[0].url, "https://example19.com");
-    assert_eq!(history[1].url, "https://example18.com");
-    assert_eq!(history[2].url, "https://example17.com");
-    assert_eq!(history[3].url, "https://example16.com");
-    assert_eq!(history[4].url, "https://example15.com");
+    assert_eq!(history[0].url, "https://example19.com/");
+    assert_eq!(history[1].url, "https://example18.com/");
+    assert_eq!(history[2].url, "https://example17.com/");
+    assert_eq!(history[3].url, "https://example16.com/");
+    assert_eq!(history[4].url, "https://example15.com/");
 
     // Assert: get_history(30) returns all 20 entries
     let history = db.get_recent_history(30).unwrap();
@@ -136,7 +136,7 @@ fn test_history_chronological_order() {
     assert_eq!(history.len(), 5);
 
     for i in 0..5 {
-        let expected_url = format!("https://example{}.com", 4 - i); // Reverse order
+        let expected_url = format!("https://example{}.com/", 4 - i); // Reverse order
         assert_eq!(history[i].url, expected_url);
     }
 }
@@ -200,6 +200,69 @@ fn test_concurrent_reads_and_writes() {
     assert_eq!(history.len(), 35);
 }
 
+#[test]
+fn test_readonly_connection_sees_persisted_data() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db_path = temp_file.path();
+
+    {
+        let db = Database::new(db_path).unwrap();
+        db.add_history("https://example.com", Some("Example Domain"))
+            .unwrap();
+        db.set_setting("theme", "dark").unwrap();
+    }
+
+    let db = Database::open_readonly(db_path).unwrap();
+    let history = db.get_recent_history(10).unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].url, "https://example.com/");
+    assert_eq!(db.get_setting("theme").unwrap(), Some("dark".to_string()));
+
+    // Writes fail and leave the data alone
+    assert!(db.add_history("https://github.com", None).is_err());
+    assert_eq!(db.get_recent_history(10).unwrap().len(), 1);
+}
+
+#[test]
+fn test_readonly_readers_alongside_writer() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db_path = temp_file.path().to_path_buf();
+    Database::new(&db_path)
+        .unwrap()
+        .add_history("https://initial.com", None)
+        .unwrap();
+
+    let writer = {
+        let db_path = db_path.clone();
+        thread::spawn(move || {
+            let db = Database::new(&db_path).unwrap();
+            for i in 0..20 {
+                db.add_history(&format!("https://write{}.com", i), None)
+                    .unwrap();
+            }
+        })
+    };
+
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let db_path = db_path.clone();
+            thread::spawn(move || {
+                let db = Database::open_readonly(&db_path).unwrap();
+                for _ in 0..10 {
+                    assert!(!db.get_recent_history(50).unwrap().is_empty());
+                }
+            })
+        })
+        .collect();
+
+    for handle in std::iter::once(writer).chain(readers) {
+        handle.join().unwrap();
+    }
+
+    let db = Database::open_readonly(&db_path).unwrap();
+    assert_eq!(db.get_recent_history(50).unwrap().len(), 21);
+}
+
 #[test]
 fn test_history_without_title() {
     // Arrange
@@ -212,7 +275,7 @@ fn test_history_without_title() {
     // Assert: Entry should exist with None title
     let history = db.get_recent_history(10).unwrap();
     assert_eq!(history.len(), 1);
-    assert_eq!(history[0].url, "https://example.com");
+    assert_eq!(history[0].url, "https://example.com/");
     assert_eq!(history[0].title, None);
 }
 
@@ -299,11 +362,11 @@ fn test_stress_test_many_entries() {
     assert_eq!(history.len(), 1000);
 
     // Verify newest entry first
-    assert_eq!(history[0].url, "https://example999.com");
-    assert_eq!(history[999].url, "https://example0.com");
+    assert_eq!(history[0].url, "https://example999.com/");
+    assert_eq!(history[999].url, "https://example0.com/");
 
     // Verify limited query works
     let limited = db.get_recent_history(10).unwrap();
     assert_eq!(limited.len(), 10);
-    assert_eq!(limited[0].url, "https://example999.com");
+    assert_eq!(limited[0].url, "https://example999.com/");
 }
@@ -0,0 +1,245 @@
+//! Lightweight HTML metadata extraction
+//!
+//! Just enough tag scanning to pull metadata out of a fetched page without
+//! building a DOM.
+
+/// Extract a page's title from its HTML
+///
+/// Uses `<title>`, falling back to the `og:title` meta tag. Entities are
+/// decoded and whitespace collapsed. Returns None if neither is present or
+/// both are blank.
+pub fn extract_title(html: &str) -> Option<String> {
+    title_element(html)
+        .or_else(|| og_title(html))
+        .map(|title| collapse_whitespace(&decode_entities(&title)))
+        .filter(|title| !title.is_empty())
+}
+
+/// Text of the first `<title>` element
+fn title_element(html: &str) -> Option<String> {
+    // ASCII lowercasing keeps byte offsets valid for `html`
+    let lower = html.to_ascii_lowercase();
+    let mut from = 0;
+
+    while let Some(offset) = lower[from..].find("<title") {
+        let open = from + offset;
+        let after_name = open + "<title".len();
+        from = after_name;
+
+        // Not `<titlefoo>`
+        if !lower[after_name..].starts_with(['>', ' ', '\t', '\n', '\r', '/']) {
+            continue;
+        }
+
+        let start = after_name + lower[after_name..].find('>')? + 1;
+        let end = start + lower[start..].find("</title")?;
+        let title = &html[start..end];
+        if !title.trim().is_empty() {
+            return Some(title.to_string());
+        }
+    }
+
+    None
+}
+
+/// `content` of the first `og:title` meta tag
+fn og_title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let mut from = 0;
+
+    while let Some(offset) = lower[from..].find("<meta") {
+        let start = from + offset + "<meta".len();
+        let end = start + lower[start..].find('>')?;
+        from = end;
+
+        let attributes = parse_attributes(&html[start..end]);
+        let is_og_title = attributes.iter().any(|(name, value)| {
+            (name == "property" || name == "name") && value.eq_ignore_ascii_case("og:title")
+        });
+        if is_og_title {
+            if let Some((_, content)) = attributes.into_iter().find(|(name, _)| name == "content") {
+                return Some(content);
+            }
+        }
+    }
+
+    None
+}
+
+/// Attributes of a tag, given the text between its name and `>`
+///
+/// Names are lowercased. Handles double-, single- and unquoted values.
+fn parse_attributes(tag: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut rest = tag.trim_start_matches('/');
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        if rest.is_empty() {
+            break;
+        }
+
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '/')
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let Some(after_eq) = rest.strip_prefix('=') else {
+            attributes.push((name, String::new()));
+            continue;
+        };
+        let after_eq = after_eq.trim_start();
+
+        let (value, remaining) = match after_eq.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let inner = &after_eq[1..];
+                match inner.find(quote) {
+                    Some(close) => (&inner[..close], &inner[close + 1..]),
+                    None => (inner, ""),
+                }
+            }
+            _ => {
+                let end = after_eq.find(char::is_whitespace).unwrap_or(after_eq.len());
+                (&after_eq[..end], &after_eq[end..])
+            }
+        };
+        attributes.push((name, value.to_string()));
+        rest = remaining;
+    }
+
+    attributes
+}
+
+/// Decode numeric and common named character references
+///
+/// Unknown or malformed references are left as written.
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let entity = rest[1..]
+            .find(';')
+            .filter(|&len| len > 0 && len <= 10)
+            .and_then(|len| decode_entity(&rest[1..=len]).map(|c| (c, len + 2)));
+
+        match entity {
+            Some((c, consumed)) => {
+                decoded.push(c);
+                rest = &rest[consumed..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Character for an entity name, without `&` and `;`
+fn decode_entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code).filter(|&c| c != '\0');
+    }
+
+    let c = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "middot" => '·',
+        "bull" => '•',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        _ => return None,
+    };
+    Some(c)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_element() {
+        let html = r#"<!DOCTYPE html>
+            <html><head>
+                <meta property="og:title" content="Social Title">
+                <TITLE lang="en">
+                    Example   Domain
+                </TITLE>
+            </head><body></body></html>"#;
+
+        assert_eq!(extract_title(html).as_deref(), Some("Example Domain"));
+    }
+
+    #[test]
+    fn test_og_title_fallback() {
+        let html = r#"<html><head>
+            <meta charset="utf-8">
+            <meta content='Rust &amp; WebAssembly' property='og:title' />
+        </head></html>"#;
+
+        assert_eq!(extract_title(html).as_deref(), Some("Rust & WebAssembly"));
+
+        // Blank <title> falls back too
+        let html = r#"<title>  </title><meta name="og:title" content="Fallback">"#;
+        assert_eq!(extract_title(html).as_deref(), Some("Fallback"));
+    }
+
+    #[test]
+    fn test_no_title() {
+        assert_eq!(extract_title(""), None);
+        assert_eq!(extract_title("<html><body><h1>Hi</h1></body></html>"), None);
+        assert_eq!(extract_title("<titlebar>Not a title</titlebar>"), None);
+        assert_eq!(
+            extract_title(r#"<meta property="og:title" content="">"#),
+            None
+        );
+        // Unterminated
+        assert_eq!(extract_title("<title>Forever"), None);
+    }
+
+    #[test]
+    fn test_entities_decoded() {
+        let html = "<title>Q&amp;A &#8211; Tom&#x27;s &quot;Blog&quot; &mdash; &copy; 2024</title>";
+        assert_eq!(
+            extract_title(html).as_deref(),
+            Some("Q&A – Tom's \"Blog\" — © 2024")
+        );
+
+        // Unknown and malformed references are kept
+        let html = "<title>AT&T &bogus; &#xZZ; fish & chips</title>";
+        assert_eq!(
+            extract_title(html).as_deref(),
+            Some("AT&T &bogus; &#xZZ; fish & chips")
+        );
+    }
+}
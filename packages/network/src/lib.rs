@@ -3,8 +3,13 @@
 //! HTTP/HTTPS client with caching and DNS resolution.
 
 use anyhow::Result;
+use std::time::Duration;
+use tracing::debug;
 use url::Url;
 
+mod html;
+pub use html::extract_title;
+
 /// HTTP client for fetching web resources
 pub struct HttpClient {
     client: reqwest::Client,
@@ -26,8 +31,62 @@ impl HttpClient {
         let body = response.text().await?;
         Ok(body)
     }
+
+    /// Fetch a URL and return the raw response body, undecoded
+    ///
+    /// For callers that pick the text encoding themselves.
+    pub async fn fetch_bytes(&self, url: Url) -> Result<Vec<u8>> {
+        let response = self.client.get(url).send().await?;
+        let body = response.bytes().await?;
+        Ok(body.to_vec())
+    }
+
+    /// Whether the server at `url` answers within `timeout`
+    ///
+    /// Sends a HEAD request. Any HTTP response counts, error statuses
+    /// included; DNS, connection and TLS failures don't.
+    pub async fn probe(&self, url: Url, timeout: Duration) -> bool {
+        self.client.head(url).timeout(timeout).send().await.is_ok()
+    }
+
+    /// Warm a connection to `url`'s origin for a navigation that's likely
+    /// to follow
+    ///
+    /// Resolves the host and opens the TCP (and TLS) connection in the
+    /// background with a HEAD request to the origin. The connection goes
+    /// back to the pool, so a later request to the same origin reuses it.
+    /// Failures are only logged; without a Tokio runtime this does nothing.
+    pub fn preconnect(&self, url: &Url) {
+        if !matches!(url.scheme(), "http" | "https") {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            debug!("No runtime to preconnect to {}", url);
+            return;
+        };
+
+        let mut origin = url.clone();
+        origin.set_path("/");
+        origin.set_query(None);
+        origin.set_fragment(None);
+
+        let client = self.client.clone();
+        runtime.spawn(async move {
+            if let Err(e) = client
+                .head(origin.clone())
+                .timeout(PRECONNECT_TIMEOUT)
+                .send()
+                .await
+            {
+                debug!("Preconnect to {} failed: {}", origin, e);
+            }
+        });
+    }
 }
 
+/// How long a preconnect may take before it's abandoned
+const PRECONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
 impl Default for HttpClient {
     fn default() -> Self {
         Self::new().expect("Failed to create default HTTP client")
@@ -37,6 +96,10 @@ impl Default for HttpClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
 
     #[test]
     fn test_client_creation() {
@@ -50,4 +113,79 @@ mod tests {
         let body = client.fetch(url).await.unwrap();
         assert!(body.contains("Example Domain"));
     }
+
+    /// Local HTTP/1.1 server counting connections and requests
+    async fn counting_server() -> (Url, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}/page", listener.local_addr().unwrap())).unwrap();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let (accepted, served) = (connections.clone(), requests.clone());
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                accepted.fetch_add(1, Ordering::SeqCst);
+                let served = served.clone();
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 1024];
+                    loop {
+                        let Ok(n) = stream.read(&mut chunk).await else {
+                            return;
+                        };
+                        if n == 0 {
+                            return;
+                        }
+                        buf.extend_from_slice(&chunk[..n]);
+                        while let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                            let head = buf.drain(..end + 4).collect::<Vec<_>>();
+                            served.fetch_add(1, Ordering::SeqCst);
+                            let body = if head.starts_with(b"HEAD") {
+                                ""
+                            } else {
+                                "hello"
+                            };
+                            let response =
+                                format!("HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\n{}", body);
+                            stream.write_all(response.as_bytes()).await.unwrap();
+                        }
+                    }
+                });
+            }
+        });
+
+        (url, connections, requests)
+    }
+
+    #[tokio::test]
+    async fn test_preconnect_warms_pool() {
+        let (url, connections, requests) = counting_server().await;
+        let client = HttpClient::new().unwrap();
+
+        client.preconnect(&url);
+        for _ in 0..100 {
+            if requests.load(Ordering::SeqCst) == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        // Let the connection return to the pool
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert_eq!(client.fetch(url).await.unwrap(), "hello");
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_preconnect_never_fails() {
+        let client = HttpClient::new().unwrap();
+
+        // No runtime
+        client.preconnect(&Url::parse("https://example.com").unwrap());
+        // Not HTTP
+        client.preconnect(&Url::parse("file:///tmp/page.html").unwrap());
+    }
 }
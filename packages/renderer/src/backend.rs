@@ -0,0 +1,208 @@
+//! Backend-independent renderer interface
+//!
+//! `Renderer` is the surface the browser needs from a rendering engine.
+//! Backends implement what they can; the rest returns
+//! `RendererError::NotSupported` (see the default methods).
+//!
+//! wry is the only backend today.
+
+use crate::{RenderedFrame, RendererConfig, RendererError, Result, WryRenderer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Navigation and page state shared by rendering backends
+pub trait Renderer {
+    /// Start loading `url`
+    fn load_url(&mut self, url: &str) -> Result<()>;
+
+    /// Reload the current page
+    fn reload(&mut self) -> Result<()>;
+
+    /// Stop the current load
+    fn stop(&mut self) -> Result<()>;
+
+    /// Go back one entry in session history
+    fn go_back(&mut self) -> Result<()>;
+
+    /// Go forward one entry in session history
+    fn go_forward(&mut self) -> Result<()>;
+
+    /// Whether `go_back()` has somewhere to go
+    fn can_go_back(&self) -> bool;
+
+    /// Whether `go_forward()` has somewhere to go
+    fn can_go_forward(&self) -> bool;
+
+    /// Whether a page load is in progress
+    fn is_loading(&self) -> bool;
+
+    /// Load progress of the current page, from 0.0 to 1.0
+    fn get_progress(&self) -> f32;
+
+    /// Current URL, None before the first navigation
+    fn get_url(&self) -> Option<String>;
+
+    /// Current page title, None if not known yet
+    fn get_title(&self) -> Option<String>;
+
+    /// Latest rendered frame, for backends that render to pixels
+    ///
+    /// # Errors
+    ///
+    /// Returns `RendererError::NotSupported` unless the backend overrides it
+    fn get_frame(&self) -> Result<RenderedFrame> {
+        Err(RendererError::NotSupported(
+            "frame readback (this backend draws to the window)".to_string(),
+        ))
+    }
+
+    /// Release the backend's resources
+    fn shutdown(&mut self);
+}
+
+impl Renderer for WryRenderer {
+    fn load_url(&mut self, url: &str) -> Result<()> {
+        WryRenderer::load_url(self, url)
+    }
+
+    fn reload(&mut self) -> Result<()> {
+        WryRenderer::reload(self)
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        WryRenderer::stop(self)
+    }
+
+    fn go_back(&mut self) -> Result<()> {
+        WryRenderer::go_back(self)
+    }
+
+    fn go_forward(&mut self) -> Result<()> {
+        WryRenderer::go_forward(self)
+    }
+
+    fn can_go_back(&self) -> bool {
+        WryRenderer::can_go_back(self)
+    }
+
+    fn can_go_forward(&self) -> bool {
+        WryRenderer::can_go_forward(self)
+    }
+
+    fn is_loading(&self) -> bool {
+        WryRenderer::is_loading(self)
+    }
+
+    fn get_progress(&self) -> f32 {
+        WryRenderer::get_progress(self)
+    }
+
+    fn get_url(&self) -> Option<String> {
+        WryRenderer::get_url(self)
+    }
+
+    fn get_title(&self) -> Option<String> {
+        WryRenderer::get_title(self)
+    }
+
+    fn shutdown(&mut self) {
+        WryRenderer::shutdown(self)
+    }
+}
+
+/// Rendering backend, as named in config (`"wry"`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RendererBackend {
+    /// Platform WebView through wry
+    #[default]
+    Wry,
+}
+
+impl RendererBackend {
+    /// Create a renderer for this backend
+    ///
+    /// # Errors
+    ///
+    /// Returns the backend's error if it fails to initialize
+    pub fn create(self, config: &RendererConfig) -> Result<Box<dyn Renderer>> {
+        match self {
+            RendererBackend::Wry => Ok(Box::new(WryRenderer::with_config(config)?)),
+        }
+    }
+}
+
+impl FromStr for RendererBackend {
+    type Err = RendererError;
+
+    fn from_str(name: &str) -> Result<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "wry" => Ok(RendererBackend::Wry),
+            other => Err(RendererError::InvalidConfig(format!(
+                "unknown renderer backend: {}",
+                other
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for RendererBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RendererBackend::Wry => write!(f, "wry"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_renderer<R: Renderer>() {}
+
+    #[test]
+    fn test_wry_implements_renderer() {
+        assert_renderer::<WryRenderer>();
+
+        let mut renderer: Box<dyn Renderer> = Box::new(WryRenderer::new().unwrap());
+        assert!(!renderer.is_loading());
+        assert_eq!(renderer.get_url(), None);
+        assert!(matches!(
+            renderer.load_url("https://example.com"),
+            Err(RendererError::NotInitialized)
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_methods() {
+        let renderer = WryRenderer::new().unwrap();
+        assert!(matches!(
+            Renderer::get_frame(&renderer),
+            Err(RendererError::NotSupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_backend_from_config() {
+        assert_eq!(
+            "wry".parse::<RendererBackend>().unwrap(),
+            RendererBackend::Wry
+        );
+        assert_eq!(
+            " Wry ".parse::<RendererBackend>().unwrap(),
+            RendererBackend::Wry
+        );
+        assert!(matches!(
+            "servo".parse::<RendererBackend>(),
+            Err(RendererError::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            "gecko".parse::<RendererBackend>(),
+            Err(RendererError::InvalidConfig(_))
+        ));
+        assert_eq!(RendererBackend::default().to_string(), "wry");
+
+        let config = RendererConfig::default();
+        let renderer = RendererBackend::Wry.create(&config).unwrap();
+        assert!(!renderer.can_go_back());
+    }
+}
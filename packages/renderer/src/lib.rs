@@ -67,9 +67,13 @@
 //!
 //! ## Key Types
 //!
+//! - **`Renderer`**: Trait over rendering backends
+//!   - Navigation and page state shared by every backend
+//!   - `RendererBackend` picks one by name from config
+//!
 //! - **`WryRenderer`**: Main wrapper around wry WebView
 //!   - Manages WebView lifecycle
-//!   - Provides simple load_url() / get_frame() API (future)
+//!   - Implements `Renderer`; `get_frame()` is not supported
 //!   - Handles platform-specific initialization
 //!
 //! - **`RendererConfig`**: Configuration for WebView creation
@@ -78,6 +82,9 @@
 //! - **`RendererError`**: Error types for WebView operations
 //!   - Initialization, navigation, IPC failures
 //!
+//! - **`reader`**: Reader mode article extraction
+//!   - `extract_readable()` strips boilerplate from page HTML
+//!
 //! ## Example Usage
 //!
 //! ```rust,ignore
@@ -126,7 +133,21 @@ use thiserror::Error;
 
 // wry renderer implementation (actual WebView wrapper)
 mod wry_renderer;
-pub use wry_renderer::WryRenderer;
+pub use wry_renderer::{
+    connect_permission_handler, parse_scroll_message, WryRenderer, SCROLL_TRACKING_SCRIPT,
+};
+
+// Backend-independent renderer trait
+mod backend;
+pub use backend::{Renderer, RendererBackend};
+
+// Reader mode article extraction
+pub mod reader;
+pub use reader::{extract_readable, ReaderArticle};
+
+// Content-type sniffing for data: URLs and local files
+pub mod sniff;
+pub use sniff::sniff_mime;
 
 /// Errors that can occur during rendering operations
 #[derive(Debug, Error)]
@@ -147,6 +168,26 @@ pub enum RendererError {
     #[error("WebView creation failed: {0}")]
     WebViewCreationFailed(String),
 
+    /// Renderer configuration is invalid
+    #[error("Invalid renderer config: {0}")]
+    InvalidConfig(String),
+
+    /// Operation not supported by the platform WebView
+    #[error("Not supported on this platform: {0}")]
+    NotSupported(String),
+
+    /// Reader mode found no main article on the page
+    #[error("No readable article found on this page")]
+    NoReadableContent,
+
+    /// Text encoding name not in the WHATWG Encoding Standard
+    #[error("Unknown text encoding: {0}")]
+    UnknownCharset(String),
+
+    /// URL scheme the renderer won't load
+    #[error("URL scheme not allowed: {0}")]
+    SchemeNotAllowed(String),
+
     /// Other errors
     #[error("{0}")]
     Other(String),
@@ -157,7 +198,13 @@ pub type Result<T> = std::result::Result<T, RendererError>;
 
 // Configuration types
 mod types;
-pub use types::RendererConfig;
+pub use types::{
+    ConsoleLevel, ConsoleMessage, ContentPreferences, ContextMenu, ContextMenuContext, DialogKind,
+    DialogRequest, DialogResponse, LoadError, LoadErrorKind, MenuItem, NewWindowAction,
+    PageSnapshot, PdfMargins, PdfOptions, PdfPageSize, PermissionDecision, PermissionKind,
+    PermissionRequest, PixelFormat, RenderedFrame, RendererConfig, RendererConfigBuilder,
+    DEFAULT_MAX_SIZE,
+};
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,446 @@
+//! Reader mode: extract a page's main article
+//!
+//! A Readability-style heuristic. Boilerplate (scripts, nav, sidebars, ads,
+//! comments) is stripped, then each block's parent is scored by how much
+//! paragraph text it holds, penalised by link density. The best-scoring
+//! element is the article.
+//!
+//! The result can be shown in the content WebView with
+//! [`ReaderArticle::to_data_url`].
+
+use crate::{RendererError, Result};
+use kuchikiki::traits::TendrilSink;
+use kuchikiki::NodeRef;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Elements never part of an article
+const STRIP_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "iframe", "object", "embed", "svg", "canvas",
+    "form", "button", "input", "select", "textarea", "nav", "header", "footer", "aside",
+];
+
+/// class/id words marking boilerplate
+const NEGATIVE_WORDS: &[&str] = &[
+    "ad",
+    "ads",
+    "advert",
+    "advertisement",
+    "banner",
+    "breadcrumb",
+    "comment",
+    "comments",
+    "cookie",
+    "footer",
+    "masthead",
+    "menu",
+    "nav",
+    "navbar",
+    "popup",
+    "promo",
+    "related",
+    "share",
+    "sidebar",
+    "social",
+    "sponsor",
+    "sponsored",
+    "subscribe",
+    "widget",
+];
+
+/// class/id words marking article content
+const POSITIVE_WORDS: &[&str] = &[
+    "article", "body", "content", "entry", "main", "page", "post", "story", "text",
+];
+
+/// Blocks whose text counts towards their ancestors' scores
+const SCORED_TAGS: &[&str] = &["p", "pre", "td", "blockquote", "li"];
+
+/// Attributes kept on extracted content
+const KEPT_ATTRIBUTES: &[&str] = &["href", "src", "alt", "title", "colspan", "rowspan"];
+
+/// Shorter blocks are ignored when scoring
+const MIN_BLOCK_LEN: usize = 25;
+
+/// Less article text than this means there's no clear main content
+const MIN_ARTICLE_LEN: usize = 200;
+
+/// Main content of a page, as shown in reader mode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReaderArticle {
+    /// Article title
+    pub title: String,
+    /// Author line, if the page has one
+    pub byline: Option<String>,
+    /// Cleaned article HTML, with links made absolute
+    pub content_html: String,
+    /// Article text, whitespace collapsed
+    pub text: String,
+}
+
+impl ReaderArticle {
+    /// Standalone reader page for the article, as a `data:` URL
+    pub fn to_data_url(&self) -> String {
+        let byline = self
+            .byline
+            .as_deref()
+            .map(|byline| format!("<p class=\"byline\">{}</p>", escape_html(byline)))
+            .unwrap_or_default();
+        let page = format!(
+            "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{title}</title>\
+             <style>body{{max-width:40em;margin:2em auto;padding:0 1em;\
+             font:18px/1.6 Georgia,serif;color:#222}}img{{max-width:100%;height:auto}}\
+             .byline{{color:#666;font-style:italic}}</style></head>\
+             <body><h1>{title}</h1>{byline}<article>{content}</article></body></html>",
+            title = escape_html(&self.title),
+            byline = byline,
+            content = self.content_html,
+        );
+        format!(
+            "data:text/html;charset=utf-8,{}",
+            urlencoding::encode(&page)
+        )
+    }
+}
+
+/// Extract the main article from a page
+///
+/// Relative links and image sources in the content are resolved against
+/// `base_url`.
+///
+/// # Errors
+///
+/// Returns `RendererError::NoReadableContent` if no element holds enough
+/// article-like text
+pub fn extract_readable(html: &str, base_url: &Url) -> Result<ReaderArticle> {
+    let document = kuchikiki::parse_html().one(html);
+
+    let title = find_title(&document);
+    let byline = find_byline(&document);
+
+    strip_boilerplate(&document);
+
+    let article = best_candidate(&document).ok_or(RendererError::NoReadableContent)?;
+    let text = collapse_whitespace(&article.text_contents());
+    if text.len() < MIN_ARTICLE_LEN {
+        return Err(RendererError::NoReadableContent);
+    }
+
+    clean_content(&article, base_url);
+    let content_html = article.children().map(|child| child.to_string()).collect();
+
+    Ok(ReaderArticle {
+        title,
+        byline,
+        content_html,
+        text,
+    })
+}
+
+fn find_title(document: &NodeRef) -> String {
+    meta_content(document, "meta[property='og:title']")
+        .or_else(|| select_text(document, "title"))
+        .or_else(|| select_text(document, "h1"))
+        .unwrap_or_default()
+}
+
+fn find_byline(document: &NodeRef) -> Option<String> {
+    meta_content(document, "meta[name='author']")
+        .or_else(|| select_text(document, "[rel='author'], .byline, .author"))
+}
+
+fn meta_content(document: &NodeRef, selector: &str) -> Option<String> {
+    let meta = document.select_first(selector).ok()?;
+    let content = meta.attributes.borrow().get("content")?.to_string();
+    Some(collapse_whitespace(&content)).filter(|content| !content.is_empty())
+}
+
+fn select_text(document: &NodeRef, selector: &str) -> Option<String> {
+    let element = document.select_first(selector).ok()?;
+    Some(collapse_whitespace(&element.text_contents())).filter(|text| !text.is_empty())
+}
+
+/// Detach comments and elements that are never article content
+fn strip_boilerplate(document: &NodeRef) {
+    let doomed: Vec<NodeRef> = document
+        .descendants()
+        .filter(|node| {
+            if node.as_comment().is_some() {
+                return true;
+            }
+            let Some(element) = node.as_element() else {
+                return false;
+            };
+            let tag = element.name.local.as_ref();
+            // Keep the document skeleton even if it's tagged like boilerplate
+            if matches!(tag, "html" | "body" | "article" | "main") {
+                return false;
+            }
+            STRIP_TAGS.contains(&tag) || class_weight(node) < 0
+        })
+        .collect();
+
+    for node in doomed {
+        node.detach();
+    }
+}
+
+/// +25 for content-like class/id words, -25 for boilerplate words
+fn class_weight(node: &NodeRef) -> i32 {
+    let Some(element) = node.as_element() else {
+        return 0;
+    };
+    let attributes = element.attributes.borrow();
+    let words: Vec<String> = ["class", "id"]
+        .iter()
+        .filter_map(|name| attributes.get(*name))
+        .flat_map(|value| value.split(|c: char| !c.is_ascii_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect();
+
+    let has_any = |list: &[&str]| words.iter().any(|word| list.contains(&word.as_str()));
+    let mut weight = 0;
+    if has_any(POSITIVE_WORDS) {
+        weight += 25;
+    }
+    if has_any(NEGATIVE_WORDS) {
+        weight -= 25;
+    }
+    weight
+}
+
+/// Starting score from an element's tag and class/id
+fn initial_score(node: &NodeRef) -> f64 {
+    let tag = node
+        .as_element()
+        .map(|element| element.name.local.to_string())
+        .unwrap_or_default();
+    let tag_score = match tag.as_str() {
+        "article" | "main" => 10.0,
+        "div" | "section" => 5.0,
+        "pre" | "td" | "blockquote" => 3.0,
+        "ol" | "ul" | "form" => -3.0,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => -5.0,
+        _ => 0.0,
+    };
+    tag_score + f64::from(class_weight(node))
+}
+
+/// Element with the highest paragraph score, adjusted for link density
+fn best_candidate(document: &NodeRef) -> Option<NodeRef> {
+    // NodeRef isn't Hash; pages have few enough candidates for a Vec
+    let mut scores: Vec<(NodeRef, f64)> = Vec::new();
+
+    let blocks: Vec<NodeRef> = document
+        .descendants()
+        .filter(|node| {
+            node.as_element()
+                .is_some_and(|e| SCORED_TAGS.contains(&e.name.local.as_ref()))
+        })
+        .collect();
+
+    for block in blocks {
+        let text = collapse_whitespace(&block.text_contents());
+        if text.len() < MIN_BLOCK_LEN {
+            continue;
+        }
+        let score = 1.0 + text.matches(',').count() as f64 + (text.len() / 100).min(3) as f64;
+
+        // Full score to the parent, half to the grandparent
+        for (ancestor, share) in block.ancestors().take(2).zip([1.0, 0.5]) {
+            if ancestor.as_element().is_none() {
+                break;
+            }
+            match scores.iter_mut().find(|(node, _)| *node == ancestor) {
+                Some((_, total)) => *total += score * share,
+                None => {
+                    let initial = initial_score(&ancestor);
+                    scores.push((ancestor, initial + score * share));
+                }
+            }
+        }
+    }
+
+    scores
+        .into_iter()
+        .map(|(node, score)| {
+            let adjusted = score * (1.0 - link_density(&node));
+            (node, adjusted)
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(node, _)| node)
+}
+
+/// Share of an element's text that is link text
+fn link_density(node: &NodeRef) -> f64 {
+    let text_len = collapse_whitespace(&node.text_contents()).len();
+    if text_len == 0 {
+        return 0.0;
+    }
+    let link_len: usize = node
+        .select("a")
+        .map(|links| {
+            links
+                .map(|link| collapse_whitespace(&link.text_contents()).len())
+                .sum()
+        })
+        .unwrap_or(0);
+    link_len as f64 / text_len as f64
+}
+
+/// Drop presentational and script attributes, and resolve links
+fn clean_content(article: &NodeRef, base_url: &Url) {
+    for node in article.inclusive_descendants() {
+        let Some(element) = node.as_element() else {
+            continue;
+        };
+        let mut attributes = element.attributes.borrow_mut();
+        attributes
+            .map
+            .retain(|name, _| KEPT_ATTRIBUTES.contains(&name.local.as_ref()));
+
+        for name in ["href", "src"] {
+            let Some(value) = attributes.get_mut(name) else {
+                continue;
+            };
+            match base_url.join(value) {
+                // Don't carry scripts into the reader page
+                Ok(url) if url.scheme() == "javascript" => value.clear(),
+                Ok(url) => *value = url.to_string(),
+                Err(_) => {}
+            }
+        }
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEWS_ARTICLE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+    <title>Rust 2.0 Released | Example News</title>
+    <meta property="og:title" content="Rust 2.0 Released">
+    <meta name="author" content="Ferris Crab">
+    <script>trackPageView();</script>
+</head>
+<body>
+    <header><nav><a href="/">Home</a> <a href="/tech">Tech</a> <a href="/sport">Sport</a></nav></header>
+    <div class="ad-banner">Buy one, get one free! Limited time offer on everything.</div>
+    <div id="main-content" class="post">
+        <h1>Rust 2.0 Released</h1>
+        <p>The Rust team announced today that version 2.0 of the language is available, bringing a long list of improvements to the compiler, the standard library and tooling.</p>
+        <p>Among the headline features are faster compile times, a redesigned async runtime interface, and better diagnostics for lifetimes, which newcomers often find confusing.</p>
+        <p>Existing code keeps compiling thanks to editions. <a href="/docs/editions">Read the edition guide</a> for details on migrating.</p>
+        <img src="images/ferris.png" alt="Ferris" onclick="zoom()">
+    </div>
+    <aside class="sidebar"><h3>Trending</h3><ul><li><a href="/a">Other story one that is trending</a></li></ul></aside>
+    <div class="comments"><p>First! This comment section is full of people arguing, as usual.</p></div>
+    <footer>Copyright Example News. All rights reserved, forever and ever.</footer>
+</body>
+</html>"#;
+
+    const BLOG_POST: &str = r#"<html>
+<head><title>Notes on SQLite</title></head>
+<body>
+    <div class="menu"><a href="/">Blog</a> | <a href="/about">About</a> | <a href="/rss">RSS</a></div>
+    <article>
+        <span class="byline">by Ada</span>
+        <p>SQLite is a small, fast, self-contained SQL database engine, and it is the most widely deployed database in the world by a large margin.</p>
+        <p>Write-ahead logging lets readers and a writer proceed concurrently, which matters a great deal for desktop applications with background work.</p>
+    </article>
+    <div class="related"><p>You might also like: ten reasons to switch databases today.</p></div>
+</body>
+</html>"#;
+
+    fn base() -> Url {
+        Url::parse("https://news.example.com/tech/rust-2").unwrap()
+    }
+
+    #[test]
+    fn test_extracts_news_article() {
+        let article = extract_readable(NEWS_ARTICLE, &base()).unwrap();
+
+        assert_eq!(article.title, "Rust 2.0 Released");
+        assert_eq!(article.byline.as_deref(), Some("Ferris Crab"));
+        assert!(article.text.contains("version 2.0 of the language"));
+        assert!(article.text.contains("better diagnostics for lifetimes"));
+
+        // Boilerplate removed
+        for boilerplate in ["Buy one", "Trending", "First!", "Copyright", "Sport"] {
+            assert!(
+                !article.text.contains(boilerplate),
+                "{boilerplate} left in article"
+            );
+        }
+        assert!(!article.content_html.contains("trackPageView"));
+    }
+
+    #[test]
+    fn test_cleans_content_html() {
+        let article = extract_readable(NEWS_ARTICLE, &base()).unwrap();
+
+        assert!(article
+            .content_html
+            .contains(r#"href="https://news.example.com/docs/editions""#));
+        assert!(article
+            .content_html
+            .contains(r#"src="https://news.example.com/tech/images/ferris.png""#));
+        assert!(!article.content_html.contains("onclick"));
+        assert!(!article.content_html.contains("class="));
+    }
+
+    #[test]
+    fn test_extracts_blog_post() {
+        let article = extract_readable(BLOG_POST, &base()).unwrap();
+
+        assert_eq!(article.title, "Notes on SQLite");
+        assert_eq!(article.byline.as_deref(), Some("by Ada"));
+        assert!(article.text.contains("Write-ahead logging"));
+        assert!(!article.text.contains("You might also like"));
+        assert!(!article.text.contains("RSS"));
+    }
+
+    #[test]
+    fn test_no_main_content() {
+        let html = r#"<html><body>
+            <nav><a href="/a">A</a><a href="/b">B</a></nav>
+            <div><a href="/login">Log in</a> to continue.</div>
+        </body></html>"#;
+
+        assert!(matches!(
+            extract_readable(html, &base()),
+            Err(RendererError::NoReadableContent)
+        ));
+    }
+
+    #[test]
+    fn test_data_url() {
+        let article = ReaderArticle {
+            title: "A & B".to_string(),
+            byline: None,
+            content_html: "<p>Hello</p>".to_string(),
+            text: "Hello".to_string(),
+        };
+
+        let url = article.to_data_url();
+        assert!(url.starts_with("data:text/html;charset=utf-8,"));
+
+        let page = urlencoding::decode(url.split_once(',').unwrap().1).unwrap();
+        assert!(page.contains("<h1>A &amp; B</h1>"));
+        assert!(page.contains("<article><p>Hello</p></article>"));
+    }
+}
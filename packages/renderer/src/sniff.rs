@@ -0,0 +1,249 @@
+//! Content-type sniffing for `data:` URLs and local files
+//!
+//! A small subset of the WHATWG MIME Sniffing Standard: magic bytes for
+//! common images and PDF, a few leading tags for HTML and XML, and a
+//! binary-byte check to tell plain text from everything else.
+
+use crate::{RendererError, Result};
+use base64::Engine;
+use url::Url;
+
+/// MIME type for content with nothing recognisable in it
+pub const OCTET_STREAM: &str = "application/octet-stream";
+
+/// Leading bytes of binary formats and their MIME types
+const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+    (b"\x00\x00\x01\x00", "image/x-icon"),
+    (b"BM", "image/bmp"),
+];
+
+/// Tags that mark a document as HTML when it starts with one
+const HTML_TAGS: &[&str] = &[
+    "<!doctype html",
+    "<html",
+    "<head",
+    "<script",
+    "<iframe",
+    "<h1",
+    "<div",
+    "<font",
+    "<table",
+    "<a",
+    "<style",
+    "<title",
+    "<b",
+    "<body",
+    "<br",
+    "<p",
+];
+
+/// Guess the MIME type of content from its first bytes
+///
+/// Returns `text/plain` for text that isn't HTML or XML, and
+/// `application/octet-stream` for binary data nothing matched.
+pub fn sniff_mime(bytes: &[u8]) -> &'static str {
+    for (signature, mime) in SIGNATURES {
+        if bytes.starts_with(signature) {
+            return mime;
+        }
+    }
+    if bytes.len() >= 14 && bytes.starts_with(b"RIFF") && &bytes[8..14] == b"WEBPVP" {
+        return "image/webp";
+    }
+
+    let text = skip_bom(bytes);
+    let has_bom = text.len() < bytes.len();
+    let start = text
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(text.len());
+    let text = &text[start..];
+    if starts_with_tag(text, HTML_TAGS) || starts_with_ignore_case(text, b"<!--") {
+        return "text/html";
+    }
+    if starts_with_ignore_case(text, b"<svg") {
+        return "image/svg+xml";
+    }
+    if starts_with_ignore_case(text, b"<?xml") {
+        return "text/xml";
+    }
+
+    if has_bom || !bytes.iter().any(|&b| is_binary_byte(b)) {
+        "text/plain"
+    } else {
+        OCTET_STREAM
+    }
+}
+
+/// Add a sniffed MIME type to a `data:` URL that doesn't declare one
+///
+/// URLs with a type are checked and returned unchanged.
+///
+/// # Errors
+/// Returns `RendererError::LoadFailed` if the URL has no `,`, its type
+/// isn't `type/subtype`, or its base64 payload doesn't decode
+pub fn typed_data_url(url: &Url) -> Result<String> {
+    let invalid = |reason: &str| RendererError::LoadFailed(format!("Invalid data URL: {}", reason));
+
+    let (header, payload) = url.as_str()["data:".len()..]
+        .split_once(',')
+        .ok_or_else(|| invalid("missing ','"))?;
+    let (mediatype, base64) = match header.strip_suffix(";base64") {
+        Some(mediatype) => (mediatype, true),
+        None => (header, false),
+    };
+
+    let mime = mediatype.split(';').next().unwrap_or_default().trim();
+    if !mime.is_empty() {
+        if !is_valid_mime(mime) {
+            return Err(invalid(&format!("bad media type {:?}", mime)));
+        }
+        return Ok(url.to_string());
+    }
+
+    let bytes = if base64 {
+        base64::engine::general_purpose::STANDARD
+            .decode(urlencoding::decode_binary(payload.as_bytes()))
+            .map_err(|e| invalid(&e.to_string()))?
+    } else {
+        urlencoding::decode_binary(payload.as_bytes()).into_owned()
+    };
+
+    let mut typed = format!("data:{}{}", sniff_mime(&bytes), mediatype);
+    if base64 {
+        typed.push_str(";base64");
+    }
+    typed.push(',');
+    typed.push_str(payload);
+    Ok(typed)
+}
+
+/// Wrap `bytes` in a base64 `data:` URL of type `mime`
+pub fn bytes_data_url(bytes: &[u8], mime: &str) -> String {
+    format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    )
+}
+
+fn skip_bom(bytes: &[u8]) -> &[u8] {
+    [&b"\xef\xbb\xbf"[..], b"\xfe\xff", b"\xff\xfe"]
+        .iter()
+        .find_map(|bom| bytes.strip_prefix(*bom))
+        .unwrap_or(bytes)
+}
+
+/// Whether `text` opens with one of `tags`, followed by a space or `>`
+fn starts_with_tag(text: &[u8], tags: &[&str]) -> bool {
+    tags.iter().any(|tag| {
+        starts_with_ignore_case(text, tag.as_bytes())
+            && matches!(text.get(tag.len()), Some(b' ' | b'>'))
+    })
+}
+
+fn starts_with_ignore_case(text: &[u8], prefix: &[u8]) -> bool {
+    text.len() >= prefix.len() && text[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
+
+/// Control bytes that don't occur in text
+fn is_binary_byte(b: u8) -> bool {
+    matches!(b, 0x00..=0x08 | 0x0b | 0x0e..=0x1a | 0x1c..=0x1f)
+}
+
+/// `type/subtype`, both non-empty HTTP tokens
+fn is_valid_mime(mime: &str) -> bool {
+    let is_token = |s: &str| {
+        !s.is_empty()
+            && s.bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+    };
+    mime.split_once('/')
+        .is_some_and(|(kind, subtype)| is_token(kind) && is_token(subtype))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_html() {
+        assert_eq!(sniff_mime(b"<!DOCTYPE html><html></html>"), "text/html");
+        assert_eq!(sniff_mime(b"\n  <HTML lang=\"en\">"), "text/html");
+        assert_eq!(sniff_mime(b"\xef\xbb\xbf<p>Hi</p>"), "text/html");
+        assert_eq!(sniff_mime(b"<!-- comment -->"), "text/html");
+        // Not a tag from the list
+        assert_eq!(sniff_mime(b"<pre-formatted>"), "text/plain");
+    }
+
+    #[test]
+    fn test_sniff_images() {
+        assert_eq!(
+            sniff_mime(b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR"),
+            "image/png"
+        );
+        assert_eq!(sniff_mime(b"\xff\xd8\xff\xe0\x00\x10JFIF"), "image/jpeg");
+        assert_eq!(sniff_mime(b"GIF89a\x01\x00\x01\x00"), "image/gif");
+        assert_eq!(sniff_mime(b"RIFF\x24\x00\x00\x00WEBPVP8 "), "image/webp");
+        assert_eq!(
+            sniff_mime(b"<svg xmlns=\"http://www.w3.org/2000/svg\">"),
+            "image/svg+xml"
+        );
+        assert_eq!(sniff_mime(b"%PDF-1.7\n"), "application/pdf");
+    }
+
+    #[test]
+    fn test_sniff_text_and_binary() {
+        assert_eq!(sniff_mime(b"Hello, world!\r\n\tIndented"), "text/plain");
+        assert_eq!(sniff_mime(b""), "text/plain");
+        assert_eq!(sniff_mime(b"<?xml version=\"1.0\"?><feed/>"), "text/xml");
+        assert_eq!(sniff_mime(b"\x00\x01\x02\x03binary"), OCTET_STREAM);
+        // UTF-16 text is full of NULs, but the BOM says it's text
+        assert_eq!(sniff_mime(b"\xff\xfeH\x00i\x00"), "text/plain");
+    }
+
+    #[test]
+    fn test_typed_data_url() {
+        let url = |s: &str| Url::parse(s).unwrap();
+
+        assert_eq!(
+            typed_data_url(&url("data:,%3Ch1%3EHi%3C/h1%3E")).unwrap(),
+            "data:text/html,%3Ch1%3EHi%3C/h1%3E"
+        );
+        assert_eq!(
+            typed_data_url(&url("data:;base64,iVBORw0KGgo=")).unwrap(),
+            "data:image/png;base64,iVBORw0KGgo="
+        );
+        assert_eq!(
+            typed_data_url(&url("data:;charset=utf-8,plain")).unwrap(),
+            "data:text/plain;charset=utf-8,plain"
+        );
+
+        // Declared types are kept
+        assert_eq!(
+            typed_data_url(&url("data:text/css,body{}")).unwrap(),
+            "data:text/css,body{}"
+        );
+
+        for bad in ["data:text/html", "data:html,hi", "data:;base64,!!!"] {
+            assert!(
+                matches!(typed_data_url(&url(bad)), Err(RendererError::LoadFailed(_))),
+                "{} accepted",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_bytes_data_url() {
+        assert_eq!(
+            bytes_data_url(b"hi", "text/plain"),
+            "data:text/plain;base64,aGk="
+        );
+    }
+}
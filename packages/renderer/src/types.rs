@@ -1,5 +1,14 @@
 //! Shared types for the renderer
 
+use crate::{RendererError, Result};
+use std::path::Path;
+use tracing::warn;
+
+/// Default for `RendererConfig::max_size`
+///
+/// A 8192×8192 RGBA surface is 256 MiB.
+pub const DEFAULT_MAX_SIZE: u32 = 8192;
+
 /// Configuration for the renderer
 #[derive(Debug, Clone)]
 pub struct RendererConfig {
@@ -13,6 +22,12 @@ pub struct RendererConfig {
     pub enable_webgl: bool,
     /// Enable JavaScript
     pub enable_javascript: bool,
+    /// Load images automatically
+    pub load_images: bool,
+    /// Allow loading local files through `file://` URLs
+    pub allow_file_urls: bool,
+    /// Largest width or height in device pixels
+    pub max_size: u32,
 }
 
 impl Default for RendererConfig {
@@ -23,26 +38,604 @@ impl Default for RendererConfig {
             device_pixel_ratio: 1.0,
             enable_webgl: true,
             enable_javascript: true,
+            load_images: true,
+            allow_file_urls: false,
+            max_size: DEFAULT_MAX_SIZE,
+        }
+    }
+}
+
+impl RendererConfig {
+    /// Start building a config from the defaults
+    pub fn builder() -> RendererConfigBuilder {
+        RendererConfigBuilder::default()
+    }
+
+    /// Check that the config can be used to size a renderer
+    ///
+    /// # Errors
+    ///
+    /// Returns `RendererError::InvalidConfig` if width or height is 0, the
+    /// device pixel ratio isn't a positive finite number, or the size in
+    /// device pixels is over `max_size`
+    pub fn validate(&self) -> Result<()> {
+        if self.width == 0 || self.height == 0 {
+            return Err(RendererError::InvalidConfig(format!(
+                "size must be non-zero, got {}x{}",
+                self.width, self.height
+            )));
+        }
+        if !(self.device_pixel_ratio.is_finite() && self.device_pixel_ratio > 0.0) {
+            return Err(RendererError::InvalidConfig(format!(
+                "device pixel ratio must be positive, got {}",
+                self.device_pixel_ratio
+            )));
+        }
+        let (width, height) = self.physical_size();
+        if width > self.max_size || height > self.max_size {
+            return Err(RendererError::InvalidConfig(format!(
+                "size {}x{} device pixels is over the maximum of {}",
+                width, height, self.max_size
+            )));
+        }
+        Ok(())
+    }
+
+    /// Clamp a logical size so it fits within `max_size` device pixels
+    ///
+    /// For resizes (`WryRenderer::resize`), which shouldn't fail: an
+    /// oversized request is shrunk (with a warning) rather than attempting
+    /// a huge surface. Each side is clamped on its own and is at least 1.
+    pub fn clamp_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let max_logical = ((self.max_size as f32 / self.device_pixel_ratio).floor() as u32).max(1);
+        let clamped = (width.clamp(1, max_logical), height.clamp(1, max_logical));
+        if clamped != (width, height) {
+            warn!(
+                "Clamped size {}x{} to {}x{} (max {} device pixels)",
+                width, height, clamped.0, clamped.1, self.max_size
+            );
+        }
+        clamped
+    }
+
+    /// Size in device pixels (`width`/`height` scaled by the pixel ratio)
+    pub fn physical_size(&self) -> (u32, u32) {
+        physical_size(self.width, self.height, self.device_pixel_ratio)
+    }
+}
+
+/// Scale a logical size to device pixels, rounding to the nearest pixel
+fn physical_size(width: u32, height: u32, device_pixel_ratio: f32) -> (u32, u32) {
+    let scale = |v: u32| (v as f32 * device_pixel_ratio).round() as u32;
+    (scale(width), scale(height))
+}
+
+/// Builder for `RendererConfig`, validated on `build()`
+#[derive(Debug, Clone, Default)]
+pub struct RendererConfigBuilder {
+    config: RendererConfig,
+}
+
+impl RendererConfigBuilder {
+    /// Set the initial width in pixels
+    pub fn width(mut self, width: u32) -> Self {
+        self.config.width = width;
+        self
+    }
+
+    /// Set the initial height in pixels
+    pub fn height(mut self, height: u32) -> Self {
+        self.config.height = height;
+        self
+    }
+
+    /// Set the device pixel ratio
+    pub fn device_pixel_ratio(mut self, device_pixel_ratio: f32) -> Self {
+        self.config.device_pixel_ratio = device_pixel_ratio;
+        self
+    }
+
+    /// Enable or disable WebGL
+    pub fn enable_webgl(mut self, enable: bool) -> Self {
+        self.config.enable_webgl = enable;
+        self
+    }
+
+    /// Enable or disable JavaScript
+    pub fn enable_javascript(mut self, enable: bool) -> Self {
+        self.config.enable_javascript = enable;
+        self
+    }
+
+    /// Enable or disable automatic image loading
+    pub fn load_images(mut self, load: bool) -> Self {
+        self.config.load_images = load;
+        self
+    }
+
+    /// Allow or disallow `file://` URLs (disallowed by default)
+    pub fn allow_file_urls(mut self, allow: bool) -> Self {
+        self.config.allow_file_urls = allow;
+        self
+    }
+
+    /// Set the largest width or height in device pixels
+    pub fn max_size(mut self, max_size: u32) -> Self {
+        self.config.max_size = max_size;
+        self
+    }
+
+    /// Validate and return the config
+    ///
+    /// # Errors
+    ///
+    /// Same as `RendererConfig::validate()`
+    pub fn build(self) -> Result<RendererConfig> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+/// Paper size for PDF export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PdfPageSize {
+    /// ISO A4 (210 × 297 mm)
+    #[default]
+    A4,
+    /// US Letter (8.5 × 11 in)
+    Letter,
+    /// US Legal (8.5 × 14 in)
+    Legal,
+}
+
+impl PdfPageSize {
+    /// Portrait width and height in millimetres
+    pub fn size_mm(self) -> (f64, f64) {
+        match self {
+            PdfPageSize::A4 => (210.0, 297.0),
+            PdfPageSize::Letter => (215.9, 279.4),
+            PdfPageSize::Legal => (215.9, 355.6),
+        }
+    }
+}
+
+/// Page margins for PDF export, in millimetres
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PdfMargins {
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+impl Default for PdfMargins {
+    fn default() -> Self {
+        Self {
+            top: 10.0,
+            right: 10.0,
+            bottom: 10.0,
+            left: 10.0,
+        }
+    }
+}
+
+/// Options for printing the current page to PDF
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PdfOptions {
+    /// Paper size
+    pub page_size: PdfPageSize,
+    /// Page margins
+    pub margins: PdfMargins,
+    /// Landscape orientation (portrait when false)
+    pub landscape: bool,
+}
+
+/// Content settings applied to the WebView on the next load
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentPreferences {
+    /// Run page scripts
+    pub javascript: bool,
+    /// Load images automatically
+    pub images: bool,
+    /// Allow WebGL contexts
+    pub webgl: bool,
+}
+
+impl Default for ContentPreferences {
+    fn default() -> Self {
+        Self {
+            javascript: true,
+            images: true,
+            webgl: true,
+        }
+    }
+}
+
+impl ContentPreferences {
+    /// Safe browsing: no scripts or WebGL, images still shown
+    pub fn safe() -> Self {
+        Self {
+            javascript: false,
+            images: true,
+            webgl: false,
+        }
+    }
+}
+
+impl From<&RendererConfig> for ContentPreferences {
+    fn from(config: &RendererConfig) -> Self {
+        Self {
+            javascript: config.enable_javascript,
+            images: config.load_images,
+            webgl: config.enable_webgl,
+        }
+    }
+}
+
+/// Page state captured in a single consistent read
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageSnapshot {
+    /// Current URL (None before the first navigation)
+    pub url: Option<String>,
+    /// Page title (None if not known yet)
+    pub title: Option<String>,
+    /// Whether a page load is in progress
+    pub loading: bool,
+}
+
+/// What to do when a page requests a new window
+///
+/// Triggered by `target="_blank"` links and `window.open()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NewWindowAction {
+    /// Let the application open the URL in a new tab
+    #[default]
+    OpenInNewTab,
+    /// Load the URL in the requesting WebView
+    OpenInSameTab,
+    /// Ignore the request
+    Deny,
+}
+
+/// Severity of a console message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleLevel {
+    /// `console.debug`
+    Debug,
+    /// `console.log`
+    #[default]
+    Log,
+    /// `console.info`
+    Info,
+    /// `console.warn`
+    Warn,
+    /// `console.error`
+    Error,
+}
+
+/// A message the page wrote to its JavaScript console
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleMessage {
+    /// Which console method was called
+    pub level: ConsoleLevel,
+    /// Arguments converted to strings and joined with spaces
+    pub message: String,
+    /// URL of the page that logged the message
+    pub source: Option<String>,
+}
+
+/// Kind of JavaScript dialog opened by a page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogKind {
+    /// `alert()`: message with an OK button
+    Alert,
+    /// `confirm()`: OK or Cancel
+    Confirm,
+    /// `prompt()`: text input with OK or Cancel
+    Prompt,
+}
+
+/// A JavaScript dialog waiting for the embedder's answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialogRequest {
+    /// Which dialog function the page called
+    pub kind: DialogKind,
+    /// Message passed by the page
+    pub message: String,
+    /// Initial input text (prompts only)
+    pub default_text: Option<String>,
+}
+
+impl DialogRequest {
+    /// Answer used when no dialog handler is set
+    ///
+    /// Alerts are acknowledged; confirms and prompts are cancelled so pages
+    /// never act on a choice the user didn't make.
+    pub fn dismiss(&self) -> DialogResponse {
+        match self.kind {
+            DialogKind::Alert => DialogResponse::Ok,
+            DialogKind::Confirm | DialogKind::Prompt => DialogResponse::Cancel,
+        }
+    }
+}
+
+/// Answer to a JavaScript dialog
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum DialogResponse {
+    /// OK pressed (prompts return their default text)
+    Ok,
+    /// OK pressed with the given prompt text
+    Text(String),
+    /// Cancel pressed or dialog closed
+    #[default]
+    Cancel,
+}
+
+impl DialogResponse {
+    /// Whether the dialog was accepted (`confirm()` returns true)
+    pub fn is_accepted(&self) -> bool {
+        !matches!(self, DialogResponse::Cancel)
+    }
+}
+
+/// Capability a page asked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PermissionKind {
+    /// `navigator.geolocation`
+    Geolocation,
+    /// `Notification.requestPermission()`
+    Notifications,
+}
+
+impl PermissionKind {
+    /// Stable name, suitable for persisting decisions
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PermissionKind::Geolocation => "geolocation",
+            PermissionKind::Notifications => "notifications",
+        }
+    }
+}
+
+/// A page's request for a permission
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionRequest {
+    /// Origin of the requesting page (e.g. `https://example.com`)
+    pub origin: String,
+    /// Requested capability
+    pub kind: PermissionKind,
+}
+
+/// Embedder's answer to a permission request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PermissionDecision {
+    /// Grant the permission
+    Allow,
+    /// Refuse the permission
+    #[default]
+    Deny,
+}
+
+/// Why a page failed to load
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadErrorKind {
+    /// Host name couldn't be resolved
+    Dns,
+    /// Connection refused, reset or unreachable
+    Connection,
+    /// TLS handshake or certificate failure
+    Tls,
+    /// Server didn't answer in time
+    Timeout,
+    /// Page loaded but the server answered with an error status (4xx/5xx)
+    HttpStatus(u16),
+    /// Anything else
+    Other,
+}
+
+impl LoadErrorKind {
+    /// Categorize a platform error message
+    ///
+    /// Matches the wording of WebKitGTK/libsoup, WKWebView and WebView2
+    /// errors, case-insensitively. Unrecognized messages are `Other`.
+    pub fn from_message(message: &str) -> Self {
+        let message = message.to_ascii_lowercase();
+        let mentions = |needles: &[&str]| needles.iter().any(|needle| message.contains(needle));
+
+        if mentions(&["tls", "ssl", "certificate", "err_cert", "handshake"]) {
+            LoadErrorKind::Tls
+        } else if mentions(&[
+            "resolv",
+            "name or service not known",
+            "nodename nor servname",
+            "no address associated",
+            "name_not_resolved",
+            "hostname",
+            "host name",
+            "dns",
+        ]) {
+            LoadErrorKind::Dns
+        } else if mentions(&["timed out", "timeout", "time out"]) {
+            LoadErrorKind::Timeout
+        } else if mentions(&[
+            "refused",
+            "reset",
+            "could not connect",
+            "unreachable",
+            "connection",
+            "disconnected",
+        ]) {
+            LoadErrorKind::Connection
+        } else {
+            LoadErrorKind::Other
+        }
+    }
+
+    /// Category for an HTTP status, None unless it's an error (400 and up)
+    pub fn from_status(status: u16) -> Option<Self> {
+        (400..=599)
+            .contains(&status)
+            .then_some(LoadErrorKind::HttpStatus(status))
+    }
+}
+
+/// A failed page load
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadError {
+    /// What went wrong
+    pub kind: LoadErrorKind,
+    /// URL that failed to load
+    pub url: String,
+    /// Platform error message, for logs and details
+    pub message: String,
+}
+
+/// What was right-clicked, for building a custom context menu
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ContextMenuContext {
+    /// Horizontal click position in the WebView, in CSS pixels
+    pub x: f64,
+    /// Vertical click position in the WebView, in CSS pixels
+    pub y: f64,
+    /// URL of the page the menu was opened on
+    pub page_url: Option<String>,
+    /// Target of the link under the pointer
+    pub link_url: Option<String>,
+    /// Selected text, trimmed
+    pub selection_text: Option<String>,
+    /// Source of the image under the pointer
+    pub image_url: Option<String>,
+}
+
+impl ContextMenuContext {
+    /// Whether the click was on a link
+    pub fn is_link(&self) -> bool {
+        self.link_url.is_some()
+    }
+
+    /// Whether the click was on an image
+    pub fn is_image(&self) -> bool {
+        self.image_url.is_some()
+    }
+}
+
+/// Entry in a custom context menu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuItem {
+    /// Clickable entry; `id` tells the embedder which action was chosen
+    Action {
+        id: String,
+        label: String,
+        enabled: bool,
+    },
+    /// Divider between groups of entries
+    Separator,
+}
+
+impl MenuItem {
+    /// Enabled action entry
+    pub fn action(id: impl Into<String>, label: impl Into<String>) -> Self {
+        MenuItem::Action {
+            id: id.into(),
+            label: label.into(),
+            enabled: true,
+        }
+    }
+
+    /// Same entry, greyed out (separators are unchanged)
+    pub fn disabled(self) -> Self {
+        match self {
+            MenuItem::Action { id, label, .. } => MenuItem::Action {
+                id,
+                label,
+                enabled: false,
+            },
+            MenuItem::Separator => MenuItem::Separator,
+        }
+    }
+}
+
+/// A context menu waiting for the embedder to show it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextMenu {
+    /// What was clicked
+    pub context: ContextMenuContext,
+    /// Entries from the context-menu handler, never empty
+    pub items: Vec<MenuItem>,
+}
+
+/// Byte order and alpha mode of a frame's pixels (4 bytes per pixel)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PixelFormat {
+    /// Straight-alpha RGBA (Servo readback)
+    #[default]
+    Rgba,
+    /// Straight-alpha BGRA
+    Bgra,
+    /// RGBA with color channels multiplied by alpha
+    RgbaPremultiplied,
+    /// BGRA with color channels multiplied by alpha
+    BgraPremultiplied,
+}
+
+impl PixelFormat {
+    /// Red and blue are swapped relative to RGBA
+    pub fn is_bgra(&self) -> bool {
+        matches!(self, PixelFormat::Bgra | PixelFormat::BgraPremultiplied)
+    }
+
+    /// Color channels are multiplied by alpha
+    pub fn is_premultiplied(&self) -> bool {
+        matches!(
+            self,
+            PixelFormat::RgbaPremultiplied | PixelFormat::BgraPremultiplied
+        )
+    }
+
+    fn from_parts(bgra: bool, premultiplied: bool) -> Self {
+        match (bgra, premultiplied) {
+            (false, false) => PixelFormat::Rgba,
+            (true, false) => PixelFormat::Bgra,
+            (false, true) => PixelFormat::RgbaPremultiplied,
+            (true, true) => PixelFormat::BgraPremultiplied,
         }
     }
 }
 
-/// A rendered frame from Servo
+/// A rendered frame, from backends that render to pixels
 #[derive(Debug, Clone)]
-#[allow(dead_code)]
 pub struct RenderedFrame {
     /// Width in device pixels
     pub width: u32,
     /// Height in device pixels
     pub height: u32,
-    /// RGBA pixel data (4 bytes per pixel)
+    /// Width in logical (CSS) pixels
+    pub logical_width: u32,
+    /// Height in logical (CSS) pixels
+    pub logical_height: u32,
+    /// Pixel data (4 bytes per pixel, layout given by `format`)
     pub pixels: Vec<u8>,
+    /// Layout of `pixels`
+    pub format: PixelFormat,
 }
 
-#[allow(dead_code)]
 impl RenderedFrame {
-    /// Create a new rendered frame
+    /// Create a new rendered frame at a pixel ratio of 1
     pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Self {
+        Self::with_pixel_ratio(width, height, 1.0, pixels)
+    }
+
+    /// Create a frame for a logical size rendered at `device_pixel_ratio`
+    ///
+    /// `pixels` covers the physical size, e.g. 4× the logical area at 2.0.
+    pub fn with_pixel_ratio(
+        logical_width: u32,
+        logical_height: u32,
+        device_pixel_ratio: f32,
+        pixels: Vec<u8>,
+    ) -> Self {
+        let (width, height) = physical_size(logical_width, logical_height, device_pixel_ratio);
         assert_eq!(
             pixels.len(),
             (width * height * 4) as usize,
@@ -51,7 +644,10 @@ impl RenderedFrame {
         Self {
             width,
             height,
+            logical_width,
+            logical_height,
             pixels,
+            format: PixelFormat::Rgba,
         }
     }
 
@@ -59,6 +655,119 @@ impl RenderedFrame {
     pub fn is_empty(&self) -> bool {
         self.pixels.is_empty()
     }
+
+    /// Copy of the frame with red and blue in BGRA order
+    ///
+    /// Alpha mode is kept; a frame that is already BGRA is copied as is.
+    pub fn to_bgra(&self) -> Self {
+        if self.format.is_bgra() {
+            return self.clone();
+        }
+        self.convert(PixelFormat::from_parts(
+            true,
+            self.format.is_premultiplied(),
+        ))
+    }
+
+    /// Copy of the frame with color channels multiplied by alpha
+    ///
+    /// Byte order is kept; a premultiplied frame is copied as is.
+    pub fn to_premultiplied(&self) -> Self {
+        if self.format.is_premultiplied() {
+            return self.clone();
+        }
+        self.convert(PixelFormat::from_parts(self.format.is_bgra(), true))
+    }
+
+    /// Copy of the frame as straight-alpha RGBA
+    ///
+    /// Un-premultiplying is lossy for low alpha values.
+    pub fn to_rgba(&self) -> Self {
+        if self.format == PixelFormat::Rgba {
+            return self.clone();
+        }
+        self.convert(PixelFormat::Rgba)
+    }
+
+    /// Convert pixels from the current format to `format`
+    fn convert(&self, format: PixelFormat) -> Self {
+        let swap = self.format.is_bgra() != format.is_bgra();
+        let premultiply = !self.format.is_premultiplied() && format.is_premultiplied();
+        let unpremultiply = self.format.is_premultiplied() && !format.is_premultiplied();
+
+        let mut pixels = self.pixels.clone();
+        for pixel in pixels.chunks_exact_mut(4) {
+            if swap {
+                pixel.swap(0, 2);
+            }
+            let alpha = pixel[3] as u32;
+            if premultiply {
+                for channel in &mut pixel[..3] {
+                    *channel = ((*channel as u32 * alpha + 127) / 255) as u8;
+                }
+            } else if unpremultiply {
+                for channel in &mut pixel[..3] {
+                    *channel = match alpha {
+                        0 => 0,
+                        _ => ((*channel as u32 * 255 + alpha / 2) / alpha).min(255) as u8,
+                    };
+                }
+            }
+        }
+
+        Self {
+            pixels,
+            format,
+            ..self.clone()
+        }
+    }
+
+    /// Encode the frame as PNG
+    ///
+    /// An empty frame encodes as a single transparent pixel, since PNG
+    /// can't represent a zero-sized image.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RendererError::Other` if the pixel buffer doesn't match the
+    /// dimensions or encoding fails
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        use image::codecs::png::PngEncoder;
+        use image::{ExtendedColorType, ImageEncoder};
+
+        let expected = self.width as usize * self.height as usize * 4;
+        if self.pixels.len() != expected {
+            return Err(RendererError::Other(format!(
+                "Pixel data size mismatch: expected {} bytes, got {}",
+                expected,
+                self.pixels.len()
+            )));
+        }
+
+        let rgba = self.to_rgba();
+        let (width, height, pixels) = if self.width == 0 || self.height == 0 {
+            (1, 1, &[0u8; 4][..])
+        } else {
+            (self.width, self.height, &rgba.pixels[..])
+        };
+
+        let mut png = Vec::new();
+        PngEncoder::new(&mut png)
+            .write_image(pixels, width, height, ExtendedColorType::Rgba8)
+            .map_err(|e| RendererError::Other(format!("PNG encoding failed: {}", e)))?;
+        Ok(png)
+    }
+
+    /// Encode the frame as PNG and write it to `path`
+    ///
+    /// # Errors
+    ///
+    /// Same as `to_png()`, or `RendererError::Other` if the file can't be written
+    pub fn save_png(&self, path: &Path) -> Result<()> {
+        let png = self.to_png()?;
+        std::fs::write(path, png)
+            .map_err(|e| RendererError::Other(format!("Failed to write {}: {}", path.display(), e)))
+    }
 }
 
 #[cfg(test)]
@@ -72,6 +781,182 @@ mod tests {
         assert_eq!(config.height, 768);
     }
 
+    #[test]
+    fn test_renderer_config_builder_defaults() {
+        let config = RendererConfig::builder().build().unwrap();
+        assert_eq!(config.width, 1024);
+        assert_eq!(config.height, 768);
+        assert_eq!(config.device_pixel_ratio, 1.0);
+        assert!(config.enable_webgl);
+        assert!(config.enable_javascript);
+        assert!(config.load_images);
+        assert!(!config.allow_file_urls);
+
+        let config = RendererConfig::builder()
+            .width(800)
+            .height(600)
+            .device_pixel_ratio(2.0)
+            .enable_webgl(false)
+            .allow_file_urls(true)
+            .build()
+            .unwrap();
+        assert_eq!((config.width, config.height), (800, 600));
+        assert_eq!(config.device_pixel_ratio, 2.0);
+        assert!(!config.enable_webgl);
+        assert!(config.allow_file_urls);
+    }
+
+    #[test]
+    fn test_content_preferences_from_config() {
+        assert_eq!(
+            ContentPreferences::from(&RendererConfig::default()),
+            ContentPreferences::default()
+        );
+
+        let config = RendererConfig::builder()
+            .enable_javascript(false)
+            .load_images(false)
+            .build()
+            .unwrap();
+        assert_eq!(
+            ContentPreferences::from(&config),
+            ContentPreferences {
+                javascript: false,
+                images: false,
+                webgl: true,
+            }
+        );
+
+        let config = RendererConfig::builder()
+            .enable_javascript(false)
+            .enable_webgl(false)
+            .build()
+            .unwrap();
+        assert_eq!(
+            ContentPreferences::from(&config),
+            ContentPreferences::safe()
+        );
+    }
+
+    #[test]
+    fn test_renderer_config_rejects_zero_size() {
+        for (width, height) in [(0, 768), (1024, 0), (0, 0)] {
+            let result = RendererConfig::builder()
+                .width(width)
+                .height(height)
+                .build();
+            assert!(matches!(result, Err(RendererError::InvalidConfig(_))));
+        }
+    }
+
+    #[test]
+    fn test_renderer_config_rejects_bad_dpr() {
+        for dpr in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            let config = RendererConfig {
+                device_pixel_ratio: dpr,
+                ..RendererConfig::default()
+            };
+            assert!(matches!(
+                config.validate(),
+                Err(RendererError::InvalidConfig(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_renderer_config_rejects_oversized() {
+        let result = RendererConfig::builder().width(16000).height(16000).build();
+        assert!(matches!(result, Err(RendererError::InvalidConfig(_))));
+
+        // The limit is in device pixels
+        let result = RendererConfig::builder()
+            .width(5000)
+            .height(3000)
+            .device_pixel_ratio(2.0)
+            .build();
+        assert!(matches!(result, Err(RendererError::InvalidConfig(_))));
+
+        let config = RendererConfig::builder()
+            .width(16000)
+            .height(16000)
+            .max_size(16384)
+            .build()
+            .unwrap();
+        assert_eq!(config.physical_size(), (16000, 16000));
+    }
+
+    #[test]
+    fn test_renderer_config_clamp_size() {
+        let config = RendererConfig::builder()
+            .device_pixel_ratio(2.0)
+            .max_size(4096)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.clamp_size(1280, 720), (1280, 720));
+        assert_eq!(config.clamp_size(16000, 16000), (2048, 2048));
+        assert_eq!(config.clamp_size(3000, 0), (2048, 1));
+
+        // A clamped size always makes a valid config
+        let (width, height) = config.clamp_size(16000, 900);
+        let resized = RendererConfig {
+            width,
+            height,
+            ..config
+        };
+        assert!(resized.validate().is_ok());
+        assert_eq!(resized.physical_size(), (4096, 1800));
+    }
+
+    #[test]
+    fn test_pdf_options_default() {
+        let options = PdfOptions::default();
+        assert_eq!(options.page_size, PdfPageSize::A4);
+        assert_eq!(options.margins, PdfMargins::default());
+        assert_eq!(options.margins.top, 10.0);
+        assert!(!options.landscape);
+        assert_eq!(PdfPageSize::A4.size_mm(), (210.0, 297.0));
+        assert_eq!(PdfPageSize::Letter.size_mm(), (215.9, 279.4));
+    }
+
+    #[test]
+    fn test_new_window_action_default() {
+        assert_eq!(NewWindowAction::default(), NewWindowAction::OpenInNewTab);
+    }
+
+    #[test]
+    fn test_dialog_response() {
+        assert_eq!(DialogResponse::default(), DialogResponse::Cancel);
+        assert!(DialogResponse::Ok.is_accepted());
+        assert!(DialogResponse::Text("name".to_string()).is_accepted());
+        assert!(!DialogResponse::Cancel.is_accepted());
+    }
+
+    #[test]
+    fn test_dialog_dismiss() {
+        let request = |kind| DialogRequest {
+            kind,
+            message: "Are you sure?".to_string(),
+            default_text: None,
+        };
+        assert_eq!(request(DialogKind::Alert).dismiss(), DialogResponse::Ok);
+        assert_eq!(
+            request(DialogKind::Confirm).dismiss(),
+            DialogResponse::Cancel
+        );
+        assert_eq!(
+            request(DialogKind::Prompt).dismiss(),
+            DialogResponse::Cancel
+        );
+    }
+
+    #[test]
+    fn test_permission_defaults() {
+        assert_eq!(PermissionDecision::default(), PermissionDecision::Deny);
+        assert_eq!(PermissionKind::Geolocation.as_str(), "geolocation");
+        assert_eq!(PermissionKind::Notifications.as_str(), "notifications");
+    }
+
     #[test]
     fn test_rendered_frame() {
         let pixels = vec![0u8; 1024 * 768 * 4];
@@ -81,6 +966,128 @@ mod tests {
         assert_eq!(frame.height, 768);
     }
 
+    #[test]
+    fn test_renderer_config_physical_size() {
+        let config = RendererConfig::builder()
+            .width(800)
+            .height(600)
+            .device_pixel_ratio(2.0)
+            .build()
+            .unwrap();
+        assert_eq!(config.physical_size(), (1600, 1200));
+
+        let config = RendererConfig {
+            device_pixel_ratio: 1.5,
+            ..RendererConfig::default()
+        };
+        assert_eq!(config.physical_size(), (1536, 1152));
+    }
+
+    #[test]
+    fn test_rendered_frame_hidpi() {
+        let pixels = vec![0u8; 200 * 100 * 4];
+        let frame = RenderedFrame::with_pixel_ratio(100, 50, 2.0, pixels);
+        assert_eq!((frame.width, frame.height), (200, 100));
+        assert_eq!((frame.logical_width, frame.logical_height), (100, 50));
+        assert_eq!(frame.pixels.len(), 4 * 100 * 50 * 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Pixel data size mismatch")]
+    fn test_rendered_frame_hidpi_logical_buffer() {
+        // A buffer sized for the logical area is too small at 2x
+        RenderedFrame::with_pixel_ratio(100, 50, 2.0, vec![0u8; 100 * 50 * 4]);
+    }
+
+    #[test]
+    fn test_rendered_frame_to_png() {
+        // 2x2 frame, top-right pixel opaque red
+        let mut pixels = vec![0u8; 2 * 2 * 4];
+        pixels[4..8].copy_from_slice(&[255, 0, 0, 255]);
+        let frame = RenderedFrame::new(2, 2, pixels);
+
+        let png = frame.to_png().unwrap();
+        let decoded = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(decoded.dimensions(), (2, 2));
+        assert_eq!(decoded.get_pixel(1, 0).0, [255, 0, 0, 255]);
+        assert_eq!(decoded.get_pixel(0, 1).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_rendered_frame_to_bgra() {
+        let frame = RenderedFrame::new(1, 1, vec![10, 20, 30, 255]);
+        assert_eq!(frame.format, PixelFormat::Rgba);
+
+        let bgra = frame.to_bgra();
+        assert_eq!(bgra.format, PixelFormat::Bgra);
+        assert_eq!(bgra.pixels, vec![30, 20, 10, 255]);
+        assert_eq!(bgra.to_bgra().pixels, bgra.pixels);
+        assert_eq!(bgra.to_rgba().pixels, frame.pixels);
+    }
+
+    #[test]
+    fn test_rendered_frame_to_premultiplied() {
+        // Half-transparent, fully opaque and fully transparent pixels
+        let frame = RenderedFrame::new(
+            3,
+            1,
+            vec![200, 100, 50, 128, 200, 100, 50, 255, 200, 100, 50, 0],
+        );
+
+        let premultiplied = frame.to_premultiplied();
+        assert_eq!(premultiplied.format, PixelFormat::RgbaPremultiplied);
+        assert_eq!(&premultiplied.pixels[0..4], &[100, 50, 25, 128]);
+        assert_eq!(&premultiplied.pixels[4..8], &[200, 100, 50, 255]);
+        assert_eq!(&premultiplied.pixels[8..12], &[0, 0, 0, 0]);
+
+        let both = premultiplied.to_bgra();
+        assert_eq!(both.format, PixelFormat::BgraPremultiplied);
+        assert_eq!(&both.pixels[0..4], &[25, 50, 100, 128]);
+
+        // Opaque pixels survive the round trip exactly
+        let straight = both.to_rgba();
+        assert_eq!(&straight.pixels[4..8], &[200, 100, 50, 255]);
+        assert_eq!(&straight.pixels[8..12], &[0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_bgra_frame_to_png() {
+        let frame = RenderedFrame::new(1, 1, vec![255, 0, 0, 255]).to_bgra();
+        let decoded = image::load_from_memory(&frame.to_png().unwrap())
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(decoded.get_pixel(0, 0).0, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_empty_frame_to_png() {
+        let frame = RenderedFrame::new(0, 0, Vec::new());
+        let decoded = image::load_from_memory(&frame.to_png().unwrap())
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(decoded.dimensions(), (1, 1));
+        assert_eq!(decoded.get_pixel(0, 0).0[3], 0);
+    }
+
+    #[test]
+    fn test_to_png_rejects_mismatched_buffer() {
+        let mut frame = RenderedFrame::new(2, 2, vec![0u8; 16]);
+        frame.pixels.truncate(8);
+        assert!(matches!(frame.to_png(), Err(RendererError::Other(_))));
+    }
+
+    #[test]
+    fn test_save_png() {
+        let path = std::env::temp_dir().join(format!("frame-{}.png", std::process::id()));
+        RenderedFrame::new(3, 1, vec![128u8; 12])
+            .save_png(&path)
+            .unwrap();
+
+        let decoded = image::open(&path).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (3, 1));
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     #[should_panic(expected = "Pixel data size mismatch")]
     fn test_rendered_frame_wrong_size() {
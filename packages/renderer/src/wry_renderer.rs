@@ -19,19 +19,90 @@
 //!
 //! This is a hybrid approach: egui for UI chrome, wry for web content.
 
-use crate::{RendererError, Result};
+#[cfg(target_os = "linux")]
+use crate::PdfPageSize;
+use crate::{
+    sniff, ConsoleLevel, ConsoleMessage, ContentPreferences, ContextMenu, ContextMenuContext,
+    DialogRequest, DialogResponse, LoadError, LoadErrorKind, MenuItem, NewWindowAction,
+    PageSnapshot, PdfOptions, PermissionDecision, PermissionRequest, RendererConfig, RendererError,
+    Result,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::rc::Rc;
 use std::sync::{Arc, Mutex};
-use tracing::{debug, info};
+use tracing::{debug, error, info};
 use url::Url;
 use winit::raw_window_handle::HasWindowHandle;
-use wry::WebView;
+use wry::http::{HeaderMap, HeaderName, HeaderValue};
+use wry::{PageLoadEvent, WebView};
+
+/// Session history position tracked from finished page loads
+///
+/// The page's `window.history` exposes a length but not the current index,
+/// so the position is tracked here from page loads and back/forward calls.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct HistoryPosition {
+    /// Number of entries in the session history
+    length: usize,
+    /// Index of the current entry (meaningful when length > 0)
+    index: usize,
+    /// Next finished load is a back/forward traversal, not a new entry
+    traversing: bool,
+    /// URL of the last finished top-level load
+    committed_url: Option<String>,
+}
+
+/// URL, title and loading flag, kept under one lock so readers never see
+/// a mix of old and new values
+#[derive(Debug, Clone, Default)]
+struct PageState {
+    url: String,
+    title: String,
+    loading: bool,
+    /// Load progress from 0.0 to 1.0
+    progress: f32,
+    /// Icon declared by the page, cleared when the URL changes
+    favicon_url: Option<String>,
+}
+
+/// Per-tab settings pushed into each page, kept across navigations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PageSettings {
+    spellcheck: bool,
+    muted: bool,
+    /// A page loaded since the settings were last pushed to it
+    stale: bool,
+}
+
+impl Default for PageSettings {
+    fn default() -> Self {
+        Self {
+            spellcheck: true,
+            muted: false,
+            stale: false,
+        }
+    }
+}
 
 /// Shared state for WebView callbacks
 #[derive(Clone, Default)]
 struct WebViewState {
-    url: Arc<Mutex<String>>,
-    title: Arc<Mutex<String>>,
-    loading: Arc<Mutex<bool>>,
+    page: Arc<Mutex<PageState>>,
+    page_settings: Arc<Mutex<PageSettings>>,
+    /// Ids of the page's media elements that are currently playing
+    audio_playing: Arc<Mutex<HashSet<u32>>>,
+    fullscreen: Arc<Mutex<bool>>,
+    /// Last scroll offset reported by the page, in CSS pixels
+    scroll_position: Arc<Mutex<(f64, f64)>>,
+    /// Why the current page failed to load, cleared when the next load starts
+    last_error: Arc<Mutex<Option<LoadError>>>,
+    /// Custom context menu the embedder hasn't taken yet
+    context_menu: Arc<Mutex<Option<ContextMenu>>>,
+    history: Arc<Mutex<HistoryPosition>>,
+    request_headers: Arc<Mutex<HashMap<String, String>>>,
+    pending_navigation: Arc<Mutex<Option<String>>>,
+    console: Arc<Mutex<VecDeque<ConsoleMessage>>>,
 }
 
 impl WebViewState {
@@ -40,31 +111,288 @@ impl WebViewState {
     }
 
     fn set_url(&self, url: String) {
-        *self.url.lock().unwrap() = url;
+        let mut page = self.page.lock().unwrap();
+        if page.url != url {
+            page.favicon_url = None;
+        }
+        page.url = url;
+    }
+
+    fn set_favicon_url(&self, favicon_url: Option<String>) {
+        self.page.lock().unwrap().favicon_url = favicon_url;
+    }
+
+    fn get_favicon_url(&self) -> Option<String> {
+        self.page.lock().unwrap().favicon_url.clone()
     }
 
     #[allow(dead_code)]
     fn set_title(&self, title: String) {
-        *self.title.lock().unwrap() = title;
+        self.page.lock().unwrap().title = title;
     }
 
     fn set_loading(&self, loading: bool) {
-        *self.loading.lock().unwrap() = loading;
+        self.page.lock().unwrap().loading = loading;
+    }
+
+    /// A new navigation was requested; nothing has loaded yet
+    fn reset_progress(&self) {
+        self.page.lock().unwrap().progress = 0.0;
+    }
+
+    /// The platform started loading the page
+    ///
+    /// The old document's media, scroll offset and load error go away with it.
+    fn page_load_started(&self) {
+        let mut page = self.page.lock().unwrap();
+        page.loading = true;
+        page.progress = LOAD_STARTED_PROGRESS;
+        self.audio_playing.lock().unwrap().clear();
+        *self.scroll_position.lock().unwrap() = (0.0, 0.0);
+        *self.last_error.lock().unwrap() = None;
+    }
+
+    /// The page failed to load, or loaded with an HTTP error status
+    fn page_load_failed(&self, error: LoadError) {
+        debug!(
+            "Load failed ({:?}): {} - {}",
+            error.kind, error.url, error.message
+        );
+        self.page.lock().unwrap().loading = false;
+        *self.last_error.lock().unwrap() = Some(error);
+    }
+
+    fn last_error(&self) -> Option<LoadError> {
+        self.last_error.lock().unwrap().clone()
+    }
+
+    /// Intermediate progress from the platform, ignored outside a load
+    ///
+    /// Never moves backwards within a load.
+    fn set_load_progress(&self, progress: f32) {
+        let mut page = self.page.lock().unwrap();
+        if page.loading && progress.is_finite() {
+            page.progress = progress.clamp(page.progress, 1.0);
+        }
+    }
+
+    /// The platform finished loading the page
+    fn page_load_finished(&self) {
+        let mut page = self.page.lock().unwrap();
+        page.loading = false;
+        page.progress = 1.0;
+        self.page_settings.lock().unwrap().stale = true;
+    }
+
+    fn set_spellcheck_enabled(&self, enabled: bool) {
+        self.page_settings.lock().unwrap().spellcheck = enabled;
+    }
+
+    fn spellcheck_enabled(&self) -> bool {
+        self.page_settings.lock().unwrap().spellcheck
+    }
+
+    fn set_muted(&self, muted: bool) {
+        self.page_settings.lock().unwrap().muted = muted;
+    }
+
+    fn is_muted(&self) -> bool {
+        self.page_settings.lock().unwrap().muted
+    }
+
+    /// A media element started or stopped playing
+    fn media_playback_changed(&self, id: u32, playing: bool) {
+        let mut audio_playing = self.audio_playing.lock().unwrap();
+        if playing {
+            audio_playing.insert(id);
+        } else {
+            audio_playing.remove(&id);
+        }
+    }
+
+    fn is_audio_playing(&self) -> bool {
+        !self.audio_playing.lock().unwrap().is_empty()
+    }
+
+    /// Record the page entering or leaving fullscreen
+    ///
+    /// Returns true if that changed the state.
+    fn set_fullscreen(&self, fullscreen: bool) -> bool {
+        let mut current = self.fullscreen.lock().unwrap();
+        std::mem::replace(&mut *current, fullscreen) != fullscreen
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        *self.fullscreen.lock().unwrap()
+    }
+
+    fn set_scroll_position(&self, x: f64, y: f64) {
+        *self.scroll_position.lock().unwrap() = (x, y);
+    }
+
+    fn scroll_position(&self) -> (f64, f64) {
+        *self.scroll_position.lock().unwrap()
+    }
+
+    /// Queue a context menu, replacing one that wasn't shown
+    fn set_context_menu(&self, menu: ContextMenu) {
+        *self.context_menu.lock().unwrap() = Some(menu);
+    }
+
+    fn take_context_menu(&self) -> Option<ContextMenu> {
+        self.context_menu.lock().unwrap().take()
+    }
+
+    fn page_settings(&self) -> PageSettings {
+        *self.page_settings.lock().unwrap()
+    }
+
+    /// Settings to push to a newly loaded page, if one loaded since the last call
+    fn take_settings_for_page(&self) -> Option<PageSettings> {
+        let mut settings = self.page_settings.lock().unwrap();
+        std::mem::take(&mut settings.stale).then_some(*settings)
+    }
+
+    fn get_progress(&self) -> f32 {
+        self.page.lock().unwrap().progress
+    }
+
+    fn set_request_headers(&self, headers: HashMap<String, String>) {
+        *self.request_headers.lock().unwrap() = headers;
+    }
+
+    fn get_request_headers(&self) -> HashMap<String, String> {
+        self.request_headers.lock().unwrap().clone()
+    }
+
+    fn set_pending_navigation(&self, url: String) {
+        *self.pending_navigation.lock().unwrap() = Some(url);
+    }
+
+    fn take_pending_navigation(&self) -> Option<String> {
+        self.pending_navigation.lock().unwrap().take()
+    }
+
+    /// Append a console message, dropping the oldest once full
+    fn push_console_message(&self, message: ConsoleMessage) {
+        let mut console = self.console.lock().unwrap();
+        if console.len() >= MAX_CONSOLE_MESSAGES {
+            console.pop_front();
+        }
+        console.push_back(message);
+    }
+
+    fn drain_console_messages(&self) -> Vec<ConsoleMessage> {
+        self.console.lock().unwrap().drain(..).collect()
     }
 
     fn get_url(&self) -> String {
-        self.url.lock().unwrap().clone()
+        self.page.lock().unwrap().url.clone()
     }
 
     fn get_title(&self) -> String {
-        self.title.lock().unwrap().clone()
+        self.page.lock().unwrap().title.clone()
     }
 
     fn is_loading(&self) -> bool {
-        *self.loading.lock().unwrap()
+        self.page.lock().unwrap().loading
+    }
+
+    fn snapshot(&self) -> PageSnapshot {
+        let page = self.page.lock().unwrap();
+        PageSnapshot {
+            url: non_empty(&page.url),
+            title: non_empty(&page.title),
+            loading: page.loading,
+        }
+    }
+
+    /// Record a finished top-level page load of `url`
+    ///
+    /// Only a load that changes the committed URL adds an entry, so
+    /// reloads don't; new entries truncate forward history. The load
+    /// following a back/forward call only completes that traversal.
+    fn record_navigation(&self, url: &str) {
+        let mut history = self.history.lock().unwrap();
+        let changed = history.committed_url.as_deref() != Some(url);
+        history.committed_url = Some(url.to_string());
+        if history.traversing {
+            history.traversing = false;
+        } else if !changed {
+            // Reload of the current entry
+        } else if history.length == 0 {
+            history.length = 1;
+            history.index = 0;
+        } else {
+            history.index += 1;
+            history.length = history.index + 1;
+        }
+    }
+
+    /// Move back one entry, returns false if already at the start
+    fn begin_back(&self) -> bool {
+        let mut history = self.history.lock().unwrap();
+        if history.index > 0 {
+            history.index -= 1;
+            history.traversing = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Move forward one entry, returns false if already at the end
+    fn begin_forward(&self) -> bool {
+        let mut history = self.history.lock().unwrap();
+        if history.index + 1 < history.length {
+            history.index += 1;
+            history.traversing = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Reconcile the tracked length with `window.history.length`
+    fn sync_history_length(&self, length: usize) {
+        let mut history = self.history.lock().unwrap();
+        if length == 0 {
+            return;
+        }
+        history.length = length.max(history.index + 1);
+    }
+
+    fn can_go_back(&self) -> bool {
+        self.history.lock().unwrap().index > 0
+    }
+
+    fn can_go_forward(&self) -> bool {
+        let history = self.history.lock().unwrap();
+        history.index + 1 < history.length
     }
 }
 
+/// Progress reported as soon as a load starts
+const LOAD_STARTED_PROGRESS: f32 = 0.1;
+
+/// Maximum number of buffered console messages
+const MAX_CONSOLE_MESSAGES: usize = 500;
+
+/// Callback deciding how to handle new-window requests
+type NewWindowHandler = Rc<dyn Fn(String) -> NewWindowAction>;
+
+/// Callback answering JavaScript dialogs
+type DialogHandler = Rc<dyn Fn(DialogRequest) -> DialogResponse>;
+
+/// Callback deciding permission requests
+type PermissionHandler = Rc<dyn Fn(&PermissionRequest) -> PermissionDecision>;
+
+/// Callback told when the page enters (true) or leaves (false) fullscreen
+type FullscreenHandler = Rc<dyn Fn(bool)>;
+
+/// Callback building a custom context menu
+type ContextMenuHandler = Rc<dyn Fn(ContextMenuContext) -> Vec<MenuItem>>;
+
 /// Wry-based WebView renderer
 ///
 /// This renderer manages a wry WebView that displays web content.
@@ -72,6 +400,21 @@ impl WebViewState {
 pub struct WryRenderer {
     state: WebViewState,
     webview: Option<WebView>,
+    new_window_handler: Option<NewWindowHandler>,
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    dialog_handler: Option<DialogHandler>,
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    permission_handler: Option<PermissionHandler>,
+    fullscreen_handler: Option<FullscreenHandler>,
+    context_menu_handler: Option<ContextMenuHandler>,
+    /// Content settings for the next load
+    preferences: ContentPreferences,
+    /// Content settings the WebView currently has
+    applied_preferences: Option<ContentPreferences>,
+    /// Whether `file://` URLs may be loaded
+    allow_file_urls: bool,
+    /// Current size, with the pixel ratio and `max_size` limiting resizes
+    config: RendererConfig,
 }
 
 impl WryRenderer {
@@ -84,9 +427,32 @@ impl WryRenderer {
         Ok(Self {
             state: WebViewState::new(),
             webview: None,
+            new_window_handler: None,
+            dialog_handler: None,
+            permission_handler: None,
+            fullscreen_handler: None,
+            context_menu_handler: None,
+            preferences: ContentPreferences::default(),
+            applied_preferences: None,
+            allow_file_urls: false,
+            config: RendererConfig::default(),
         })
     }
 
+    /// Create a renderer with content settings taken from `config`
+    ///
+    /// # Errors
+    ///
+    /// Returns `RendererError::InvalidConfig` if the config doesn't validate
+    pub fn with_config(config: &RendererConfig) -> Result<Self> {
+        config.validate()?;
+        let mut renderer = Self::new()?;
+        renderer.preferences = ContentPreferences::from(config);
+        renderer.allow_file_urls = config.allow_file_urls;
+        renderer.config = config.clone();
+        Ok(renderer)
+    }
+
     /// Create the actual WebView
     ///
     /// This must be called from the main thread with a valid window.
@@ -103,19 +469,51 @@ impl WryRenderer {
 
         let state = self.state.clone();
 
-        let webview = WebViewBuilder::new()
+        let mut builder = WebViewBuilder::new()
             .with_url(url)
             .with_devtools(cfg!(debug_assertions))
             // Navigation handler - update state when URL changes
             .with_navigation_handler(move |uri: String| {
                 debug!("Navigation: {}", uri);
                 state.set_url(uri);
+                state.reset_progress();
                 true // Allow navigation
+            });
+
+        if let Some(handler) = self.new_window_req_handler() {
+            builder = builder.with_new_window_req_handler(handler);
+        }
+
+        let load_state = self.state.clone();
+        if self.context_menu_handler.is_some() {
+            builder = builder.with_initialization_script(CONTEXT_MENU_SCRIPT);
+        }
+
+        let ipc = self.ipc_callbacks();
+        builder = builder
+            .with_initialization_script(SPELLCHECK_SCRIPT)
+            .with_initialization_script(MUTE_SCRIPT)
+            .with_initialization_script(FULLSCREEN_SCRIPT)
+            .with_initialization_script(HTTP_STATUS_SCRIPT)
+            .with_on_page_load_handler(move |event, url| match event {
+                PageLoadEvent::Started => load_state.page_load_started(),
+                PageLoadEvent::Finished => {
+                    load_state.page_load_finished();
+                    load_state.record_navigation(&url);
+                }
             })
+            .with_initialization_script(CONSOLE_CAPTURE_SCRIPT)
+            .with_initialization_script(MEDIA_STATE_SCRIPT)
+            .with_initialization_script(SCROLL_TRACKING_SCRIPT)
+            .with_ipc_handler(move |request| ipc.handle(request.body()));
+
+        let webview = builder
             .build(window)
             .map_err(|e| RendererError::WebViewCreationFailed(e.to_string()))?;
 
+        apply_page_settings(&webview, self.state.page_settings());
         self.webview = Some(webview);
+        self.apply_preferences();
         info!("Wry WebView created successfully");
 
         Ok(())
@@ -130,38 +528,394 @@ impl WryRenderer {
 
         let state = self.state.clone();
 
-        let webview = WebViewBuilder::new()
+        let mut builder = WebViewBuilder::new()
             .with_url(url)
             .with_devtools(cfg!(debug_assertions))
             .with_navigation_handler(move |uri: String| {
                 debug!("Navigation: {}", uri);
                 state.set_url(uri);
+                state.reset_progress();
                 true
+            });
+
+        if let Some(handler) = self.new_window_req_handler() {
+            builder = builder.with_new_window_req_handler(handler);
+        }
+
+        let load_state = self.state.clone();
+        if self.context_menu_handler.is_some() {
+            builder = builder.with_initialization_script(CONTEXT_MENU_SCRIPT);
+        }
+
+        let ipc = self.ipc_callbacks();
+        builder = builder
+            .with_initialization_script(SPELLCHECK_SCRIPT)
+            .with_on_page_load_handler(move |event, url| match event {
+                PageLoadEvent::Started => load_state.page_load_started(),
+                PageLoadEvent::Finished => {
+                    load_state.page_load_finished();
+                    load_state.record_navigation(&url);
+                }
             })
+            .with_initialization_script(CONSOLE_CAPTURE_SCRIPT)
+            .with_initialization_script(MEDIA_STATE_SCRIPT)
+            .with_initialization_script(SCROLL_TRACKING_SCRIPT)
+            .with_ipc_handler(move |request| ipc.handle(request.body()));
+
+        let webview = builder
             .build(window)
             .map_err(|e| RendererError::WebViewCreationFailed(e.to_string()))?;
 
+        connect_script_dialogs(&webview, self.dialog_handler());
+        connect_permission_requests(&webview, self.permission_handler());
+        connect_load_progress(&webview, self.state.clone());
+        connect_load_failures(&webview, self.state.clone());
+        connect_fullscreen(&webview, self.fullscreen_callback());
+
+        apply_page_settings(&webview, self.state.page_settings());
         self.webview = Some(webview);
+        self.apply_preferences();
         info!("Wry WebView created successfully (Linux/GTK)");
 
         Ok(())
     }
 
+    /// Set the handler for new-window requests
+    ///
+    /// Called with the target URL when a page follows a `target="_blank"`
+    /// link or calls `window.open()`. The platform popup is always blocked;
+    /// the returned action decides what happens instead:
+    /// - `OpenInNewTab`: nothing further, the handler is expected to open the tab
+    /// - `OpenInSameTab`: URL is queued, see `load_pending_navigation()`
+    /// - `Deny`: request is dropped
+    ///
+    /// Must be set before `create_webview()`; wry fixes its callbacks at build time.
+    pub fn set_on_new_window(&mut self, handler: impl Fn(String) -> NewWindowAction + 'static) {
+        self.new_window_handler = Some(Rc::new(handler));
+    }
+
+    /// Set the handler for `alert()`, `confirm()` and `prompt()`
+    ///
+    /// The handler blocks the page's script until it returns, so it should
+    /// show its UI modally. Without a handler dialogs are auto-dismissed
+    /// (see `DialogRequest::dismiss()`).
+    ///
+    /// Must be set before `create_webview()`.
+    ///
+    /// # Platform Support
+    /// - **Linux (WebKitGTK)**: routed via the `script-dialog` signal
+    /// - **macOS / Windows**: wry doesn't expose dialog callbacks, so the
+    ///   platform's native dialogs are shown and the handler is unused
+    pub fn set_dialog_handler(
+        &mut self,
+        handler: impl Fn(DialogRequest) -> DialogResponse + 'static,
+    ) {
+        self.dialog_handler = Some(Rc::new(handler));
+    }
+
+    /// Registered dialog handler, or one that auto-dismisses
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn dialog_handler(&self) -> DialogHandler {
+        self.dialog_handler
+            .clone()
+            .unwrap_or_else(|| Rc::new(|request: DialogRequest| request.dismiss()))
+    }
+
+    /// Set the handler for geolocation and notification permission requests
+    ///
+    /// Without a handler every request is denied. The handler is asked on
+    /// each request; remembering decisions is up to the embedder.
+    ///
+    /// Must be set before `create_webview()`.
+    ///
+    /// # Platform Support
+    /// - **Linux (WebKitGTK)**: routed via the `permission-request` signal
+    /// - **macOS / Windows**: wry doesn't expose permission callbacks, so
+    ///   the platform's own prompts are shown and the handler is unused
+    pub fn set_permission_handler(
+        &mut self,
+        handler: impl Fn(&PermissionRequest) -> PermissionDecision + 'static,
+    ) {
+        self.permission_handler = Some(Rc::new(handler));
+    }
+
+    /// Registered permission handler, or one that denies everything
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn permission_handler(&self) -> PermissionHandler {
+        self.permission_handler
+            .clone()
+            .unwrap_or_else(|| Rc::new(|_: &PermissionRequest| PermissionDecision::Deny))
+    }
+
+    /// Set the handler told when the page enters or leaves fullscreen
+    ///
+    /// Called with true when the page enters fullscreen (e.g. a video's
+    /// fullscreen button) and false when it leaves, so the embedder can
+    /// hide its chrome and give the content the whole window. Called only
+    /// on changes.
+    ///
+    /// Must be set before `create_webview()`.
+    ///
+    /// # Platform Support
+    /// - **Linux (WebKitGTK)**: `enter-fullscreen` / `leave-fullscreen`
+    ///   signals; WebKit also fullscreens the window
+    /// - **macOS / Windows**: reported by script from `fullscreenchange`;
+    ///   the element fills the WebView, resizing it is up to the embedder
+    pub fn set_on_fullscreen(&mut self, handler: impl Fn(bool) + 'static) {
+        self.fullscreen_handler = Some(Rc::new(handler));
+    }
+
+    /// Whether the page is in fullscreen
+    pub fn is_fullscreen(&self) -> bool {
+        self.state.is_fullscreen()
+    }
+
+    /// Record fullscreen changes and pass them on to the registered handler
+    fn fullscreen_callback(&self) -> FullscreenHandler {
+        let state = self.state.clone();
+        let handler = self.fullscreen_handler.clone();
+
+        Rc::new(move |fullscreen: bool| {
+            if !state.set_fullscreen(fullscreen) {
+                return;
+            }
+            debug!(
+                "Page {} fullscreen",
+                if fullscreen { "entered" } else { "left" }
+            );
+            if let Some(handler) = &handler {
+                handler(fullscreen);
+            }
+        })
+    }
+
+    /// Set the handler building custom context menus
+    ///
+    /// While a handler is set the page's native context menu is replaced:
+    /// on right-click the handler gets what was clicked (link, selection,
+    /// image) and returns the entries to show. The menu is queued for the
+    /// embedder to display, see `take_context_menu()`. An empty list shows
+    /// no menu.
+    ///
+    /// Must be set before `create_webview()`.
+    pub fn set_context_menu_handler(
+        &mut self,
+        handler: impl Fn(ContextMenuContext) -> Vec<MenuItem> + 'static,
+    ) {
+        self.context_menu_handler = Some(Rc::new(handler));
+    }
+
+    /// Take the context menu waiting to be shown, if any
+    ///
+    /// Call from the event loop and show the entries at the menu's
+    /// `context.x`/`context.y`. Only the latest menu is kept.
+    pub fn take_context_menu(&self) -> Option<ContextMenu> {
+        self.state.take_context_menu()
+    }
+
+    /// Handlers for messages posted by the injected scripts
+    fn ipc_callbacks(&self) -> IpcCallbacks {
+        IpcCallbacks {
+            state: self.state.clone(),
+            on_fullscreen: self.fullscreen_callback(),
+            context_menu_handler: self.context_menu_handler.clone(),
+        }
+    }
+
+    /// Load a URL queued by a `NewWindowAction::OpenInSameTab` decision or
+    /// an encoding override refetch
+    ///
+    /// Neither the new-window callback nor the refetch thread can navigate
+    /// the WebView directly, so call this from the event loop after events
+    /// are processed.
+    /// Also pushes the spell-check and mute settings to a page that finished
+    /// loading since the last call. Returns true if a navigation was started.
+    pub fn load_pending_navigation(&mut self) -> Result<bool> {
+        if let (Some(settings), Some(webview)) =
+            (self.state.take_settings_for_page(), &self.webview)
+        {
+            apply_page_settings(webview, settings);
+        }
+
+        match self.state.take_pending_navigation() {
+            Some(url) => {
+                self.load_url(&url)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Build the wry new-window callback from the registered handler
+    fn new_window_req_handler(&self) -> Option<impl Fn(String) -> bool + 'static> {
+        let handler = self.new_window_handler.clone()?;
+        let state = self.state.clone();
+
+        Some(move |url: String| {
+            let action = handler(url.clone());
+            debug!("New window request for {}: {:?}", url, action);
+            if action == NewWindowAction::OpenInSameTab {
+                state.set_pending_navigation(url);
+            }
+            false // Never let the platform open its own window
+        })
+    }
+
+    /// Content settings used from the next load
+    pub fn preferences(&self) -> ContentPreferences {
+        self.preferences
+    }
+
+    /// Whether the settings have changed since the current page loaded
+    pub fn preferences_pending(&self) -> bool {
+        self.applied_preferences != Some(self.preferences)
+    }
+
+    /// Replace all content settings
+    ///
+    /// Takes effect on the next `load_url()` or `reload()`; the current
+    /// page keeps running with the settings it loaded with.
+    ///
+    /// # Platform Support
+    /// - **Linux (WebKitGTK)**: applied through the WebView's settings
+    /// - **macOS / Windows**: wry doesn't expose these settings, so they are
+    ///   recorded but the platform defaults stay in effect
+    pub fn set_preferences(&mut self, preferences: ContentPreferences) {
+        self.preferences = preferences;
+    }
+
+    /// Enable or disable page scripts from the next load
+    ///
+    /// See `set_preferences()` for platform support.
+    pub fn set_javascript_enabled(&mut self, enabled: bool) {
+        self.preferences.javascript = enabled;
+    }
+
+    /// Enable or disable automatic image loading from the next load
+    ///
+    /// See `set_preferences()` for platform support.
+    pub fn set_images_enabled(&mut self, enabled: bool) {
+        self.preferences.images = enabled;
+    }
+
+    /// Push changed content settings to the WebView
+    fn apply_preferences(&mut self) {
+        let Some(ref webview) = self.webview else {
+            return;
+        };
+        if !self.preferences_pending() {
+            return;
+        }
+
+        debug!("Applying content preferences: {:?}", self.preferences);
+        apply_content_preferences(webview, self.preferences);
+        self.applied_preferences = Some(self.preferences);
+    }
+
+    /// What the WebView should load for `url`
+    ///
+    /// `data:` URLs without a media type get a sniffed one. Local files
+    /// are read and handed over as `data:` URLs typed by sniffing their
+    /// contents, HTML keeping a `<base>` pointing at the file.
+    ///
+    /// # Errors
+    /// Returns `RendererError::SchemeNotAllowed` for schemes other than
+    /// http(s), `data`, `blob`, `about:blank` and (if allowed by config)
+    /// `file`, or
+    /// `RendererError::LoadFailed` for a malformed `data:` URL or an
+    /// unreadable file
+    fn webview_url(&self, url: &Url) -> Result<String> {
+        match url.scheme() {
+            // blob: URLs are minted by pages and resolved by the WebView
+            "http" | "https" | "blob" => Ok(url.to_string()),
+            "about" if url.path() == "blank" => Ok(url.to_string()),
+            "data" => sniff::typed_data_url(url),
+            "file" if self.allow_file_urls => {
+                let path = url.to_file_path().map_err(|()| {
+                    RendererError::LoadFailed(format!("Not a local file: {}", url))
+                })?;
+                let bytes = std::fs::read(&path).map_err(|e| {
+                    RendererError::LoadFailed(format!("Failed to read {}: {}", path.display(), e))
+                })?;
+
+                let mime = sniff::sniff_mime(&bytes);
+                debug!("Sniffed {} as {}", path.display(), mime);
+                if mime == "text/html" {
+                    Ok(decoded_data_url(&bytes, encoding_rs::UTF_8, url))
+                } else {
+                    Ok(sniff::bytes_data_url(&bytes, mime))
+                }
+            }
+            "file" => Err(RendererError::SchemeNotAllowed(
+                "file (local files are disabled, see RendererConfig::allow_file_urls)".to_string(),
+            )),
+            other => Err(RendererError::SchemeNotAllowed(other.to_string())),
+        }
+    }
+
     /// Load a URL in the WebView
+    ///
+    /// # Errors
+    /// Returns `RendererError::LoadFailed` for an invalid URL,
+    /// `RendererError::SchemeNotAllowed` for a scheme the renderer won't
+    /// load (see `RendererConfig::allow_file_urls` for local files), or
+    /// `RendererError::NotInitialized` without a WebView
     pub fn load_url(&mut self, url_str: &str) -> Result<()> {
         // Validate URL
         let url = Url::parse(url_str)
             .map_err(|e| RendererError::LoadFailed(format!("Invalid URL: {}", e)))?;
+        let webview_url = self.webview_url(&url)?;
 
         info!("Loading URL: {}", url);
 
+        self.apply_preferences();
+        if let Some(ref webview) = self.webview {
+            webview
+                .load_url(&webview_url)
+                .map_err(|e| RendererError::LoadFailed(e.to_string()))?;
+
+            self.state.set_loading(true);
+            self.state.reset_progress();
+            self.state.set_url(url.to_string());
+            self.state.set_request_headers(HashMap::new());
+
+            Ok(())
+        } else {
+            Err(RendererError::NotInitialized)
+        }
+    }
+
+    /// Load a URL with custom request headers (e.g. `Authorization`)
+    ///
+    /// Headers apply to the top-level document request only. On all
+    /// platforms (WebView2, WKWebView, WebKitGTK) sub-resources and later
+    /// navigations are sent with the WebView's default headers.
+    ///
+    /// # Errors
+    /// Returns `RendererError::LoadFailed` for an invalid URL or an invalid
+    /// header name/value, `RendererError::NotInitialized` without a WebView
+    pub fn load_url_with_headers(
+        &mut self,
+        url_str: &str,
+        headers: HashMap<String, String>,
+    ) -> Result<()> {
+        let url = Url::parse(url_str)
+            .map_err(|e| RendererError::LoadFailed(format!("Invalid URL: {}", e)))?;
+        let webview_url = self.webview_url(&url)?;
+        let header_map = build_header_map(&headers)?;
+
+        info!("Loading URL with {} custom headers: {}", headers.len(), url);
+
+        self.apply_preferences();
         if let Some(ref webview) = self.webview {
             webview
-                .load_url(url.as_str())
+                .load_url_with_headers(&webview_url, header_map)
                 .map_err(|e| RendererError::LoadFailed(e.to_string()))?;
 
             self.state.set_loading(true);
+            self.state.reset_progress();
             self.state.set_url(url.to_string());
+            self.state.set_request_headers(headers);
 
             Ok(())
         } else {
@@ -169,10 +923,16 @@ impl WryRenderer {
         }
     }
 
+    /// Get the custom headers sent with the current top-level request
+    pub fn request_headers(&self) -> HashMap<String, String> {
+        self.state.get_request_headers()
+    }
+
     /// Reload the current page
     pub fn reload(&mut self) -> Result<()> {
         info!("Reloading page");
 
+        self.apply_preferences();
         if let Some(ref webview) = self.webview {
             // wry doesn't have direct reload - use JavaScript
             let _ = webview.evaluate_script("window.location.reload();");
@@ -187,6 +947,7 @@ impl WryRenderer {
         info!("Navigating back");
 
         if let Some(ref webview) = self.webview {
+            self.state.begin_back();
             // Use JavaScript for back navigation
             let _ = webview.evaluate_script("window.history.back();");
             Ok(())
@@ -200,6 +961,7 @@ impl WryRenderer {
         info!("Navigating forward");
 
         if let Some(ref webview) = self.webview {
+            self.state.begin_forward();
             // Use JavaScript for forward navigation
             let _ = webview.evaluate_script("window.history.forward();");
             Ok(())
@@ -208,43 +970,341 @@ impl WryRenderer {
         }
     }
 
-    /// Stop loading the current page
-    pub fn stop(&mut self) -> Result<()> {
-        info!("Stopping page load");
+    /// Check if back navigation is possible
+    ///
+    /// Based on the tracked history position, see `refresh_history_length()`.
+    pub fn can_go_back(&self) -> bool {
+        self.state.can_go_back()
+    }
+
+    /// Check if forward navigation is possible
+    ///
+    /// Based on the tracked history position, see `refresh_history_length()`.
+    pub fn can_go_forward(&self) -> bool {
+        self.state.can_go_forward()
+    }
 
+    /// Re-sync the tracked history length from `window.history.length`
+    ///
+    /// The result arrives asynchronously via the WebView's script callback.
+    ///
+    /// # Limitations
+    /// `window.history.length` is imperfect: it counts entries created by
+    /// `pushState` and iframe navigations, and never reveals the current
+    /// index. The current position therefore stays tracked from navigation
+    /// events, and cross-origin or same-document navigations can skew it.
+    pub fn refresh_history_length(&self) -> Result<()> {
         if let Some(ref webview) = self.webview {
-            // wry doesn't expose stop - use JavaScript
-            let _ = webview.evaluate_script("window.stop();");
-            self.state.set_loading(false);
+            let state = self.state.clone();
+            webview
+                .evaluate_script_with_callback("window.history.length", move |result| {
+                    if let Ok(length) = result.trim().parse::<usize>() {
+                        state.sync_history_length(length);
+                    }
+                })
+                .map_err(|e| RendererError::Other(format!("Script eval failed: {}", e)))?;
             Ok(())
         } else {
             Err(RendererError::NotInitialized)
         }
     }
 
-    /// Check if a page is currently loading
-    pub fn is_loading(&self) -> bool {
-        self.state.is_loading()
-    }
+    /// Stop loading the current page
+    ///
+    /// Safe to call repeatedly. The loading flag is cleared even when the
+    /// WebView isn't created yet.
+    ///
+    /// # Platform Support
+    /// - **Linux (WebKitGTK)**: `webkit_web_view_stop_loading`, which also
+    ///   cancels in-flight subresource loads
+    /// - **macOS / Windows**: wry doesn't expose stop, so `window.stop()`
+    ///   is evaluated in the page
+    pub fn stop(&mut self) -> Result<()> {
+        info!("Stopping page load");
 
-    /// Get the current URL
-    pub fn get_url(&self) -> Option<String> {
-        let url = self.state.get_url();
-        if url.is_empty() {
-            None
-        } else {
-            Some(url)
+        self.state.set_loading(false);
+        let webview = self.webview.as_ref().ok_or(RendererError::NotInitialized)?;
+
+        #[cfg(target_os = "linux")]
+        {
+            use webkit2gtk::WebViewExt;
+            use wry::WebViewExtUnix;
+            webview.webview().stop_loading();
         }
-    }
 
-    /// Get the page title
-    pub fn get_title(&self) -> Option<String> {
-        let title = self.state.get_title();
-        if title.is_empty() {
-            None
-        } else {
-            Some(title)
+        #[cfg(not(target_os = "linux"))]
+        {
+            webview
+                .evaluate_script("window.stop();")
+                .map_err(|e| RendererError::Other(format!("Failed to stop loading: {}", e)))?;
         }
+
+        Ok(())
+    }
+
+    /// Re-decode the current page with the given text encoding
+    ///
+    /// For legacy pages that declare the wrong charset. `charset` is any
+    /// label from the WHATWG Encoding Standard (`"windows-1252"`,
+    /// `"latin1"`, `"Shift_JIS"`, ...), matched case-insensitively.
+    ///
+    /// # Platform Support
+    /// - **Linux (WebKitGTK)**: `webkit_web_view_set_custom_charset()`;
+    ///   WebKit reloads the page and keeps the override for later loads
+    /// - **macOS / Windows**: wry can't override the encoding, so the page
+    ///   is fetched again on a worker thread, decoded there and queued as a
+    ///   `data:` URL for `load_pending_navigation()`. Only http(s) pages can
+    ///   be refetched; cookies aren't sent and the address becomes the
+    ///   `data:` URL. A failed refetch is reported through `last_error()`.
+    ///
+    /// # Errors
+    /// - `RendererError::UnknownCharset` if `charset` isn't a known label
+    /// - `RendererError::NotInitialized` if the WebView isn't created yet
+    /// - `RendererError::Other` if the refetch can't be started
+    pub fn set_text_encoding(&self, charset: &str) -> Result<()> {
+        let encoding = charset_encoding(charset)?;
+        if self.webview.is_none() {
+            return Err(RendererError::NotInitialized);
+        }
+
+        info!("Overriding text encoding: {}", encoding.name());
+
+        #[cfg(target_os = "linux")]
+        if let Some(ref webview) = self.webview {
+            use webkit2gtk::WebViewExt;
+            use wry::WebViewExtUnix;
+            webview.webview().set_custom_charset(Some(encoding.name()));
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let url = Url::parse(&self.state.get_url())
+                .ok()
+                .filter(|url| matches!(url.scheme(), "http" | "https"))
+                .ok_or_else(|| {
+                    RendererError::NotSupported(
+                        "encoding override for pages without an http(s) URL".to_string(),
+                    )
+                })?;
+
+            let state = self.state.clone();
+            std::thread::Builder::new()
+                .name("encoding-refetch".to_string())
+                .spawn(move || match fetch_page_bytes(url.clone()) {
+                    Ok(bytes) => {
+                        state.set_pending_navigation(decoded_data_url(&bytes, encoding, &url))
+                    }
+                    Err(e) => {
+                        error!("Refetch for encoding override failed: {}", e);
+                        state.page_load_failed(LoadError {
+                            kind: LoadErrorKind::from_message(&e.to_string()),
+                            url: url.to_string(),
+                            message: e.to_string(),
+                        });
+                    }
+                })
+                .map_err(|e| RendererError::Other(format!("Failed to start refetch: {}", e)))?;
+            self.state.set_loading(true);
+            self.state.reset_progress();
+        }
+
+        Ok(())
+    }
+
+    /// Resize the WebView to a logical size
+    ///
+    /// A size over `RendererConfig::max_size` device pixels is clamped (see
+    /// `RendererConfig::clamp_size`). Returns the size applied. Before the
+    /// WebView exists only the stored size changes.
+    ///
+    /// # Errors
+    /// Returns `RendererError::Other` if the WebView's bounds can't be set
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(u32, u32)> {
+        let (width, height) = self.config.clamp_size(width, height);
+        self.config.width = width;
+        self.config.height = height;
+
+        if let Some(ref webview) = self.webview {
+            let position = webview
+                .bounds()
+                .map(|bounds| bounds.position)
+                .unwrap_or_else(|_| wry::dpi::LogicalPosition::new(0, 0).into());
+            webview
+                .set_bounds(wry::Rect {
+                    position,
+                    size: wry::dpi::LogicalSize::new(width, height).into(),
+                })
+                .map_err(|e| RendererError::Other(format!("Failed to resize: {}", e)))?;
+        }
+        Ok((width, height))
+    }
+
+    /// Current logical size, as last set by `resize()` or the config
+    pub fn size(&self) -> (u32, u32) {
+        (self.config.width, self.config.height)
+    }
+
+    /// Scroll the page by an offset in CSS pixels
+    ///
+    /// The WebView handles device pixel ratio itself, so offsets are logical.
+    /// No-op when the WebView isn't created yet.
+    pub fn scroll_by(&mut self, dx: f32, dy: f32) -> Result<()> {
+        self.eval_scroll("scrollBy", dx, dy)
+    }
+
+    /// Scroll the page to an absolute position in CSS pixels
+    ///
+    /// No-op when the WebView isn't created yet.
+    pub fn scroll_to(&mut self, x: f32, y: f32) -> Result<()> {
+        self.eval_scroll("scrollTo", x, y)
+    }
+
+    fn eval_scroll(&self, method: &str, x: f32, y: f32) -> Result<()> {
+        match self.webview {
+            Some(_) => self.eval_script(&scroll_script(method, x.into(), y.into())),
+            None => {
+                debug!("Ignoring {} before WebView creation", method);
+                Ok(())
+            }
+        }
+    }
+
+    /// Current scroll offset of the page in CSS pixels, as `(x, y)`
+    ///
+    /// Reported by the page as it scrolls, so it's available without a
+    /// script round-trip, e.g. to save it before navigating away. (0, 0)
+    /// while a new page loads.
+    ///
+    /// # Errors
+    /// Returns `RendererError::NotInitialized` if the WebView isn't created yet
+    pub fn get_scroll_position(&self) -> Result<(f64, f64)> {
+        if self.webview.is_none() {
+            return Err(RendererError::NotInitialized);
+        }
+        Ok(self.state.scroll_position())
+    }
+
+    /// Scroll the page to `(x, y)` in CSS pixels
+    ///
+    /// To restore a saved position, call once the page has finished
+    /// loading; the page clamps offsets past its end.
+    ///
+    /// # Errors
+    /// Returns `RendererError::NotInitialized` if the WebView isn't created yet
+    pub fn set_scroll_position(&self, x: f64, y: f64) -> Result<()> {
+        self.eval_script(&scroll_script("scrollTo", x, y))?;
+        self.state.set_scroll_position(x, y);
+        Ok(())
+    }
+
+    /// Stop loading and destroy the WebView
+    ///
+    /// Pending navigations are dropped and later calls that need the WebView
+    /// return `NotInitialized`. Safe to call repeatedly; also run on drop.
+    pub fn shutdown(&mut self) {
+        if self.webview.is_some() {
+            info!("Shutting down Wry renderer");
+            if let Err(e) = self.stop() {
+                debug!("Stop before shutdown failed: {}", e);
+            }
+            self.webview = None;
+        }
+        self.state.set_loading(false);
+        self.state.take_pending_navigation();
+    }
+
+    /// Check if a page is currently loading
+    pub fn is_loading(&self) -> bool {
+        self.state.is_loading()
+    }
+
+    /// Load progress of the current page, from 0.0 to 1.0
+    ///
+    /// 0.0 once a navigation is requested, 0.1 when the load starts and
+    /// 1.0 when it finishes.
+    ///
+    /// # Platform Support
+    /// - **Linux (WebKitGTK)**: intermediate values from WebKit's estimate
+    /// - **macOS / Windows**: start and finish only
+    pub fn get_progress(&self) -> f32 {
+        self.state.get_progress()
+    }
+
+    /// Why the current page failed to load, if it did
+    ///
+    /// Set when a navigation fails (DNS, connection, TLS, timeout) or the
+    /// page comes back with an HTTP error status; loading is cleared too.
+    /// Reset when the next load starts. Use it to show an error page.
+    ///
+    /// # Platform Support
+    /// - **Linux (WebKitGTK)**: `load-failed` and
+    ///   `load-failed-with-tls-errors` signals, plus the main resource's
+    ///   status. WebKit's own error page is suppressed.
+    /// - **macOS / Windows**: wry doesn't report load failures, so only
+    ///   HTTP error statuses are detected (from the page's Navigation
+    ///   Timing entry) and the platform error page is shown as usual
+    pub fn last_error(&self) -> Option<LoadError> {
+        self.state.last_error()
+    }
+
+    /// Get the current URL
+    pub fn get_url(&self) -> Option<String> {
+        non_empty(&self.state.get_url())
+    }
+
+    /// Get the page title
+    pub fn get_title(&self) -> Option<String> {
+        non_empty(&self.state.get_title())
+    }
+
+    /// Get URL, title and loading state in one consistent read
+    ///
+    /// Prefer this over separate getters when showing several fields
+    /// together, since the page can change between individual calls.
+    pub fn snapshot(&self) -> PageSnapshot {
+        self.state.snapshot()
+    }
+
+    /// Get the favicon URL for the current page
+    ///
+    /// Returns the icon found by the last `refresh_favicon_url()`, or
+    /// `/favicon.ico` on the page's origin when none was declared.
+    /// None for pages without an http(s) origin.
+    pub fn get_favicon_url(&self) -> Option<String> {
+        self.state
+            .get_favicon_url()
+            .or_else(|| fallback_favicon_url(&self.state.get_url()))
+    }
+
+    /// Look up the page's declared favicon (`<link rel="icon">`)
+    ///
+    /// The result arrives asynchronously via the WebView's script callback.
+    /// Call after a page finishes loading; the stored URL is cleared on
+    /// every navigation.
+    pub fn refresh_favicon_url(&self) -> Result<()> {
+        if let Some(ref webview) = self.webview {
+            let state = self.state.clone();
+            webview
+                .evaluate_script_with_callback(FAVICON_SCRIPT, move |result| {
+                    let href = serde_json::from_str::<Option<String>>(&result)
+                        .ok()
+                        .flatten()
+                        .filter(|href| !href.is_empty());
+                    debug!("Favicon: {:?}", href);
+                    state.set_favicon_url(href);
+                })
+                .map_err(|e| RendererError::Other(format!("Script eval failed: {}", e)))?;
+            Ok(())
+        } else {
+            Err(RendererError::NotInitialized)
+        }
+    }
+
+    /// Take all buffered console messages, oldest first
+    ///
+    /// Up to 500 messages are kept between calls; older ones are dropped.
+    pub fn drain_console_messages(&self) -> Vec<ConsoleMessage> {
+        self.state.drain_console_messages()
     }
 
     /// Execute JavaScript in the WebView
@@ -259,6 +1319,395 @@ impl WryRenderer {
         }
     }
 
+    /// Move keyboard focus to the WebView
+    ///
+    /// Uses wry's native focus; where the platform doesn't support it, the
+    /// page is focused by script (`window.focus()`) instead.
+    ///
+    /// # Errors
+    /// Returns `RendererError::NotInitialized` if the WebView isn't created yet
+    pub fn focus(&self) -> Result<()> {
+        let webview = self.webview.as_ref().ok_or(RendererError::NotInitialized)?;
+        if let Err(e) = webview.focus() {
+            debug!("Native focus failed ({}), focusing by script", e);
+            webview
+                .evaluate_script("window.focus();")
+                .map_err(|e| RendererError::Other(format!("Focus failed: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Print the current page to a PDF file
+    ///
+    /// `options` sets paper size, margins and orientation; None leaves the
+    /// layout to the platform (`PdfOptions::default()` on Linux and Windows,
+    /// the whole page as one PDF page on macOS).
+    ///
+    /// The result comes through `on_done` rather than the return value
+    /// because every platform WebView prints in the background and reports
+    /// back on the event loop thread. Waiting here would block the event
+    /// loop the print job needs to finish. The return value only says
+    /// whether the job started; `on_done` gets `Ok` once `dest` is written,
+    /// or the error if printing failed.
+    ///
+    /// # Platform Support
+    /// - **Linux (WebKitGTK)**: `WebKitPrintOperation` with the "Print to
+    ///   File" backend
+    /// - **Windows (WebView2)**: `ICoreWebView2_7::PrintToPdf`
+    /// - **macOS (WKWebView)**: `createPDFWithConfiguration`, which can't
+    ///   lay out pages, so `options` isn't supported
+    ///
+    /// # Errors
+    /// Returns `RendererError::NotInitialized` if the WebView isn't created yet
+    /// Returns `RendererError::NotSupported` for `options` on macOS
+    /// Returns `RendererError::Other` if the print job can't be started
+    pub fn print_to_pdf(
+        &self,
+        dest: &Path,
+        options: Option<PdfOptions>,
+        on_done: impl FnOnce(Result<()>) + 'static,
+    ) -> Result<()> {
+        let webview = self.webview.as_ref().ok_or(RendererError::NotInitialized)?;
+
+        info!("Printing page to PDF: {:?}", dest);
+
+        #[cfg(target_os = "linux")]
+        {
+            use gtk::{PageOrientation, PageSetup, PaperSize, PrintSettings, Unit};
+            use std::cell::Cell;
+            use webkit2gtk::{PrintOperation, PrintOperationExt};
+            use wry::WebViewExtUnix;
+
+            let options = options.unwrap_or_default();
+
+            let output_uri = Url::from_file_path(dest).map_err(|_| {
+                RendererError::Other(format!(
+                    "PDF destination must be an absolute path: {}",
+                    dest.display()
+                ))
+            })?;
+
+            let paper_name = match options.page_size {
+                PdfPageSize::A4 => gtk::PAPER_NAME_A4,
+                PdfPageSize::Letter => gtk::PAPER_NAME_LETTER,
+                PdfPageSize::Legal => gtk::PAPER_NAME_LEGAL,
+            };
+            let orientation = if options.landscape {
+                PageOrientation::Landscape
+            } else {
+                PageOrientation::Portrait
+            };
+
+            let page_setup = PageSetup::new();
+            page_setup.set_paper_size(&PaperSize::new(Some(paper_name)));
+            page_setup.set_orientation(orientation);
+            page_setup.set_top_margin(options.margins.top, Unit::Mm);
+            page_setup.set_right_margin(options.margins.right, Unit::Mm);
+            page_setup.set_bottom_margin(options.margins.bottom, Unit::Mm);
+            page_setup.set_left_margin(options.margins.left, Unit::Mm);
+
+            let settings = PrintSettings::new();
+            settings.set_printer("Print to File");
+            settings.set(gtk::PRINT_SETTINGS_OUTPUT_FILE_FORMAT, Some("pdf"));
+            settings.set(gtk::PRINT_SETTINGS_OUTPUT_URI, Some(output_uri.as_str()));
+            settings.set_orientation(orientation);
+
+            let operation = PrintOperation::new(&webview.webview());
+            operation.set_page_setup(&page_setup);
+            operation.set_print_settings(&settings);
+
+            // "finished" follows "failed", so whichever fires first reports
+            let on_done = Rc::new(Cell::new(Some(on_done)));
+            let on_failed = on_done.clone();
+            operation.connect_failed(move |_, e| {
+                if let Some(on_done) = on_failed.take() {
+                    on_done(Err(RendererError::Other(format!(
+                        "Print to PDF failed: {}",
+                        e
+                    ))));
+                }
+            });
+            let dest_display = dest.display().to_string();
+            operation.connect_finished(move |_| {
+                if let Some(on_done) = on_done.take() {
+                    info!("PDF written: {}", dest_display);
+                    on_done(Ok(()));
+                }
+            });
+            operation.print();
+
+            Ok(())
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            use webview2_com::Microsoft::Web::WebView2::Win32::{
+                ICoreWebView2Environment6, ICoreWebView2_2, ICoreWebView2_7,
+                COREWEBVIEW2_PRINT_ORIENTATION_LANDSCAPE, COREWEBVIEW2_PRINT_ORIENTATION_PORTRAIT,
+            };
+            use webview2_com::PrintToPdfCompletedHandler;
+            use windows::core::{Interface, HSTRING};
+            use wry::WebViewExtWindows;
+
+            // WebView2 print settings are in inches
+            const MM_PER_INCH: f64 = 25.4;
+
+            fn print_failed(e: windows::core::Error) -> RendererError {
+                RendererError::Other(format!("Print to PDF failed: {}", e))
+            }
+
+            let options = options.unwrap_or_default();
+            let (width, height) = options.page_size.size_mm();
+            let orientation = if options.landscape {
+                COREWEBVIEW2_PRINT_ORIENTATION_LANDSCAPE
+            } else {
+                COREWEBVIEW2_PRINT_ORIENTATION_PORTRAIT
+            };
+            let dest_display = dest.display().to_string();
+
+            unsafe {
+                let core = webview.controller().CoreWebView2().map_err(print_failed)?;
+                let settings = core
+                    .cast::<ICoreWebView2_2>()
+                    .and_then(|core| core.Environment())
+                    .and_then(|environment| environment.cast::<ICoreWebView2Environment6>())
+                    .and_then(|environment| environment.CreatePrintSettings())
+                    .map_err(print_failed)?;
+                settings.SetOrientation(orientation).map_err(print_failed)?;
+                settings
+                    .SetPageWidth(width / MM_PER_INCH)
+                    .map_err(print_failed)?;
+                settings
+                    .SetPageHeight(height / MM_PER_INCH)
+                    .map_err(print_failed)?;
+                settings
+                    .SetMarginTop(options.margins.top / MM_PER_INCH)
+                    .map_err(print_failed)?;
+                settings
+                    .SetMarginRight(options.margins.right / MM_PER_INCH)
+                    .map_err(print_failed)?;
+                settings
+                    .SetMarginBottom(options.margins.bottom / MM_PER_INCH)
+                    .map_err(print_failed)?;
+                settings
+                    .SetMarginLeft(options.margins.left / MM_PER_INCH)
+                    .map_err(print_failed)?;
+
+                let handler =
+                    PrintToPdfCompletedHandler::create(Box::new(move |result, written| {
+                        on_done(match result {
+                            Ok(()) if written => {
+                                info!("PDF written: {}", dest_display);
+                                Ok(())
+                            }
+                            Ok(()) => Err(RendererError::Other(format!(
+                                "Print to PDF failed: couldn't write {}",
+                                dest_display
+                            ))),
+                            Err(e) => Err(print_failed(e)),
+                        });
+                        Ok(())
+                    }));
+
+                core.cast::<ICoreWebView2_7>()
+                    .and_then(|core| {
+                        core.PrintToPdf(&HSTRING::from(dest.as_os_str()), &settings, &handler)
+                    })
+                    .map_err(print_failed)?;
+            }
+
+            Ok(())
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            use block2::RcBlock;
+            use objc2_foundation::{NSData, NSError};
+            use std::cell::Cell;
+            use wry::WebViewExtMacOS;
+
+            // WKPDFConfiguration only takes a capture rect
+            if options.is_some() {
+                return Err(RendererError::NotSupported(
+                    "PDF page size, margins and orientation on macOS".to_string(),
+                ));
+            }
+
+            let dest = dest.to_path_buf();
+            let on_done = Cell::new(Some(on_done));
+            let handler = RcBlock::new(move |data: *mut NSData, error: *mut NSError| {
+                let Some(on_done) = on_done.take() else {
+                    return;
+                };
+                // SAFETY: WebKit passes either valid objects or null
+                let result = match unsafe { data.as_ref() } {
+                    Some(data) => std::fs::write(&dest, data.bytes()).map_err(|e| {
+                        RendererError::Other(format!(
+                            "Couldn't write PDF to {}: {}",
+                            dest.display(),
+                            e
+                        ))
+                    }),
+                    None => {
+                        let reason = unsafe { error.as_ref() }
+                            .map(|e| e.localizedDescription().to_string())
+                            .unwrap_or_else(|| "no PDF data".to_string());
+                        Err(RendererError::Other(format!(
+                            "Print to PDF failed: {}",
+                            reason
+                        )))
+                    }
+                };
+                if result.is_ok() {
+                    info!("PDF written: {}", dest.display());
+                }
+                on_done(result);
+            });
+
+            unsafe {
+                webview
+                    .webview()
+                    .createPDFWithConfiguration_completionHandler(None, &handler);
+            }
+
+            Ok(())
+        }
+
+        #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+        {
+            let _ = (webview, options, on_done);
+            Err(RendererError::NotSupported(
+                "print to PDF on this platform".to_string(),
+            ))
+        }
+    }
+
+    /// Clear cookies for all sites
+    ///
+    /// Works whether or not a page is currently loaded.
+    ///
+    /// # Platform Support
+    /// - **Linux (WebKitGTK)**: Clears cookies only
+    /// - **macOS / Windows**: wry only exposes clearing all browsing data,
+    ///   so cookies, cache and site storage are cleared together
+    ///
+    /// # Errors
+    /// Returns `RendererError::NotInitialized` if the WebView isn't created yet
+    pub fn clear_cookies(&self) -> Result<()> {
+        let webview = self.webview.as_ref().ok_or(RendererError::NotInitialized)?;
+
+        info!("Clearing cookies");
+
+        #[cfg(target_os = "linux")]
+        {
+            clear_website_data(webview, webkit2gtk::WebsiteDataTypes::COOKIES)
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            clear_all_browsing_data(webview)
+        }
+    }
+
+    /// Clear the memory and disk cache
+    ///
+    /// Works whether or not a page is currently loaded.
+    ///
+    /// # Platform Support
+    /// - **Linux (WebKitGTK)**: Clears memory and disk cache only
+    /// - **macOS / Windows**: wry only exposes clearing all browsing data,
+    ///   so cookies, cache and site storage are cleared together
+    ///
+    /// # Errors
+    /// Returns `RendererError::NotInitialized` if the WebView isn't created yet
+    pub fn clear_cache(&self) -> Result<()> {
+        let webview = self.webview.as_ref().ok_or(RendererError::NotInitialized)?;
+
+        info!("Clearing cache");
+
+        #[cfg(target_os = "linux")]
+        {
+            use webkit2gtk::WebsiteDataTypes;
+            clear_website_data(
+                webview,
+                WebsiteDataTypes::MEMORY_CACHE | WebsiteDataTypes::DISK_CACHE,
+            )
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            clear_all_browsing_data(webview)
+        }
+    }
+
+    /// Turn spell-checking of editable fields on or off
+    ///
+    /// Enabled by default. The setting persists across navigations. Without
+    /// a WebView it's stored for `create_webview()` and `NotInitialized` is
+    /// returned.
+    ///
+    /// # Platform Support
+    /// - **Linux (WebKitGTK)**: toggles WebKit's spell checker, and sets the
+    ///   `spellcheck` attribute on fields as they gain focus
+    /// - **macOS / Windows**: wry has no spell-check API, so only the
+    ///   `spellcheck` attribute is set. New pages get the setting in
+    ///   `load_pending_navigation()`.
+    ///
+    /// # Errors
+    /// Returns `RendererError::NotInitialized` if the WebView isn't created yet
+    pub fn set_spellcheck(&self, enabled: bool) -> Result<()> {
+        self.state.set_spellcheck_enabled(enabled);
+        let webview = self.webview.as_ref().ok_or(RendererError::NotInitialized)?;
+
+        info!(
+            "Spell-check {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+        apply_spellcheck(webview, enabled);
+        Ok(())
+    }
+
+    /// Whether spell-checking is enabled
+    pub fn spellcheck_enabled(&self) -> bool {
+        self.state.spellcheck_enabled()
+    }
+
+    /// Mute or unmute audio in the WebView
+    ///
+    /// The setting persists across navigations. Without a WebView it's
+    /// stored for `create_webview()` and `NotInitialized` is returned.
+    ///
+    /// # Platform Support
+    /// - **Linux (WebKitGTK)**: uses `webkit_web_view_set_is_muted()`
+    /// - **macOS / Windows**: wry has no mute API, so `<audio>` and `<video>`
+    ///   elements are muted by script, including ones added later. Media
+    ///   the page muted itself stays muted on unmute. New pages get the
+    ///   setting in `load_pending_navigation()`.
+    ///
+    /// # Errors
+    /// Returns `RendererError::NotInitialized` if the WebView isn't created yet
+    pub fn set_muted(&self, muted: bool) -> Result<()> {
+        self.state.set_muted(muted);
+        let webview = self.webview.as_ref().ok_or(RendererError::NotInitialized)?;
+
+        info!("Audio {}", if muted { "muted" } else { "unmuted" });
+        apply_mute(webview, muted);
+        Ok(())
+    }
+
+    /// Whether audio is muted
+    pub fn is_muted(&self) -> bool {
+        self.state.is_muted()
+    }
+
+    /// Whether any media element on the page is playing
+    ///
+    /// For a tab's audio indicator. Reported by an injected script, so it
+    /// includes muted media; check `is_muted()` to show a muted indicator.
+    pub fn is_audio_playing(&self) -> bool {
+        self.state.is_audio_playing()
+    }
+
     /// Get mutable reference to WebView (for advanced usage)
     pub fn webview_mut(&mut self) -> Option<&mut WebView> {
         self.webview.as_mut()
@@ -270,33 +1719,1873 @@ impl WryRenderer {
     }
 }
 
-impl Default for WryRenderer {
-    fn default() -> Self {
-        Self::new().expect("Failed to create WryRenderer")
+/// Clear selected website data through WebKitGTK's data manager
+///
+/// Clearing completes asynchronously; failures are logged.
+#[cfg(target_os = "linux")]
+fn clear_website_data(webview: &WebView, types: webkit2gtk::WebsiteDataTypes) -> Result<()> {
+    use webkit2gtk::{gio, glib, WebViewExt, WebsiteDataManagerExtManual};
+    use wry::WebViewExtUnix;
+
+    let manager = webview.webview().website_data_manager().ok_or_else(|| {
+        RendererError::Other("WebKitGTK website data manager unavailable".to_string())
+    })?;
+
+    // A zero timespan clears data regardless of when it was stored
+    manager.clear(
+        types,
+        glib::TimeSpan(0),
+        None::<&gio::Cancellable>,
+        |result| {
+            if let Err(e) = result {
+                error!("Failed to clear website data: {}", e);
+            }
+        },
+    );
+
+    Ok(())
+}
+
+/// Apply content settings through the WebKitGTK settings object
+///
+/// Only page scripts are disabled; scripts the embedder evaluates (console
+/// capture, `reload()`) keep working.
+#[cfg(target_os = "linux")]
+fn apply_content_preferences(webview: &WebView, preferences: ContentPreferences) {
+    use webkit2gtk::{SettingsExt, WebViewExt};
+    use wry::WebViewExtUnix;
+
+    let Some(settings) = webview.webview().settings() else {
+        error!("WebKitGTK settings unavailable, content preferences not applied");
+        return;
+    };
+    settings.set_enable_javascript_markup(preferences.javascript);
+    settings.set_auto_load_images(preferences.images);
+    settings.set_enable_webgl(preferences.webgl);
+}
+
+/// wry exposes no content settings off Linux
+#[cfg(not(target_os = "linux"))]
+fn apply_content_preferences(_webview: &WebView, preferences: ContentPreferences) {
+    if preferences != ContentPreferences::default() {
+        debug!("Content preferences not supported on this platform");
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Feed WebKitGTK's estimated load progress into the shared state
+#[cfg(target_os = "linux")]
+fn connect_load_progress(webview: &WebView, state: WebViewState) {
+    use webkit2gtk::WebViewExt;
+    use wry::WebViewExtUnix;
 
-    #[test]
-    fn test_create_renderer() {
-        let renderer = WryRenderer::new();
-        assert!(renderer.is_ok());
+    webview
+        .webview()
+        .connect_estimated_load_progress_notify(move |webview| {
+            state.set_load_progress(webview.estimated_load_progress() as f32);
+        });
+}
+
+/// Record WebKitGTK load failures and HTTP error statuses
+///
+/// Cancelled loads and ones interrupted by a download aren't failures.
+#[cfg(target_os = "linux")]
+fn connect_load_failures(webview: &WebView, state: WebViewState) {
+    use webkit2gtk::gio::{IOErrorEnum, ResolverError};
+    use webkit2gtk::{
+        LoadEvent, NetworkError, PolicyError, URIResponseExt, WebResourceExt, WebViewExt,
+    };
+    use wry::WebViewExtUnix;
+
+    let failed_state = state.clone();
+    webview
+        .webview()
+        .connect_load_failed(move |_, _, uri, error| {
+            if error.matches(NetworkError::Cancelled)
+                || error.matches(PolicyError::FrameLoadInterruptedByPolicyChange)
+            {
+                return false;
+            }
+
+            let kind = if error.matches(ResolverError::NotFound)
+                || error.matches(ResolverError::TemporaryFailure)
+            {
+                LoadErrorKind::Dns
+            } else if error.matches(IOErrorEnum::TimedOut) {
+                LoadErrorKind::Timeout
+            } else if error.matches(IOErrorEnum::ConnectionRefused)
+                || error.matches(IOErrorEnum::HostUnreachable)
+                || error.matches(IOErrorEnum::NetworkUnreachable)
+            {
+                LoadErrorKind::Connection
+            } else {
+                LoadErrorKind::from_message(error.message())
+            };
+            failed_state.page_load_failed(LoadError {
+                kind,
+                url: uri.to_string(),
+                message: error.message().to_string(),
+            });
+            true
+        });
+
+    let tls_state = state.clone();
+    webview
+        .webview()
+        .connect_load_failed_with_tls_errors(move |_, uri, _, flags| {
+            tls_state.page_load_failed(LoadError {
+                kind: LoadErrorKind::Tls,
+                url: uri.to_string(),
+                message: format!("TLS certificate errors: {:?}", flags),
+            });
+            true
+        });
+
+    webview.webview().connect_load_changed(move |view, event| {
+        if event != LoadEvent::Finished {
+            return;
+        }
+        let Some(response) = view
+            .main_resource()
+            .and_then(|resource| resource.response())
+        else {
+            return;
+        };
+        let status = response.status_code();
+        if let Some(kind) = u16::try_from(status)
+            .ok()
+            .and_then(LoadErrorKind::from_status)
+        {
+            state.page_load_failed(LoadError {
+                kind,
+                url: response.uri().map(String::from).unwrap_or_default(),
+                message: format!("HTTP status {}", status),
+            });
+        }
+    });
+}
+
+/// Report WebKitGTK fullscreen changes, keeping WebKit's default handling
+#[cfg(target_os = "linux")]
+fn connect_fullscreen(webview: &WebView, on_fullscreen: FullscreenHandler) {
+    use webkit2gtk::WebViewExt;
+    use wry::WebViewExtUnix;
+
+    let on_enter = on_fullscreen.clone();
+    webview.webview().connect_enter_fullscreen(move |_| {
+        on_enter(true);
+        false
+    });
+    webview.webview().connect_leave_fullscreen(move |_| {
+        on_fullscreen(false);
+        false
+    });
+}
+
+/// Answer WebKitGTK script dialogs through the dialog handler
+///
+/// `beforeunload` confirmations keep WebKit's default behavior.
+#[cfg(target_os = "linux")]
+fn connect_script_dialogs(webview: &WebView, handler: DialogHandler) {
+    use crate::DialogKind;
+    use webkit2gtk::{ScriptDialogType, WebViewExt};
+    use wry::WebViewExtUnix;
+
+    webview.webview().connect_script_dialog(move |_, dialog| {
+        let kind = match dialog.dialog_type() {
+            ScriptDialogType::Alert => DialogKind::Alert,
+            ScriptDialogType::Confirm => DialogKind::Confirm,
+            ScriptDialogType::Prompt => DialogKind::Prompt,
+            _ => return false,
+        };
+        let default_text = match kind {
+            DialogKind::Prompt => dialog.prompt_get_default_text().map(String::from),
+            _ => None,
+        };
+        let request = DialogRequest {
+            kind,
+            message: dialog.message().map(String::from).unwrap_or_default(),
+            default_text: default_text.clone(),
+        };
+
+        let response = handler(request);
+        debug!("Script dialog {:?}: {:?}", kind, response);
+
+        match (kind, response) {
+            (DialogKind::Alert, _) => {}
+            (DialogKind::Confirm, response) => dialog.confirm_set_confirmed(response.is_accepted()),
+            (DialogKind::Prompt, DialogResponse::Text(text)) => dialog.prompt_set_text(&text),
+            (DialogKind::Prompt, DialogResponse::Ok) => {
+                dialog.prompt_set_text(default_text.as_deref().unwrap_or_default())
+            }
+            // Unset prompt text makes prompt() return null
+            (DialogKind::Prompt, DialogResponse::Cancel) => {}
+        }
+        true
+    });
+}
+
+/// Decide a WebView's geolocation and notification permission requests
+///
+/// For WebViews built without `WryRenderer`; platform support is as for
+/// `WryRenderer::set_permission_handler`.
+pub fn connect_permission_handler(
+    webview: &WebView,
+    handler: impl Fn(&PermissionRequest) -> PermissionDecision + 'static,
+) {
+    #[cfg(target_os = "linux")]
+    connect_permission_requests(webview, Rc::new(handler));
+    #[cfg(not(target_os = "linux"))]
+    let _ = (webview, handler);
+}
+
+/// Decide WebKitGTK permission requests through the permission handler
+///
+/// Other request types (camera, microphone, ...) keep WebKit's default,
+/// which denies them.
+#[cfg(target_os = "linux")]
+fn connect_permission_requests(webview: &WebView, handler: PermissionHandler) {
+    use crate::PermissionKind;
+    use webkit2gtk::glib::prelude::*;
+    use webkit2gtk::{
+        GeolocationPermissionRequest, NotificationPermissionRequest, PermissionRequestExt,
+        WebViewExt,
+    };
+    use wry::WebViewExtUnix;
+
+    webview
+        .webview()
+        .connect_permission_request(move |view, request| {
+            let kind = if request.is::<GeolocationPermissionRequest>() {
+                PermissionKind::Geolocation
+            } else if request.is::<NotificationPermissionRequest>() {
+                PermissionKind::Notifications
+            } else {
+                return false;
+            };
+
+            let origin = view.uri().as_deref().and_then(origin_of);
+            let decision = match origin {
+                Some(origin) => handler(&PermissionRequest { origin, kind }),
+                None => PermissionDecision::Deny,
+            };
+            debug!("Permission request {:?}: {:?}", kind, decision);
+
+            match decision {
+                PermissionDecision::Allow => request.allow(),
+                PermissionDecision::Deny => request.deny(),
+            }
+            true
+        });
+}
+
+/// Serialized origin of a page URL, None for opaque origins
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn origin_of(page_url: &str) -> Option<String> {
+    let origin = Url::parse(page_url).ok()?.origin();
+    origin.is_tuple().then(|| origin.ascii_serialization())
+}
+
+/// Clear all browsing data (platforms without selective clearing)
+#[cfg(not(target_os = "linux"))]
+fn clear_all_browsing_data(webview: &WebView) -> Result<()> {
+    webview
+        .clear_all_browsing_data()
+        .map_err(|e| RendererError::Other(format!("Failed to clear browsing data: {}", e)))
+}
+
+/// Forwards `console.*` calls to the IPC handler, keeping the original output
+const CONSOLE_CAPTURE_SCRIPT: &str = r#"(function() {
+    ['debug', 'log', 'info', 'warn', 'error'].forEach(function(level) {
+        var original = console[level];
+        console[level] = function() {
+            try {
+                var message = Array.prototype.map.call(arguments, function(arg) {
+                    if (typeof arg === 'string') return arg;
+                    try { return JSON.stringify(arg); } catch (e) { return String(arg); }
+                }).join(' ');
+                window.ipc.postMessage(JSON.stringify({
+                    type: 'console', level: level, message: message, source: location.href
+                }));
+            } catch (e) {}
+            return original.apply(console, arguments);
+        };
+    });
+})();"#;
+
+/// Sets `spellcheck` on editable fields as they gain focus, from
+/// `window.__browserSpellcheck` (enabled unless `false`)
+const SPELLCHECK_SCRIPT: &str = r#"(function() {
+    document.addEventListener('focusin', function(event) {
+        var target = event.target;
+        if (target && (target.isContentEditable
+                || target.tagName === 'INPUT' || target.tagName === 'TEXTAREA')) {
+            target.spellcheck = window.__browserSpellcheck !== false;
+        }
+    }, true);
+})();"#;
+
+/// Script setting `window.__browserSpellcheck`, applied to the focused field
+fn spellcheck_script(enabled: bool) -> String {
+    format!(
+        "window.__browserSpellcheck = {enabled};\
+         if (document.activeElement) {{ document.activeElement.spellcheck = {enabled}; }}"
+    )
+}
+
+/// Push the spell-check setting to the WebView and its current page
+///
+/// Failures are logged; the page keeps its previous setting.
+fn apply_spellcheck(webview: &WebView, enabled: bool) {
+    #[cfg(target_os = "linux")]
+    {
+        use webkit2gtk::{WebContextExt, WebViewExt};
+        use wry::WebViewExtUnix;
+
+        match webview.webview().context() {
+            Some(context) => context.set_spell_checking_enabled(enabled),
+            None => error!("WebKitGTK context unavailable, spell checker not toggled"),
+        }
     }
 
-    #[test]
-    fn test_state_management() {
-        let state = WebViewState::new();
+    if let Err(e) = webview.evaluate_script(&spellcheck_script(enabled)) {
+        debug!("Failed to apply spell-check setting to page: {}", e);
+    }
+}
 
-        state.set_url("https://example.com".to_string());
-        assert_eq!(state.get_url(), "https://example.com");
+/// Mutes `<audio>`/`<video>` while `window.__browserMuted` is set, including
+/// elements added later or played without being attached
+///
+/// Elements it muted are tagged so unmuting leaves the page's own muting alone.
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+const MUTE_SCRIPT: &str = r#"(function() {
+    function apply(media) {
+        if (window.__browserMuted) {
+            if (!media.muted) {
+                media.muted = true;
+                media.dataset.browserMuted = '';
+            }
+        } else if (media.dataset.browserMuted !== undefined) {
+            media.muted = false;
+            delete media.dataset.browserMuted;
+        }
+    }
+    window.__browserApplyMute = function() {
+        document.querySelectorAll('audio, video').forEach(apply);
+    };
+    new MutationObserver(function(mutations) {
+        if (!window.__browserMuted) return;
+        mutations.forEach(function(mutation) {
+            mutation.addedNodes.forEach(function(node) {
+                if (node.nodeType !== Node.ELEMENT_NODE) return;
+                if (node.matches('audio, video')) apply(node);
+                node.querySelectorAll('audio, video').forEach(apply);
+            });
+        });
+    }).observe(document, { childList: true, subtree: true });
+    var play = HTMLMediaElement.prototype.play;
+    HTMLMediaElement.prototype.play = function() {
+        apply(this);
+        return play.apply(this, arguments);
+    };
+})();"#;
 
-        state.set_title("Example".to_string());
-        assert_eq!(state.get_title(), "Example");
+/// Script setting `window.__browserMuted` and applying it to current media
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+fn mute_script(muted: bool) -> String {
+    format!(
+        "window.__browserMuted = {muted};\
+         if (window.__browserApplyMute) {{ window.__browserApplyMute(); }}"
+    )
+}
 
-        state.set_loading(true);
-        assert!(state.is_loading());
+/// Push the mute setting to the WebView
+fn apply_mute(webview: &WebView, muted: bool) {
+    #[cfg(target_os = "linux")]
+    {
+        use webkit2gtk::WebViewExt;
+        use wry::WebViewExtUnix;
+
+        webview.webview().set_is_muted(muted);
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    if let Err(e) = webview.evaluate_script(&mute_script(muted)) {
+        debug!("Failed to apply mute setting to page: {}", e);
+    }
+}
+
+/// Push all page settings to the WebView and its current page
+fn apply_page_settings(webview: &WebView, settings: PageSettings) {
+    apply_spellcheck(webview, settings.spellcheck);
+    apply_mute(webview, settings.muted);
+}
+
+/// Reports media elements starting and stopping to the IPC handler
+///
+/// Each element gets an id on first play. Elements outside the document
+/// (`new Audio()`) are picked up through `play()`.
+const MEDIA_STATE_SCRIPT: &str = r#"(function() {
+    var nextId = 1;
+    function report(media, playing) {
+        if (media.__browserPlaying === playing) return;
+        media.__browserPlaying = playing;
+        try {
+            window.ipc.postMessage(JSON.stringify({
+                type: 'media', id: media.__browserMediaId, playing: playing
+            }));
+        } catch (e) {}
+    }
+    function track(media) {
+        if (media.__browserMediaId) return;
+        media.__browserMediaId = nextId++;
+        media.addEventListener('playing', function() { report(media, true); });
+        ['pause', 'ended', 'emptied'].forEach(function(type) {
+            media.addEventListener(type, function() { report(media, false); });
+        });
+    }
+    document.addEventListener('play', function(event) {
+        if (event.target instanceof HTMLMediaElement) track(event.target);
+    }, true);
+    var play = HTMLMediaElement.prototype.play;
+    HTMLMediaElement.prototype.play = function() {
+        track(this);
+        return play.apply(this, arguments);
+    };
+})();"#;
+
+/// Reports the Fullscreen API's state to the IPC handler
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+const FULLSCREEN_SCRIPT: &str = r#"(function() {
+    document.addEventListener('fullscreenchange', function() {
+        try {
+            window.ipc.postMessage(JSON.stringify({
+                type: 'fullscreen', active: document.fullscreenElement !== null
+            }));
+        } catch (e) {}
+    });
+})();"#;
+
+/// Replaces the native context menu with an IPC message describing what
+/// was clicked
+const CONTEXT_MENU_SCRIPT: &str = r#"(function() {
+    document.addEventListener('contextmenu', function(event) {
+        var target = event.target instanceof Element ? event.target : null;
+        var link = target ? target.closest('a[href]') : null;
+        var image = target ? target.closest('img') : null;
+        event.preventDefault();
+        try {
+            window.ipc.postMessage(JSON.stringify({
+                type: 'contextmenu',
+                x: event.clientX,
+                y: event.clientY,
+                pageUrl: location.href,
+                linkUrl: link ? String(link.href) : null,
+                selectionText: String(window.getSelection() || ''),
+                imageUrl: image ? (image.currentSrc || image.src) : null
+            }));
+        } catch (e) {}
+    }, true);
+})();"#;
+
+/// Reports an HTTP error status of the page's own response to the IPC handler
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+const HTTP_STATUS_SCRIPT: &str = r#"(function() {
+    window.addEventListener('DOMContentLoaded', function() {
+        try {
+            var entry = performance.getEntriesByType('navigation')[0];
+            if (entry && entry.responseStatus >= 400) {
+                window.ipc.postMessage(JSON.stringify({
+                    type: 'httpstatus', status: entry.responseStatus, url: location.href
+                }));
+            }
+        } catch (e) {}
+    });
+})();"#;
+
+/// Reports the page's scroll offset to the IPC handler, once per frame at most
+///
+/// Public for WebViews built without `WryRenderer`; decode the messages
+/// with `parse_scroll_message`.
+pub const SCROLL_TRACKING_SCRIPT: &str = r#"(function() {
+    var pending = false;
+    window.addEventListener('scroll', function() {
+        if (pending) return;
+        pending = true;
+        requestAnimationFrame(function() {
+            pending = false;
+            try {
+                window.ipc.postMessage(JSON.stringify({
+                    type: 'scroll', x: window.scrollX, y: window.scrollY
+                }));
+            } catch (e) {}
+        });
+    }, { passive: true });
+})();"#;
+
+/// Handlers for messages posted by the injected scripts
+struct IpcCallbacks {
+    state: WebViewState,
+    on_fullscreen: FullscreenHandler,
+    context_menu_handler: Option<ContextMenuHandler>,
+}
+
+impl IpcCallbacks {
+    /// Dispatch one IPC message body
+    fn handle(&self, body: &str) {
+        if let Some(message) = parse_console_message(body) {
+            self.state.push_console_message(message);
+        } else if let Some(media) = parse_media_message(body) {
+            self.state.media_playback_changed(media.id, media.playing);
+        } else if let Some(fullscreen) = parse_fullscreen_message(body) {
+            (self.on_fullscreen)(fullscreen);
+        } else if let Some(error) = parse_http_status_message(body) {
+            self.state.page_load_failed(error);
+        } else if let Some((x, y)) = parse_scroll_message(body) {
+            self.state.set_scroll_position(x, y);
+        } else if let Some(context) = parse_context_menu_message(body) {
+            self.show_context_menu(context);
+        }
+    }
+
+    /// Ask the handler for entries and queue the menu if there are any
+    fn show_context_menu(&self, context: ContextMenuContext) {
+        let Some(handler) = &self.context_menu_handler else {
+            return;
+        };
+        let items = handler(context.clone());
+        debug!("Context menu with {} items", items.len());
+        if !items.is_empty() {
+            self.state.set_context_menu(ContextMenu { context, items });
+        }
+    }
+}
+
+/// Error status as posted by `HTTP_STATUS_SCRIPT`
+#[derive(serde::Deserialize)]
+struct HttpStatusIpcMessage {
+    #[serde(rename = "type")]
+    kind: String,
+    status: u16,
+    url: String,
+}
+
+/// Parse an IPC body into a load error, None for anything else
+fn parse_http_status_message(body: &str) -> Option<LoadError> {
+    let ipc: HttpStatusIpcMessage = serde_json::from_str(body).ok()?;
+    if ipc.kind != "httpstatus" {
+        return None;
+    }
+    Some(LoadError {
+        kind: LoadErrorKind::from_status(ipc.status)?,
+        url: ipc.url,
+        message: format!("HTTP status {}", ipc.status),
+    })
+}
+
+/// Scroll offset as posted by `SCROLL_TRACKING_SCRIPT`
+#[derive(serde::Deserialize)]
+struct ScrollIpcMessage {
+    #[serde(rename = "type")]
+    kind: String,
+    x: f64,
+    y: f64,
+}
+
+/// Parse an IPC body into a scroll offset, None for anything else
+pub fn parse_scroll_message(body: &str) -> Option<(f64, f64)> {
+    let ipc: ScrollIpcMessage = serde_json::from_str(body).ok()?;
+    (ipc.kind == "scroll").then_some((ipc.x, ipc.y))
+}
+
+/// Right-click as posted by `CONTEXT_MENU_SCRIPT`
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContextMenuIpcMessage {
+    #[serde(rename = "type")]
+    kind: String,
+    x: f64,
+    y: f64,
+    page_url: Option<String>,
+    link_url: Option<String>,
+    selection_text: Option<String>,
+    image_url: Option<String>,
+}
+
+/// Parse an IPC body into a context-menu context, None for anything else
+///
+/// Blank strings become None and the selection is trimmed.
+fn parse_context_menu_message(body: &str) -> Option<ContextMenuContext> {
+    let ipc: ContextMenuIpcMessage = serde_json::from_str(body).ok()?;
+    if ipc.kind != "contextmenu" {
+        return None;
+    }
+
+    let non_blank = |value: Option<String>| {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    Some(ContextMenuContext {
+        x: ipc.x,
+        y: ipc.y,
+        page_url: non_blank(ipc.page_url),
+        link_url: non_blank(ipc.link_url),
+        selection_text: non_blank(ipc.selection_text),
+        image_url: non_blank(ipc.image_url),
+    })
+}
+
+/// Fullscreen change as posted by `FULLSCREEN_SCRIPT`
+#[derive(serde::Deserialize)]
+struct FullscreenIpcMessage {
+    #[serde(rename = "type")]
+    kind: String,
+    active: bool,
+}
+
+/// Parse an IPC body into the new fullscreen state, None for anything else
+fn parse_fullscreen_message(body: &str) -> Option<bool> {
+    let ipc: FullscreenIpcMessage = serde_json::from_str(body).ok()?;
+    (ipc.kind == "fullscreen").then_some(ipc.active)
+}
+
+/// Playback change as posted by `MEDIA_STATE_SCRIPT`
+#[derive(serde::Deserialize)]
+struct MediaIpcMessage {
+    #[serde(rename = "type")]
+    kind: String,
+    id: u32,
+    playing: bool,
+}
+
+/// Parse an IPC body into a playback change, None for anything else
+fn parse_media_message(body: &str) -> Option<MediaIpcMessage> {
+    serde_json::from_str(body)
+        .ok()
+        .filter(|ipc: &MediaIpcMessage| ipc.kind == "media")
+}
+
+/// Console message as posted by `CONSOLE_CAPTURE_SCRIPT`
+#[derive(serde::Deserialize)]
+struct ConsoleIpcMessage {
+    #[serde(rename = "type")]
+    kind: String,
+    level: ConsoleLevel,
+    message: String,
+    source: Option<String>,
+}
+
+/// Parse an IPC body into a console message, None for anything else
+fn parse_console_message(body: &str) -> Option<ConsoleMessage> {
+    let ipc: ConsoleIpcMessage = serde_json::from_str(body).ok()?;
+    if ipc.kind != "console" {
+        return None;
+    }
+    Some(ConsoleMessage {
+        level: ipc.level,
+        message: ipc.message,
+        source: ipc.source.filter(|source| !source.is_empty()),
+    })
+}
+
+/// Resolves the first declared icon link to an absolute URL, or null
+const FAVICON_SCRIPT: &str =
+    "(function() { var l = document.querySelector('link[rel~=\"icon\"]'); return l ? l.href : null; })()";
+
+/// Conventional `/favicon.ico` location for a page URL
+///
+/// Only http(s) pages have one; returns None for anything else.
+fn fallback_favicon_url(page_url: &str) -> Option<String> {
+    let url = Url::parse(page_url).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    url.join("/favicon.ico").ok().map(String::from)
+}
+
+/// None for an empty string
+fn non_empty(value: &str) -> Option<String> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+/// Build a `window.scrollBy`/`window.scrollTo` call
+///
+/// Non-finite coordinates are treated as 0 so the script stays valid.
+fn scroll_script(method: &str, x: f64, y: f64) -> String {
+    let finite = |v: f64| if v.is_finite() { v } else { 0.0 };
+    format!("window.{}({}, {});", method, finite(x), finite(y))
+}
+
+/// Look up a WHATWG encoding label
+///
+/// The replacement encoding (labels like `iso-2022-kr`) is rejected since
+/// it decodes every page to a single error character.
+fn charset_encoding(charset: &str) -> Result<&'static encoding_rs::Encoding> {
+    encoding_rs::Encoding::for_label(charset.trim().as_bytes())
+        .filter(|&encoding| encoding != encoding_rs::REPLACEMENT)
+        .ok_or_else(|| RendererError::UnknownCharset(charset.to_string()))
+}
+
+/// Decode `bytes` and wrap them in a UTF-8 `data:` URL
+///
+/// A `<base>` element keeps the page's relative links and resources
+/// resolving against its original URL. The `data:` URL's charset takes
+/// precedence over any `<meta charset>` in the page.
+fn decoded_data_url(bytes: &[u8], encoding: &'static encoding_rs::Encoding, base: &Url) -> String {
+    let (text, _) = encoding.decode_without_bom_handling(bytes);
+    let base_tag = format!(
+        "<base href=\"{}\">",
+        base.as_str().replace('&', "&amp;").replace('"', "&quot;")
+    );
+
+    // After <head ...> if there is one, so a leading doctype stays first
+    let lower = text.to_ascii_lowercase();
+    let insert_at = lower
+        .find("<head")
+        .filter(|&start| lower[start + "<head".len()..].starts_with(['>', ' ', '\t', '\n', '\r']))
+        .and_then(|start| lower[start..].find('>').map(|end| start + end + 1))
+        .unwrap_or(0);
+
+    let mut page = String::with_capacity(text.len() + base_tag.len());
+    page.push_str(&text[..insert_at]);
+    page.push_str(&base_tag);
+    page.push_str(&text[insert_at..]);
+
+    format!(
+        "data:text/html;charset=utf-8,{}",
+        urlencoding::encode(&page)
+    )
+}
+
+/// Fetch a page's raw bytes, blocking the calling (worker) thread until done
+#[cfg(not(target_os = "linux"))]
+fn fetch_page_bytes(url: Url) -> Result<Vec<u8>> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| RendererError::Other(format!("Failed to start fetch runtime: {}", e)))?;
+    let client =
+        network::HttpClient::new().map_err(|e| RendererError::LoadFailed(e.to_string()))?;
+
+    runtime
+        .block_on(client.fetch_bytes(url))
+        .map_err(|e| RendererError::LoadFailed(e.to_string()))
+}
+
+/// Convert header pairs to an HTTP header map, rejecting invalid names/values
+fn build_header_map(headers: &HashMap<String, String>) -> Result<HeaderMap> {
+    let mut header_map = HeaderMap::with_capacity(headers.len());
+
+    for (name, value) in headers {
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| RendererError::LoadFailed(format!("Invalid header name: {:?}", name)))?;
+        let header_value = HeaderValue::from_str(value).map_err(|_| {
+            RendererError::LoadFailed(format!("Invalid value for header {:?}", name))
+        })?;
+        header_map.insert(header_name, header_value);
+    }
+
+    Ok(header_map)
+}
+
+impl Default for WryRenderer {
+    fn default() -> Self {
+        Self::new().expect("Failed to create WryRenderer")
+    }
+}
+
+impl Drop for WryRenderer {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_renderer() {
+        let renderer = WryRenderer::new();
+        assert!(renderer.is_ok());
+    }
+
+    #[test]
+    fn test_focus_without_webview() {
+        let renderer = WryRenderer::new().unwrap();
+        assert!(matches!(
+            renderer.focus(),
+            Err(RendererError::NotInitialized)
+        ));
+    }
+
+    #[test]
+    fn test_print_to_pdf_without_webview() {
+        let renderer = WryRenderer::new().unwrap();
+        for options in [None, Some(PdfOptions::default())] {
+            let result = renderer.print_to_pdf(Path::new("/tmp/page.pdf"), options, |_| {
+                panic!("no print job without a WebView")
+            });
+            assert!(matches!(result, Err(RendererError::NotInitialized)));
+        }
+    }
+
+    #[test]
+    fn test_state_management() {
+        let state = WebViewState::new();
+
+        state.set_url("https://example.com".to_string());
+        assert_eq!(state.get_url(), "https://example.com");
+
+        state.set_title("Example".to_string());
+        assert_eq!(state.get_title(), "Example");
+
+        state.set_loading(true);
+        assert!(state.is_loading());
+    }
+
+    #[test]
+    fn test_spellcheck_state() {
+        let renderer = WryRenderer::new().unwrap();
+        assert!(renderer.spellcheck_enabled());
+
+        // Stored for create_webview() even without a WebView
+        assert!(matches!(
+            renderer.set_spellcheck(false),
+            Err(RendererError::NotInitialized)
+        ));
+        assert!(!renderer.spellcheck_enabled());
+
+        // Survives navigations, and each loaded page gets it once
+        let state = &renderer.state;
+        assert_eq!(state.take_settings_for_page(), None);
+        state.reset_progress();
+        state.page_load_started();
+        state.page_load_finished();
+        let settings = state.take_settings_for_page().unwrap();
+        assert!(!settings.spellcheck);
+        assert_eq!(state.take_settings_for_page(), None);
+        assert!(!renderer.spellcheck_enabled());
+    }
+
+    #[test]
+    fn test_spellcheck_script() {
+        assert!(spellcheck_script(false).starts_with("window.__browserSpellcheck = false;"));
+        assert!(spellcheck_script(true).contains("activeElement.spellcheck = true"));
+    }
+
+    #[test]
+    fn test_muted_state() {
+        let renderer = WryRenderer::new().unwrap();
+        assert!(!renderer.is_muted());
+
+        // Stored for create_webview() even without a WebView
+        assert!(matches!(
+            renderer.set_muted(true),
+            Err(RendererError::NotInitialized)
+        ));
+        assert!(renderer.is_muted());
+        assert!(renderer.spellcheck_enabled());
+
+        let _ = renderer.set_muted(false);
+        assert!(!renderer.is_muted());
+    }
+
+    #[test]
+    fn test_mute_reapplied_on_load() {
+        let state = WebViewState::new();
+        state.set_muted(true);
+
+        // Nothing to push until a page loads
+        assert_eq!(state.take_settings_for_page(), None);
+
+        for url in ["https://example.com", "https://example.org"] {
+            state.set_url(url.to_string());
+            state.reset_progress();
+            state.page_load_started();
+            assert_eq!(state.take_settings_for_page(), None);
+            state.page_load_finished();
+
+            let settings = state.take_settings_for_page().unwrap();
+            assert!(settings.muted);
+            assert_eq!(state.take_settings_for_page(), None);
+        }
+
+        // The latest setting is what the next page gets
+        state.set_muted(false);
+        state.page_load_finished();
+        assert!(!state.take_settings_for_page().unwrap().muted);
+    }
+
+    #[test]
+    fn test_mute_script() {
+        assert!(mute_script(true).starts_with("window.__browserMuted = true;"));
+        assert!(mute_script(false).contains("__browserApplyMute()"));
+        assert!(MUTE_SCRIPT.contains("MutationObserver"));
+    }
+
+    #[test]
+    fn test_audio_playing_transitions() {
+        let renderer = WryRenderer::new().unwrap();
+        let state = &renderer.state;
+        let ipc = renderer.ipc_callbacks();
+        assert!(!renderer.is_audio_playing());
+
+        ipc.handle(r#"{"type":"media","id":1,"playing":true}"#);
+        assert!(renderer.is_audio_playing());
+
+        // Playing while any element is
+        ipc.handle(r#"{"type":"media","id":2,"playing":true}"#);
+        ipc.handle(r#"{"type":"media","id":1,"playing":false}"#);
+        assert!(renderer.is_audio_playing());
+        ipc.handle(r#"{"type":"media","id":2,"playing":false}"#);
+        assert!(!renderer.is_audio_playing());
+
+        // Repeated or unknown stops are harmless
+        ipc.handle(r#"{"type":"media","id":2,"playing":false}"#);
+        ipc.handle(r#"{"type":"media","id":7,"playing":false}"#);
+        assert!(!renderer.is_audio_playing());
+
+        // A new page starts silent
+        ipc.handle(r#"{"type":"media","id":3,"playing":true}"#);
+        state.page_load_started();
+        assert!(!renderer.is_audio_playing());
+    }
+
+    #[test]
+    fn test_parse_media_message() {
+        let media = parse_media_message(r#"{"type":"media","id":4,"playing":true}"#).unwrap();
+        assert_eq!(media.id, 4);
+        assert!(media.playing);
+
+        assert!(parse_media_message(r#"{"type":"console","id":4,"playing":true}"#).is_none());
+        assert!(parse_media_message(r#"{"type":"media","id":-1,"playing":true}"#).is_none());
+        assert!(parse_media_message("not json").is_none());
+
+        // Console messages don't touch playback state
+        let renderer = WryRenderer::new().unwrap();
+        let state = &renderer.state;
+        renderer
+            .ipc_callbacks()
+            .handle(r#"{"type":"console","level":"log","message":"playing","source":""}"#);
+        assert!(!state.is_audio_playing());
+        assert_eq!(state.drain_console_messages().len(), 1);
+    }
+
+    #[test]
+    fn test_fullscreen_handler_plumbing() {
+        let mut renderer = WryRenderer::new().unwrap();
+
+        // Without a handler changes are only recorded
+        renderer.fullscreen_callback()(true);
+        assert!(renderer.is_fullscreen());
+        renderer.fullscreen_callback()(false);
+
+        let reported = Rc::new(Mutex::new(Vec::new()));
+        let sink = reported.clone();
+        renderer.set_on_fullscreen(move |fullscreen| sink.lock().unwrap().push(fullscreen));
+
+        let on_fullscreen = renderer.fullscreen_callback();
+        on_fullscreen(true);
+        assert!(renderer.is_fullscreen());
+        // Repeats aren't reported
+        on_fullscreen(true);
+        on_fullscreen(false);
+        assert!(!renderer.is_fullscreen());
+        assert_eq!(*reported.lock().unwrap(), vec![true, false]);
+    }
+
+    #[test]
+    fn test_fullscreen_ipc_message() {
+        assert_eq!(
+            parse_fullscreen_message(r#"{"type":"fullscreen","active":true}"#),
+            Some(true)
+        );
+        assert_eq!(
+            parse_fullscreen_message(r#"{"type":"media","active":true}"#),
+            None
+        );
+
+        let mut renderer = WryRenderer::new().unwrap();
+        let reported = Rc::new(Mutex::new(Vec::new()));
+        let sink = reported.clone();
+        renderer.set_on_fullscreen(move |fullscreen| sink.lock().unwrap().push(fullscreen));
+
+        let ipc = renderer.ipc_callbacks();
+        for active in [true, false] {
+            ipc.handle(&format!(r#"{{"type":"fullscreen","active":{active}}}"#));
+        }
+        assert_eq!(*reported.lock().unwrap(), vec![true, false]);
+    }
+
+    #[test]
+    fn test_parse_context_menu_message() {
+        let context = parse_context_menu_message(
+            r#"{"type":"contextmenu","x":120.5,"y":48,"pageUrl":"https://example.com/",
+                "linkUrl":"https://example.com/docs","selectionText":"  Example Domain \n",
+                "imageUrl":"https://example.com/logo.png"}"#,
+        )
+        .unwrap();
+        assert_eq!(context.x, 120.5);
+        assert_eq!(context.y, 48.0);
+        assert_eq!(context.page_url.as_deref(), Some("https://example.com/"));
+        assert_eq!(
+            context.link_url.as_deref(),
+            Some("https://example.com/docs")
+        );
+        assert_eq!(context.selection_text.as_deref(), Some("Example Domain"));
+        assert!(context.is_link());
+        assert!(context.is_image());
+
+        // Plain text click: blank selection, no link or image
+        let context = parse_context_menu_message(
+            r#"{"type":"contextmenu","x":0,"y":0,"pageUrl":"https://example.com/",
+                "linkUrl":null,"selectionText":"   ","imageUrl":null}"#,
+        )
+        .unwrap();
+        assert_eq!(context.selection_text, None);
+        assert!(!context.is_link());
+        assert!(!context.is_image());
+
+        assert!(parse_context_menu_message(r#"{"type":"media","x":0,"y":0}"#).is_none());
+        assert!(parse_context_menu_message("not json").is_none());
+    }
+
+    #[test]
+    fn test_context_menu_handler() {
+        let payload = r#"{"type":"contextmenu","x":10,"y":20,"linkUrl":"https://example.com/a"}"#;
+
+        // No handler: nothing queued
+        let renderer = WryRenderer::new().unwrap();
+        renderer.ipc_callbacks().handle(payload);
+        assert_eq!(renderer.take_context_menu(), None);
+
+        let mut renderer = WryRenderer::new().unwrap();
+        renderer.set_context_menu_handler(|context| {
+            let mut items = Vec::new();
+            if context.is_link() {
+                items.push(MenuItem::action("open-in-new-tab", "Open in New Tab"));
+                items.push(MenuItem::Separator);
+            }
+            if context.selection_text.is_none() {
+                items.push(MenuItem::action("copy", "Copy").disabled());
+            }
+            items
+        });
+        renderer.ipc_callbacks().handle(payload);
+
+        let menu = renderer.take_context_menu().unwrap();
+        assert_eq!(
+            menu.context.link_url.as_deref(),
+            Some("https://example.com/a")
+        );
+        assert_eq!(
+            menu.items,
+            vec![
+                MenuItem::action("open-in-new-tab", "Open in New Tab"),
+                MenuItem::Separator,
+                MenuItem::Action {
+                    id: "copy".to_string(),
+                    label: "Copy".to_string(),
+                    enabled: false,
+                },
+            ]
+        );
+        assert_eq!(renderer.take_context_menu(), None);
+
+        // An empty menu isn't queued
+        renderer.set_context_menu_handler(|_| Vec::new());
+        renderer.ipc_callbacks().handle(payload);
+        assert_eq!(renderer.take_context_menu(), None);
+    }
+
+    #[test]
+    fn test_load_progress_cycle() {
+        let state = WebViewState::new();
+        assert_eq!(state.get_progress(), 0.0);
+
+        // Progress outside a load is ignored
+        state.set_load_progress(0.5);
+        assert_eq!(state.get_progress(), 0.0);
+
+        state.page_load_started();
+        assert!(state.is_loading());
+        assert_eq!(state.get_progress(), LOAD_STARTED_PROGRESS);
+
+        state.set_load_progress(0.6);
+        assert_eq!(state.get_progress(), 0.6);
+        // Never backwards, never past 1.0
+        state.set_load_progress(0.3);
+        assert_eq!(state.get_progress(), 0.6);
+        state.set_load_progress(1.5);
+        assert_eq!(state.get_progress(), 1.0);
+
+        state.page_load_finished();
+        assert!(!state.is_loading());
+        assert_eq!(state.get_progress(), 1.0);
+
+        // Next navigation starts from zero
+        state.reset_progress();
+        assert_eq!(state.get_progress(), 0.0);
+        state.page_load_started();
+        assert_eq!(state.get_progress(), LOAD_STARTED_PROGRESS);
+    }
+
+    #[test]
+    fn test_resize_clamped_to_max_size() {
+        let config = RendererConfig::builder()
+            .device_pixel_ratio(2.0)
+            .max_size(4096)
+            .build()
+            .unwrap();
+        let mut renderer = WryRenderer::with_config(&config).unwrap();
+        assert_eq!(renderer.size(), (1024, 768));
+
+        assert_eq!(renderer.resize(1280, 720).unwrap(), (1280, 720));
+        // 16000 logical pixels is 32000 device pixels at 2x
+        assert_eq!(renderer.resize(16000, 900).unwrap(), (2048, 900));
+        assert_eq!(renderer.size(), (2048, 900));
+    }
+
+    #[test]
+    fn test_content_preferences_toggles() {
+        let mut renderer = WryRenderer::new().unwrap();
+        assert_eq!(renderer.preferences(), ContentPreferences::default());
+        assert!(renderer.preferences_pending());
+
+        renderer.set_javascript_enabled(false);
+        renderer.set_images_enabled(false);
+        assert_eq!(
+            renderer.preferences(),
+            ContentPreferences {
+                javascript: false,
+                images: false,
+                webgl: true,
+            }
+        );
+
+        // Nothing to apply to until a WebView exists
+        assert!(matches!(
+            renderer.load_url("https://example.com"),
+            Err(RendererError::NotInitialized)
+        ));
+        assert!(renderer.preferences_pending());
+
+        renderer.set_preferences(ContentPreferences::safe());
+        assert!(!renderer.preferences().javascript);
+        assert!(!renderer.preferences().webgl);
+    }
+
+    #[test]
+    fn test_with_config() {
+        let config = RendererConfig::builder()
+            .enable_javascript(false)
+            .build()
+            .unwrap();
+        let renderer = WryRenderer::with_config(&config).unwrap();
+        assert!(!renderer.preferences().javascript);
+        assert!(renderer.preferences().images);
+
+        let config = RendererConfig {
+            width: 0,
+            ..RendererConfig::default()
+        };
+        assert!(matches!(
+            WryRenderer::with_config(&config),
+            Err(RendererError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let renderer = WryRenderer::new().unwrap();
+        assert_eq!(renderer.snapshot(), PageSnapshot::default());
+
+        renderer.state.set_url("https://example.com".to_string());
+        renderer.state.set_title("Example".to_string());
+        renderer.state.set_loading(true);
+
+        assert_eq!(
+            renderer.snapshot(),
+            PageSnapshot {
+                url: Some("https://example.com".to_string()),
+                title: Some("Example".to_string()),
+                loading: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_favicon_url_state() {
+        let state = WebViewState::new();
+        assert_eq!(state.get_favicon_url(), None);
+
+        state.set_url("https://example.com/".to_string());
+        state.set_favicon_url(Some("https://example.com/icon.png".to_string()));
+        assert_eq!(
+            state.get_favicon_url().as_deref(),
+            Some("https://example.com/icon.png")
+        );
+
+        // Same URL keeps the icon, a new one clears it
+        state.set_url("https://example.com/".to_string());
+        assert!(state.get_favicon_url().is_some());
+        state.set_url("https://example.org/".to_string());
+        assert_eq!(state.get_favicon_url(), None);
+    }
+
+    #[test]
+    fn test_get_favicon_url_falls_back() {
+        let renderer = WryRenderer::new().unwrap();
+        assert_eq!(renderer.get_favicon_url(), None);
+
+        renderer
+            .state
+            .set_url("https://example.com/a/b?q=1#top".to_string());
+        assert_eq!(
+            renderer.get_favicon_url().as_deref(),
+            Some("https://example.com/favicon.ico")
+        );
+
+        renderer
+            .state
+            .set_favicon_url(Some("https://cdn.example.com/i.png".to_string()));
+        assert_eq!(
+            renderer.get_favicon_url().as_deref(),
+            Some("https://cdn.example.com/i.png")
+        );
+    }
+
+    #[test]
+    fn test_fallback_favicon_url() {
+        assert_eq!(
+            fallback_favicon_url("http://localhost:8080/page").as_deref(),
+            Some("http://localhost:8080/favicon.ico")
+        );
+        assert_eq!(fallback_favicon_url("about:blank"), None);
+        assert_eq!(fallback_favicon_url("file:///tmp/a.html"), None);
+        assert_eq!(fallback_favicon_url("not a url"), None);
+    }
+
+    #[test]
+    fn test_refresh_favicon_without_webview() {
+        let renderer = WryRenderer::new().unwrap();
+        assert!(matches!(
+            renderer.refresh_favicon_url(),
+            Err(RendererError::NotInitialized)
+        ));
+    }
+
+    fn console_message(n: usize) -> ConsoleMessage {
+        ConsoleMessage {
+            level: ConsoleLevel::Log,
+            message: format!("message {}", n),
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_console_buffer_evicts_oldest() {
+        let state = WebViewState::new();
+        for n in 0..MAX_CONSOLE_MESSAGES + 5 {
+            state.push_console_message(console_message(n));
+        }
+
+        let messages = state.drain_console_messages();
+        assert_eq!(messages.len(), MAX_CONSOLE_MESSAGES);
+        assert_eq!(messages[0].message, "message 5");
+        assert_eq!(
+            messages.last().unwrap().message,
+            format!("message {}", MAX_CONSOLE_MESSAGES + 4)
+        );
+    }
+
+    #[test]
+    fn test_drain_console_messages_clears() {
+        let renderer = WryRenderer::new().unwrap();
+        renderer.state.push_console_message(console_message(1));
+        renderer.state.push_console_message(console_message(2));
+
+        assert_eq!(renderer.drain_console_messages().len(), 2);
+        assert!(renderer.drain_console_messages().is_empty());
+    }
+
+    #[test]
+    fn test_parse_console_message() {
+        let message = parse_console_message(
+            r#"{"type":"console","level":"warn","message":"careful","source":"https://example.com/"}"#,
+        )
+        .unwrap();
+        assert_eq!(message.level, ConsoleLevel::Warn);
+        assert_eq!(message.message, "careful");
+        assert_eq!(message.source.as_deref(), Some("https://example.com/"));
+
+        assert!(parse_console_message(r#"{"type":"other","level":"log","message":"x"}"#).is_none());
+        assert!(
+            parse_console_message(r#"{"type":"console","level":"trace","message":"x"}"#).is_none()
+        );
+        assert!(parse_console_message("not json").is_none());
+    }
+
+    #[test]
+    fn test_clear_data_without_webview() {
+        let renderer = WryRenderer::new().unwrap();
+        assert!(matches!(
+            renderer.clear_cookies(),
+            Err(RendererError::NotInitialized)
+        ));
+        assert!(matches!(
+            renderer.clear_cache(),
+            Err(RendererError::NotInitialized)
+        ));
+    }
+
+    #[test]
+    fn test_new_window_handler_plumbing() {
+        let mut renderer = WryRenderer::new().unwrap();
+        assert!(renderer.new_window_req_handler().is_none());
+
+        let requested = Rc::new(Mutex::new(Vec::new()));
+        let seen = requested.clone();
+        renderer.set_on_new_window(move |url| {
+            seen.lock().unwrap().push(url.clone());
+            if url.contains("same") {
+                NewWindowAction::OpenInSameTab
+            } else if url.contains("deny") {
+                NewWindowAction::Deny
+            } else {
+                NewWindowAction::OpenInNewTab
+            }
+        });
+
+        let callback = renderer.new_window_req_handler().unwrap();
+        assert!(!callback("https://example.com/new".to_string()));
+        assert!(!callback("https://example.com/deny".to_string()));
+        assert_eq!(renderer.state.take_pending_navigation(), None);
+
+        assert!(!callback("https://example.com/same".to_string()));
+        assert_eq!(
+            renderer.state.take_pending_navigation().as_deref(),
+            Some("https://example.com/same")
+        );
+        assert_eq!(requested.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_dialog_handler_plumbing() {
+        let confirm = DialogRequest {
+            kind: crate::DialogKind::Confirm,
+            message: "Leave?".to_string(),
+            default_text: None,
+        };
+
+        let mut renderer = WryRenderer::new().unwrap();
+        assert_eq!(
+            renderer.dialog_handler()(confirm.clone()),
+            DialogResponse::Cancel
+        );
+
+        renderer.set_dialog_handler(|_| DialogResponse::Ok);
+        assert_eq!(renderer.dialog_handler()(confirm), DialogResponse::Ok);
+    }
+
+    #[test]
+    fn test_permission_handler_defaults_to_deny() {
+        let request = PermissionRequest {
+            origin: "https://example.com".to_string(),
+            kind: crate::PermissionKind::Geolocation,
+        };
+
+        let mut renderer = WryRenderer::new().unwrap();
+        assert_eq!(
+            renderer.permission_handler()(&request),
+            PermissionDecision::Deny
+        );
+
+        renderer.set_permission_handler(|_| PermissionDecision::Allow);
+        assert_eq!(
+            renderer.permission_handler()(&request),
+            PermissionDecision::Allow
+        );
+    }
+
+    #[test]
+    fn test_origin_of() {
+        assert_eq!(
+            origin_of("https://example.com:8443/path?q=1").as_deref(),
+            Some("https://example.com:8443")
+        );
+        assert_eq!(
+            origin_of("http://example.com/").as_deref(),
+            Some("http://example.com")
+        );
+        assert_eq!(origin_of("about:blank"), None);
+        assert_eq!(origin_of("data:text/html,hi"), None);
+    }
+
+    #[test]
+    fn test_load_pending_navigation_when_empty() {
+        let mut renderer = WryRenderer::new().unwrap();
+        assert!(!renderer.load_pending_navigation().unwrap());
+    }
+
+    #[test]
+    fn test_scroll_without_webview_is_noop() {
+        let mut renderer = WryRenderer::new().unwrap();
+        assert!(renderer.scroll_by(0.0, 400.0).is_ok());
+        assert!(renderer.scroll_to(0.0, 0.0).is_ok());
+    }
+
+    #[test]
+    fn test_scroll_script() {
+        assert_eq!(
+            scroll_script("scrollBy", 0.0, 400.0),
+            "window.scrollBy(0, 400);"
+        );
+        assert_eq!(
+            scroll_script("scrollTo", 12.5, -3.0),
+            "window.scrollTo(12.5, -3);"
+        );
+        assert_eq!(
+            scroll_script("scrollBy", f64::NAN, f64::INFINITY),
+            "window.scrollBy(0, 0);"
+        );
+    }
+
+    #[test]
+    fn test_scroll_position_plumbing() {
+        let renderer = WryRenderer::new().unwrap();
+        assert!(matches!(
+            renderer.get_scroll_position(),
+            Err(RendererError::NotInitialized)
+        ));
+        assert!(matches!(
+            renderer.set_scroll_position(0.0, 300.0),
+            Err(RendererError::NotInitialized)
+        ));
+
+        // Offsets come from the page as it scrolls
+        let ipc = renderer.ipc_callbacks();
+        ipc.handle(r#"{"type":"scroll","x":0,"y":1250.5}"#);
+        assert_eq!(renderer.state.scroll_position(), (0.0, 1250.5));
+        ipc.handle(r#"{"type":"scroll","x":"left","y":0}"#);
+        assert_eq!(renderer.state.scroll_position(), (0.0, 1250.5));
+
+        // A new page starts at the top
+        renderer.state.page_load_started();
+        assert_eq!(renderer.state.scroll_position(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_charset_validation() {
+        assert_eq!(
+            charset_encoding("windows-1252").unwrap().name(),
+            "windows-1252"
+        );
+        // Labels are case-insensitive aliases
+        assert_eq!(charset_encoding("Latin1").unwrap().name(), "windows-1252");
+        assert_eq!(charset_encoding(" shift_jis ").unwrap().name(), "Shift_JIS");
+        assert_eq!(charset_encoding("UTF8").unwrap().name(), "UTF-8");
+
+        for bogus in ["", "utf-9", "klingon", "windows-1252; drop", "iso-2022-kr"] {
+            assert!(
+                matches!(
+                    charset_encoding(bogus),
+                    Err(RendererError::UnknownCharset(ref name)) if name == bogus
+                ),
+                "{:?} should be rejected",
+                bogus
+            );
+        }
+    }
+
+    #[test]
+    fn test_set_text_encoding_validates_first() {
+        let renderer = WryRenderer::new().unwrap();
+        assert!(matches!(
+            renderer.set_text_encoding("bogus"),
+            Err(RendererError::UnknownCharset(_))
+        ));
+        assert!(matches!(
+            renderer.set_text_encoding("windows-1251"),
+            Err(RendererError::NotInitialized)
+        ));
+    }
+
+    #[test]
+    fn test_decoded_data_url() {
+        let base = Url::parse("http://example.com/old/page.html?a=1&b=2").unwrap();
+        // "Café" in windows-1252
+        let bytes = b"<!DOCTYPE html><html><HEAD><meta charset=utf-8></HEAD><body>Caf\xe9</body>";
+        let encoding = charset_encoding("windows-1252").unwrap();
+
+        let data_url = decoded_data_url(bytes, encoding, &base);
+        let page = urlencoding::decode(
+            data_url
+                .strip_prefix("data:text/html;charset=utf-8,")
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            page,
+            "<!DOCTYPE html><html><HEAD>\
+             <base href=\"http://example.com/old/page.html?a=1&amp;b=2\">\
+             <meta charset=utf-8></HEAD><body>Caf\u{e9}</body>"
+        );
+
+        // No <head>: base goes first; <header> isn't a head
+        let data_url = decoded_data_url(b"<header>Hi</header>", encoding, &base);
+        let page = urlencoding::decode(data_url.split_once(',').unwrap().1).unwrap();
+        assert!(page.starts_with("<base href="));
+    }
+
+    #[test]
+    fn test_load_error_categories() {
+        let cases = [
+            // WebKitGTK / libsoup
+            (
+                "Error resolving \u{201c}nosuchhost.invalid\u{201d}: Name or service not known",
+                LoadErrorKind::Dns,
+            ),
+            (
+                "Could not connect: Connection refused",
+                LoadErrorKind::Connection,
+            ),
+            ("Socket I/O timed out", LoadErrorKind::Timeout),
+            ("Unacceptable TLS certificate", LoadErrorKind::Tls),
+            // WKWebView
+            (
+                "A server with the specified hostname could not be found.",
+                LoadErrorKind::Dns,
+            ),
+            ("The request timed out.", LoadErrorKind::Timeout),
+            (
+                "An SSL error has occurred and a secure connection to the server cannot be made.",
+                LoadErrorKind::Tls,
+            ),
+            (
+                "Could not connect to the server.",
+                LoadErrorKind::Connection,
+            ),
+            // WebView2 / Chromium
+            ("HostNameNotResolved", LoadErrorKind::Dns),
+            ("net::ERR_NAME_NOT_RESOLVED", LoadErrorKind::Dns),
+            ("net::ERR_CERT_AUTHORITY_INVALID", LoadErrorKind::Tls),
+            ("net::ERR_CONNECTION_REFUSED", LoadErrorKind::Connection),
+            ("Timeout", LoadErrorKind::Timeout),
+            ("Unknown protocol", LoadErrorKind::Other),
+            ("", LoadErrorKind::Other),
+        ];
+        for (message, expected) in cases {
+            assert_eq!(
+                LoadErrorKind::from_message(message),
+                expected,
+                "{:?}",
+                message
+            );
+        }
+
+        assert_eq!(
+            LoadErrorKind::from_status(404),
+            Some(LoadErrorKind::HttpStatus(404))
+        );
+        assert_eq!(
+            LoadErrorKind::from_status(503),
+            Some(LoadErrorKind::HttpStatus(503))
+        );
+        assert_eq!(LoadErrorKind::from_status(200), None);
+        assert_eq!(LoadErrorKind::from_status(304), None);
+        assert_eq!(LoadErrorKind::from_status(0), None);
+    }
+
+    #[test]
+    fn test_load_failure_state() {
+        let renderer = WryRenderer::new().unwrap();
+        let state = &renderer.state;
+        assert_eq!(renderer.last_error(), None);
+
+        state.reset_progress();
+        state.page_load_started();
+        state.page_load_failed(LoadError {
+            kind: LoadErrorKind::Dns,
+            url: "https://nosuchhost.invalid/".to_string(),
+            message: "Name or service not known".to_string(),
+        });
+        assert!(!renderer.is_loading());
+        assert_eq!(renderer.last_error().unwrap().kind, LoadErrorKind::Dns);
+
+        // Still reported once the platform finishes the failed load
+        state.page_load_finished();
+        assert!(renderer.last_error().is_some());
+
+        // Cleared by the next load
+        state.page_load_started();
+        assert_eq!(renderer.last_error(), None);
+
+        // HTTP error statuses reported by the page
+        let ipc = renderer.ipc_callbacks();
+        ipc.handle(r#"{"type":"httpstatus","status":404,"url":"https://example.com/missing"}"#);
+        let error = renderer.last_error().unwrap();
+        assert_eq!(error.kind, LoadErrorKind::HttpStatus(404));
+        assert_eq!(error.url, "https://example.com/missing");
+
+        state.page_load_started();
+        ipc.handle(r#"{"type":"httpstatus","status":200,"url":"https://example.com/"}"#);
+        assert_eq!(renderer.last_error(), None);
+    }
+
+    #[test]
+    fn test_shutdown() {
+        let mut renderer = WryRenderer::new().unwrap();
+        renderer.state.set_loading(true);
+        renderer
+            .state
+            .set_pending_navigation("https://example.com/".to_string());
+
+        renderer.shutdown();
+        assert!(!renderer.is_loading());
+        assert!(!renderer.load_pending_navigation().unwrap());
+        assert!(matches!(
+            renderer.eval_script("1"),
+            Err(RendererError::NotInitialized)
+        ));
+
+        // Idempotent
+        renderer.shutdown();
+    }
+
+    #[test]
+    fn test_stop_clears_loading() {
+        let mut renderer = WryRenderer::new().unwrap();
+        renderer.state.set_loading(true);
+
+        assert!(matches!(
+            renderer.stop(),
+            Err(RendererError::NotInitialized)
+        ));
+        assert!(!renderer.is_loading());
+
+        // Idempotent
+        assert!(matches!(
+            renderer.stop(),
+            Err(RendererError::NotInitialized)
+        ));
+        assert!(!renderer.is_loading());
+    }
+
+    #[test]
+    fn test_build_header_map() {
+        let mut headers = HashMap::new();
+        headers.insert("Authorization".to_string(), "Bearer token".to_string());
+        headers.insert("Accept-Language".to_string(), "en-ZA".to_string());
+
+        let header_map = build_header_map(&headers).unwrap();
+        assert_eq!(header_map.len(), 2);
+        assert_eq!(header_map["authorization"], "Bearer token");
+    }
+
+    #[test]
+    fn test_build_header_map_rejects_invalid() {
+        let mut headers = HashMap::new();
+        headers.insert("Bad Header".to_string(), "value".to_string());
+        let err = build_header_map(&headers).unwrap_err();
+        assert!(err.to_string().contains("Invalid header name"));
+
+        let mut headers = HashMap::new();
+        headers.insert("X-Custom".to_string(), "line\nbreak".to_string());
+        let err = build_header_map(&headers).unwrap_err();
+        assert!(err.to_string().contains("Invalid value"));
+    }
+
+    #[test]
+    fn test_load_url_with_headers_validates_first() {
+        let mut renderer = WryRenderer::new().unwrap();
+
+        let mut headers = HashMap::new();
+        headers.insert("Bad Header".to_string(), "value".to_string());
+        let result = renderer.load_url_with_headers("https://example.com", headers);
+        assert!(matches!(result, Err(RendererError::LoadFailed(_))));
+
+        let mut headers = HashMap::new();
+        headers.insert("X-Custom".to_string(), "value".to_string());
+        let result = renderer.load_url_with_headers("https://example.com", headers);
+        assert!(matches!(result, Err(RendererError::NotInitialized)));
+    }
+
+    #[test]
+    fn test_load_url_schemes() {
+        let mut renderer = WryRenderer::new().unwrap();
+
+        for url in [
+            "ftp://example.com/file",
+            "javascript:alert(1)",
+            "file:///etc/hosts",
+            "about:config",
+            "chrome://settings",
+        ] {
+            assert!(
+                matches!(
+                    renderer.load_url(url),
+                    Err(RendererError::SchemeNotAllowed(_))
+                ),
+                "{} allowed",
+                url
+            );
+        }
+        assert!(matches!(
+            renderer.load_url("data:html,hi"),
+            Err(RendererError::LoadFailed(_))
+        ));
+        assert!(matches!(
+            renderer.load_url("data:,hi"),
+            Err(RendererError::NotInitialized)
+        ));
+    }
+
+    #[test]
+    fn test_about_blank_and_blob_allowed() {
+        let mut renderer = WryRenderer::new().unwrap();
+        let url = |s: &str| Url::parse(s).unwrap();
+
+        assert_eq!(
+            renderer.webview_url(&url("about:blank")).unwrap(),
+            "about:blank"
+        );
+        let blob = "blob:https://example.com/550e8400-e29b-41d4-a716-446655440000";
+        assert_eq!(renderer.webview_url(&url(blob)).unwrap(), blob);
+
+        // Past the scheme check, failing only for want of a WebView
+        for allowed in ["about:blank", blob] {
+            assert!(matches!(
+                renderer.load_url(allowed),
+                Err(RendererError::NotInitialized)
+            ));
+        }
+        assert!(matches!(
+            renderer.load_url("gopher://example.com"),
+            Err(RendererError::SchemeNotAllowed(_))
+        ));
+    }
+
+    #[test]
+    fn test_file_urls_sniffed() {
+        let config = RendererConfig::builder()
+            .allow_file_urls(true)
+            .build()
+            .unwrap();
+        let renderer = WryRenderer::with_config(&config).unwrap();
+        let dir = std::env::temp_dir().join(format!("renderer-sniff-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let page = dir.join("page");
+        std::fs::write(&page, "<!DOCTYPE html><html><head></head><p>Hi</p></html>").unwrap();
+        let url = Url::from_file_path(&page).unwrap();
+        let loaded = renderer.webview_url(&url).unwrap();
+        assert!(loaded.starts_with("data:text/html;charset=utf-8,"));
+        assert!(urlencoding::decode(&loaded)
+            .unwrap()
+            .contains(&format!("<base href=\"{}\">", url)));
+
+        let image = dir.join("image.bin");
+        std::fs::write(&image, b"\x89PNG\r\n\x1a\n\x00\x00").unwrap();
+        let loaded = renderer
+            .webview_url(&Url::from_file_path(&image).unwrap())
+            .unwrap();
+        assert!(loaded.starts_with("data:image/png;base64,"));
+
+        let missing = Url::from_file_path(dir.join("missing")).unwrap();
+        assert!(matches!(
+            renderer.webview_url(&missing),
+            Err(RendererError::LoadFailed(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_request_headers_tracking() {
+        let state = WebViewState::new();
+        assert!(state.get_request_headers().is_empty());
+
+        let mut headers = HashMap::new();
+        headers.insert("Authorization".to_string(), "Bearer token".to_string());
+        state.set_request_headers(headers.clone());
+        assert_eq!(state.get_request_headers(), headers);
+
+        state.set_request_headers(HashMap::new());
+        assert!(state.get_request_headers().is_empty());
+    }
+
+    #[test]
+    fn test_history_position_tracking() {
+        let state = WebViewState::new();
+        assert!(!state.can_go_back());
+        assert!(!state.can_go_forward());
+
+        // Three loads: A, B, C
+        state.record_navigation("https://a.example/");
+        state.record_navigation("https://b.example/");
+        state.record_navigation("https://c.example/");
+        assert!(state.can_go_back());
+        assert!(!state.can_go_forward());
+
+        // Back to B (the finished load completes the traversal)
+        assert!(state.begin_back());
+        state.record_navigation("https://b.example/");
+        assert!(state.can_go_back());
+        assert!(state.can_go_forward());
+
+        // New load from B truncates C
+        state.record_navigation("https://d.example/");
+        assert!(!state.can_go_forward());
+        assert_eq!(state.history.lock().unwrap().length, 3);
+    }
+
+    #[test]
+    fn test_reload_adds_no_history_entry() {
+        let state = WebViewState::new();
+        state.record_navigation("https://a.example/");
+        state.record_navigation("https://a.example/");
+        assert!(!state.can_go_back());
+
+        state.record_navigation("https://b.example/");
+        state.record_navigation("https://b.example/");
+        assert_eq!(state.history.lock().unwrap().length, 2);
+        assert_eq!(state.history.lock().unwrap().index, 1);
+    }
+
+    #[test]
+    fn test_history_position_bounds() {
+        let state = WebViewState::new();
+        assert!(!state.begin_back());
+        assert!(!state.begin_forward());
+
+        state.record_navigation("https://a.example/");
+        assert!(!state.begin_back());
+        assert!(!state.begin_forward());
+        assert!(!state.history.lock().unwrap().traversing);
+    }
+
+    #[test]
+    fn test_sync_history_length() {
+        let state = WebViewState::new();
+        state.record_navigation("https://a.example/");
+        state.record_navigation("https://b.example/");
+        assert!(state.begin_back());
+        state.record_navigation("https://a.example/");
+
+        // Page reports a longer history (e.g. pushState entries)
+        state.sync_history_length(4);
+        assert!(state.can_go_forward());
+
+        // Length never drops below the current position
+        state.sync_history_length(0);
+        state.sync_history_length(1);
+        assert_eq!(state.history.lock().unwrap().length, 1);
+        assert!(!state.can_go_forward());
     }
 }
@@ -0,0 +1,137 @@
+//! Host blocklist
+//!
+//! Matches URLs against a set of host patterns:
+//! - `example.com` blocks exactly that host
+//! - `*.example.com` blocks every subdomain of `example.com` (not the
+//!   bare domain itself; list both to block both)
+
+use ::url::Url;
+use std::collections::HashSet;
+
+/// Set of blocked host patterns
+#[derive(Debug, Clone, Default)]
+pub struct Blocklist {
+    /// Hosts blocked exactly
+    exact: HashSet<String>,
+    /// Domains whose subdomains are blocked (stored without the `*.`)
+    wildcard: HashSet<String>,
+}
+
+impl Blocklist {
+    /// Create an empty blocklist
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a blocklist from host patterns
+    ///
+    /// Patterns are case-insensitive; blank entries are ignored.
+    pub fn from_patterns<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut blocklist = Self::new();
+        for pattern in patterns {
+            blocklist.add(pattern.as_ref());
+        }
+        blocklist
+    }
+
+    /// Add a host pattern
+    pub fn add(&mut self, pattern: &str) {
+        let pattern = pattern.trim().to_ascii_lowercase();
+
+        let (set, host) = match pattern.strip_prefix("*.") {
+            Some(domain) => (&mut self.wildcard, domain),
+            None => (&mut self.exact, pattern.as_str()),
+        };
+
+        let host = host.trim_end_matches('.');
+        if !host.is_empty() {
+            set.insert(host.to_string());
+        }
+    }
+
+    /// Number of patterns
+    pub fn len(&self) -> usize {
+        self.exact.len() + self.wildcard.len()
+    }
+
+    /// Whether the blocklist has no patterns
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check whether a URL's host is blocked
+    ///
+    /// URLs without a host (e.g. `about:blank`) are never blocked.
+    pub fn is_blocked(&self, url: &Url) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+
+        if self.exact.contains(&host) {
+            return true;
+        }
+
+        // Walk parent domains: a.b.example.com -> b.example.com -> example.com
+        let mut rest = host.as_str();
+        while let Some((_, parent)) = rest.split_once('.') {
+            if self.wildcard.contains(parent) {
+                return true;
+            }
+            rest = parent;
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocked(blocklist: &Blocklist, url: &str) -> bool {
+        blocklist.is_blocked(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn test_exact_match() {
+        let blocklist = Blocklist::from_patterns(["ads.example.com"]);
+
+        assert!(blocked(&blocklist, "https://ads.example.com/banner"));
+        assert!(blocked(&blocklist, "http://ADS.Example.com:8080/"));
+        assert!(!blocked(&blocklist, "https://example.com/"));
+        assert!(!blocked(&blocklist, "https://x.ads.example.com/"));
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        let blocklist = Blocklist::from_patterns(["*.tracker.net"]);
+
+        assert!(blocked(&blocklist, "https://cdn.tracker.net/"));
+        assert!(blocked(&blocklist, "https://a.b.tracker.net/"));
+        assert!(!blocked(&blocklist, "https://tracker.net/"));
+        assert!(!blocked(&blocklist, "https://nottracker.net/"));
+    }
+
+    #[test]
+    fn test_domain_and_subdomains() {
+        let blocklist = Blocklist::from_patterns(["social.com", "*.social.com"]);
+
+        assert!(blocked(&blocklist, "https://social.com/"));
+        assert!(blocked(&blocklist, "https://www.social.com/feed"));
+        assert!(!blocked(&blocklist, "https://social.com.example.org/"));
+    }
+
+    #[test]
+    fn test_patterns_are_normalized() {
+        let blocklist = Blocklist::from_patterns(["  Example.COM. ", "", "*."]);
+
+        assert_eq!(blocklist.len(), 1);
+        assert!(blocked(&blocklist, "https://example.com/"));
+        assert!(!blocked(&blocklist, "about:blank"));
+    }
+}
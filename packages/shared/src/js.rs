@@ -0,0 +1,90 @@
+//! Escaping for values injected into JavaScript
+//!
+//! Anything spliced into a script passed to `evaluate_script` must go through
+//! here; hand-rolled `replace('\'', ...)` escaping misses backslashes,
+//! newlines and the other quote character.
+
+use std::fmt::Write;
+
+/// Escape `s` for use inside a single- or double-quoted JavaScript string
+///
+/// Quotes, backslashes and control characters are backslash-escaped, as are
+/// U+2028/U+2029 (line terminators in older engines) and the `/` in `</`
+/// so the result can't close a surrounding `<script>` element.
+pub fn escape_js_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    let mut prev = '\0';
+
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\'' => escaped.push_str("\\'"),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '/' if prev == '<' => escaped.push_str("\\/"),
+            '\u{2028}' => escaped.push_str("\\u2028"),
+            '\u{2029}' => escaped.push_str("\\u2029"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+        prev = c;
+    }
+
+    escaped
+}
+
+/// Quote `s` as a JavaScript string literal
+///
+/// `format!("updateTitle({})", to_js_literal(title))` is safe for any
+/// `title`.
+pub fn to_js_literal(s: &str) -> String {
+    format!("\"{}\"", escape_js_string(s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_string_unchanged() {
+        assert_eq!(escape_js_string("Example Domain"), "Example Domain");
+        assert_eq!(to_js_literal("Example Domain"), "\"Example Domain\"");
+    }
+
+    #[test]
+    fn test_escapes_quotes_and_backslashes() {
+        assert_eq!(
+            escape_js_string(r#"It's a "test" \ path"#),
+            r#"It\'s a \"test\" \\ path"#
+        );
+        // A trailing backslash can't swallow the closing quote
+        assert_eq!(to_js_literal("C:\\"), r#""C:\\""#);
+    }
+
+    #[test]
+    fn test_escapes_line_terminators() {
+        let escaped = escape_js_string("one\ntwo\r\nthree\u{2028}four\u{2029}\tfive\u{0}");
+        assert_eq!(escaped, r"one\ntwo\r\nthree\u2028four\u2029\tfive\u0000");
+        assert!(!escaped.chars().any(char::is_control));
+    }
+
+    #[test]
+    fn test_escapes_closing_script_tag() {
+        let escaped = to_js_literal("</script><script>alert(1)</script>");
+        assert!(!escaped.contains("</"));
+        assert_eq!(escaped, r#""<\/script><script>alert(1)<\/script>""#);
+        // Other slashes are left alone
+        assert_eq!(escape_js_string("a/b"), "a/b");
+    }
+
+    #[test]
+    fn test_injection_attempt_stays_in_string() {
+        let title = "');alert(document.cookie);//";
+        let script = format!("updateTabTitle({})", to_js_literal(title));
+        assert_eq!(script, r#"updateTabTitle("\');alert(document.cookie);//")"#);
+    }
+}
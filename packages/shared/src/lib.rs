@@ -5,8 +5,15 @@
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use thiserror::Error;
 
+pub mod blocklist;
+pub mod js;
+pub mod url;
+
+pub use blocklist::Blocklist;
+
 /// Unique identifier for browser tabs
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TabId(pub usize);
@@ -17,6 +24,27 @@ impl fmt::Display for TabId {
     }
 }
 
+/// Thread-safe allocator of unique tab IDs
+///
+/// Share one generator (e.g. in an `Arc`) between components that create
+/// tabs so their IDs never collide.
+#[derive(Debug, Default)]
+pub struct TabIdGenerator {
+    next: AtomicUsize,
+}
+
+impl TabIdGenerator {
+    /// Create a generator starting at `TabId(0)`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allocate the next ID (monotonically increasing)
+    pub fn next_id(&self) -> TabId {
+        TabId(self.next.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
 /// Browser-wide error types
 #[derive(Error, Debug)]
 pub enum BrowserError {
@@ -38,3 +66,45 @@ pub enum BrowserError {
 
 /// Result type for browser operations
 pub type Result<T> = std::result::Result<T, BrowserError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_tab_id_generator_is_monotonic() {
+        let ids = TabIdGenerator::new();
+        assert_eq!(ids.next_id(), TabId(0));
+        assert_eq!(ids.next_id(), TabId(1));
+        assert_eq!(ids.next_id(), TabId(2));
+    }
+
+    #[test]
+    fn test_tab_id_generator_never_repeats_across_threads() {
+        let ids = Arc::new(TabIdGenerator::new());
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let ids = ids.clone();
+                thread::spawn(move || (0..1000).map(|_| ids.next_id()).collect::<Vec<_>>())
+            })
+            .collect();
+
+        let mut seen = HashSet::new();
+        for handle in handles {
+            for id in handle.join().unwrap() {
+                assert!(seen.insert(id), "{} allocated twice", id);
+            }
+        }
+        assert_eq!(seen.len(), 8000);
+    }
+
+    #[test]
+    fn test_tab_id_generator_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<TabIdGenerator>();
+    }
+}
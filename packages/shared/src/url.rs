@@ -0,0 +1,95 @@
+//! URL normalization
+//!
+//! Gives equivalent URLs a single spelling so history entries and
+//! bookmark lookups match regardless of how the URL was typed.
+
+use crate::{BrowserError, Result};
+use ::url::Url;
+
+/// Normalize a URL for storage and comparison
+///
+/// - Lowercases the scheme and host
+/// - Removes default ports (`:80` for http, `:443` for https)
+/// - Resolves `.` and `..` path segments
+/// - Strips the fragment
+/// - Ensures a path (`https://example.com` becomes `https://example.com/`)
+///
+/// Query strings and path case are preserved.
+///
+/// # Errors
+/// Returns `BrowserError::InvalidUrl` if the input isn't an absolute URL
+pub fn normalize(input: &str) -> Result<String> {
+    let mut url = Url::parse(input.trim())
+        .map_err(|e| BrowserError::InvalidUrl(format!("{}: {}", input, e)))?;
+
+    // Parsing already lowercases the host, drops default ports, resolves
+    // dot segments and adds the root path for http(s)
+    url.set_fragment(None);
+
+    Ok(url.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trailing_slash() {
+        assert_eq!(
+            normalize("https://example.com").unwrap(),
+            "https://example.com/"
+        );
+        assert_eq!(
+            normalize("https://example.com/").unwrap(),
+            "https://example.com/"
+        );
+    }
+
+    #[test]
+    fn test_uppercase_host() {
+        assert_eq!(
+            normalize("HTTPS://Example.COM/Path/To").unwrap(),
+            "https://example.com/Path/To"
+        );
+    }
+
+    #[test]
+    fn test_default_port_removal() {
+        assert_eq!(
+            normalize("http://example.com:80/a").unwrap(),
+            "http://example.com/a"
+        );
+        assert_eq!(
+            normalize("https://example.com:443/").unwrap(),
+            "https://example.com/"
+        );
+        assert_eq!(
+            normalize("https://example.com:8443/").unwrap(),
+            "https://example.com:8443/"
+        );
+    }
+
+    #[test]
+    fn test_fragment_stripping() {
+        assert_eq!(
+            normalize("https://example.com/page?q=Rust#section").unwrap(),
+            "https://example.com/page?q=Rust"
+        );
+    }
+
+    #[test]
+    fn test_dot_segments() {
+        assert_eq!(
+            normalize("https://example.com/a/./b/../c").unwrap(),
+            "https://example.com/a/c"
+        );
+    }
+
+    #[test]
+    fn test_invalid_url() {
+        assert!(matches!(
+            normalize("not a url"),
+            Err(BrowserError::InvalidUrl(_))
+        ));
+    }
+}
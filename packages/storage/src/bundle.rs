@@ -0,0 +1,375 @@
+//! Export bundles for moving browser data between machines
+//!
+//! `Database::export_bundle` writes history, bookmarks (with tags) and
+//! settings as one versioned JSON document; `Database::import_bundle`
+//! reads it back. Secrets, zoom levels and autofill values stay on the
+//! machine they were saved on.
+//!
+//! Timestamps are copied as stored, so an imported entry sorts exactly
+//! where it did on the original machine.
+
+use crate::{normalize_url, Database, StorageError};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use rusqlite::{params, Transaction};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::info;
+
+/// Bundle format written by `export_bundle`, the only one `import_bundle`
+/// reads
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// How `import_bundle` combines a bundle with existing data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Add what's missing; existing bookmarks and settings win
+    Merge,
+    /// Delete history, bookmarks and settings first
+    Replace,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Bundle {
+    version: u32,
+    exported_at: String,
+    history: Vec<BundleHistoryEntry>,
+    bookmarks: Vec<BundleBookmark>,
+    settings: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleHistoryEntry {
+    url: String,
+    title: Option<String>,
+    visit_time: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleBookmark {
+    url: String,
+    title: Option<String>,
+    folder: String,
+    created_at: String,
+    position: i64,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Just the version, read before the rest so a newer format fails with
+/// `StorageError::UnsupportedBundleVersion` rather than a parse error
+#[derive(Deserialize)]
+struct BundleHeader {
+    version: u32,
+}
+
+impl Database {
+    /// Export history, bookmarks and settings as a JSON bundle
+    ///
+    /// Secrets aren't included.
+    pub fn export_bundle(&self) -> Result<Vec<u8>> {
+        let history = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT url, title, visit_time FROM history ORDER BY visit_time, id")?;
+            let entries = stmt.query_map([], |row| {
+                Ok(BundleHistoryEntry {
+                    url: row.get(0)?,
+                    title: row.get(1)?,
+                    visit_time: row.get(2)?,
+                })
+            })?;
+            entries.collect::<Result<Vec<_>, _>>()?
+        };
+
+        let bookmarks = {
+            let mut stmt = self.conn.prepare(
+                "SELECT b.url, b.title, b.folder, b.created_at, b.position,
+                        (SELECT json_group_array(tag) FROM
+                            (SELECT tag FROM bookmark_tags WHERE bookmark_id = b.id ORDER BY tag))
+                 FROM bookmarks b ORDER BY b.folder, b.position, b.id",
+            )?;
+            let bookmarks = stmt.query_map([], |row| {
+                let tags: String = row.get(5)?;
+                Ok(BundleBookmark {
+                    url: row.get(0)?,
+                    title: row.get(1)?,
+                    folder: row.get(2)?,
+                    created_at: row.get(3)?,
+                    position: row.get(4)?,
+                    tags: serde_json::from_str(&tags).unwrap_or_default(),
+                })
+            })?;
+            bookmarks.collect::<Result<Vec<_>, _>>()?
+        };
+
+        let settings = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT key, value FROM settings WHERE value IS NOT NULL")?;
+            let settings = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            settings.collect::<Result<BTreeMap<_, _>, _>>()?
+        };
+
+        let bundle = Bundle {
+            version: BUNDLE_VERSION,
+            exported_at: Utc::now().to_rfc3339(),
+            history,
+            bookmarks,
+            settings,
+        };
+        info!(
+            "Exported {} history entries, {} bookmarks and {} settings",
+            bundle.history.len(),
+            bundle.bookmarks.len(),
+            bundle.settings.len()
+        );
+        serde_json::to_vec_pretty(&bundle).context("Failed to serialize export bundle")
+    }
+
+    /// Import a bundle from `export_bundle`
+    ///
+    /// Runs in one transaction: on error nothing is imported. Fails with
+    /// `StorageError::UnsupportedBundleVersion` for bundles from another
+    /// format version.
+    pub fn import_bundle(&self, bytes: &[u8], mode: ImportMode) -> Result<()> {
+        self.ensure_writable("import bundle")?;
+
+        let header: BundleHeader =
+            serde_json::from_slice(bytes).context("Not a browser export bundle")?;
+        if header.version != BUNDLE_VERSION {
+            return Err(StorageError::UnsupportedBundleVersion {
+                found: header.version,
+                supported: BUNDLE_VERSION,
+            }
+            .into());
+        }
+        let bundle: Bundle = serde_json::from_slice(bytes).context("Export bundle is malformed")?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        if mode == ImportMode::Replace {
+            tx.execute_batch(
+                "DELETE FROM history;
+                 DELETE FROM bookmark_tags;
+                 DELETE FROM bookmarks;
+                 DELETE FROM settings;",
+            )?;
+        }
+        import_history(&tx, &bundle.history)?;
+        import_bookmarks(&tx, &bundle.bookmarks, mode)?;
+        for (key, value) in &bundle.settings {
+            tx.execute(
+                "INSERT OR IGNORE INTO settings (key, value) VALUES (?1, ?2)",
+                params![key, value],
+            )?;
+        }
+        tx.commit()?;
+
+        info!(
+            "Imported {} history entries, {} bookmarks and {} settings ({:?})",
+            bundle.history.len(),
+            bundle.bookmarks.len(),
+            bundle.settings.len(),
+            mode
+        );
+        Ok(())
+    }
+}
+
+/// Add history entries, skipping visits already recorded
+fn import_history(tx: &Transaction<'_>, history: &[BundleHistoryEntry]) -> Result<()> {
+    let mut stmt = tx.prepare(
+        "INSERT INTO history (url, title, visit_time)
+         SELECT ?1, ?2, ?3
+         WHERE NOT EXISTS (SELECT 1 FROM history WHERE url = ?1 AND visit_time = ?3)",
+    )?;
+    for entry in history {
+        stmt.execute(params![
+            normalize_url(&entry.url),
+            entry.title,
+            entry.visit_time
+        ])?;
+    }
+    Ok(())
+}
+
+/// Add bookmarks and their tags
+///
+/// Replacing keeps the bundle's positions; merging appends new bookmarks
+/// to their folder, and tags on existing ones are combined.
+fn import_bookmarks(
+    tx: &Transaction<'_>,
+    bookmarks: &[BundleBookmark],
+    mode: ImportMode,
+) -> Result<()> {
+    let mut insert = tx.prepare(
+        "INSERT OR IGNORE INTO bookmarks (url, title, folder, created_at, position)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    let mut append = tx.prepare(
+        "INSERT OR IGNORE INTO bookmarks (url, title, folder, created_at, position)
+         VALUES (?1, ?2, ?3, ?4,
+                 (SELECT COALESCE(MAX(position) + 1, 0) FROM bookmarks WHERE folder = ?3))",
+    )?;
+    let mut tag = tx.prepare(
+        "INSERT OR IGNORE INTO bookmark_tags (bookmark_id, tag)
+         SELECT id, ?2 FROM bookmarks WHERE url = ?1",
+    )?;
+
+    for bookmark in bookmarks {
+        let url = normalize_url(&bookmark.url);
+        match mode {
+            ImportMode::Replace => insert.execute(params![
+                url,
+                bookmark.title,
+                bookmark.folder,
+                bookmark.created_at,
+                bookmark.position
+            ])?,
+            ImportMode::Merge => append.execute(params![
+                url,
+                bookmark.title,
+                bookmark.folder,
+                bookmark.created_at
+            ])?,
+        };
+        for name in &bookmark.tags {
+            let name = name.trim();
+            if !name.is_empty() {
+                tag.execute(params![url, name])?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn count(db: &Database, table: &str) -> i64 {
+        db.connection()
+            .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+    }
+
+    fn populated() -> (NamedTempFile, Database) {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+        db.add_history("https://a.com", Some("A")).unwrap();
+        db.add_history("https://b.com", None).unwrap();
+        db.add_history("https://a.com", Some("A again")).unwrap();
+        db.add_bookmark("https://a.com", Some("A"), None).unwrap();
+        db.add_bookmark("https://c.com", None, Some("Work"))
+            .unwrap();
+        db.add_bookmark("https://d.com", None, Some("Work"))
+            .unwrap();
+        db.add_tag("https://c.com", "rust").unwrap();
+        db.add_tag("https://c.com", "docs").unwrap();
+        db.set_homepage("https://home.example").unwrap();
+        db.set_setting("https_first", "false").unwrap();
+        db.set_secret("token", "hunter2", "passphrase").unwrap();
+        (temp_file, db)
+    }
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let (_file, db) = populated();
+        let bundle = db.export_bundle().unwrap();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let fresh = Database::new(temp_file.path()).unwrap();
+        fresh.import_bundle(&bundle, ImportMode::Replace).unwrap();
+
+        for table in ["history", "bookmarks", "bookmark_tags", "settings"] {
+            assert_eq!(count(&fresh, table), count(&db, table), "{} count", table);
+        }
+        assert_eq!(fresh.get_recent_history(10).unwrap().len(), 3);
+        assert_eq!(
+            fresh
+                .get_bookmarks_by_folder("Work")
+                .unwrap()
+                .iter()
+                .map(|b| b.url.as_str())
+                .collect::<Vec<_>>(),
+            vec!["https://c.com/", "https://d.com/"]
+        );
+        assert_eq!(
+            fresh.tags_for("https://c.com").unwrap(),
+            vec!["docs", "rust"]
+        );
+        assert_eq!(fresh.homepage().unwrap(), "https://home.example");
+
+        // Secrets never leave the machine
+        assert_eq!(count(&fresh, "secrets"), 0);
+        assert!(!String::from_utf8(bundle).unwrap().contains("token"));
+    }
+
+    #[test]
+    fn test_import_merge() {
+        let (_file, db) = populated();
+        let bundle = db.export_bundle().unwrap();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let other = Database::new(temp_file.path()).unwrap();
+        other.add_history("https://e.com", None).unwrap();
+        other
+            .add_bookmark("https://c.com", Some("Mine"), Some("Work"))
+            .unwrap();
+        other.add_tag("https://c.com", "local").unwrap();
+        other.set_homepage("https://mine.example").unwrap();
+
+        other.import_bundle(&bundle, ImportMode::Merge).unwrap();
+        // Importing again adds nothing
+        other.import_bundle(&bundle, ImportMode::Merge).unwrap();
+
+        assert_eq!(count(&other, "history"), 4);
+        assert_eq!(count(&other, "bookmarks"), 3);
+        assert_eq!(
+            other.tags_for("https://c.com").unwrap(),
+            vec!["docs", "local", "rust"]
+        );
+        assert_eq!(other.homepage().unwrap(), "https://mine.example");
+        assert_eq!(other.get_setting("https_first").unwrap().unwrap(), "false");
+
+        let work = other.get_bookmarks_by_folder("Work").unwrap();
+        assert_eq!(work[0].title.as_deref(), Some("Mine"));
+        assert_eq!(
+            work.iter().map(|b| b.position).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+
+        other.import_bundle(&bundle, ImportMode::Replace).unwrap();
+        assert_eq!(count(&other, "history"), 3);
+        assert_eq!(other.homepage().unwrap(), "https://home.example");
+    }
+
+    #[test]
+    fn test_import_rejects_bad_bundles() {
+        let (_file, db) = populated();
+        let mut bundle: serde_json::Value =
+            serde_json::from_slice(&db.export_bundle().unwrap()).unwrap();
+        bundle["version"] = serde_json::json!(BUNDLE_VERSION + 1);
+        let newer = serde_json::to_vec(&bundle).unwrap();
+
+        let err = db.import_bundle(&newer, ImportMode::Replace).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<StorageError>(),
+            Some(&StorageError::UnsupportedBundleVersion {
+                found: BUNDLE_VERSION + 1,
+                supported: BUNDLE_VERSION,
+            })
+        );
+        assert!(db.import_bundle(b"not json", ImportMode::Merge).is_err());
+        assert!(db
+            .import_bundle(br#"{"version":1,"history":"nope"}"#, ImportMode::Replace)
+            .is_err());
+
+        // Failed imports change nothing
+        assert_eq!(count(&db, "history"), 3);
+        assert_eq!(count(&db, "bookmarks"), 3);
+    }
+}
@@ -6,8 +6,25 @@
 //!
 //! - **Database**: Main database manager with connection and migration
 //! - **HistoryEntry**: Browsing history record
-//! - **Bookmark**: Saved bookmark with folder organization
+//! - **HistorySession**: History entries grouped by idle gaps
+//! - **Bookmark**: Saved bookmark with folder organization and tags
 //! - **Migrations**: Schema versioning system
+//! - **Retry**: Writes retry on `SQLITE_BUSY`/`SQLITE_LOCKED` (see `retry`)
+//! - **Read-only handles**: `Database::open_readonly` for readers that
+//!   shouldn't contend with writers
+//! - **In-memory databases**: `Database::new_in_memory`, private to one
+//!   connection
+//! - **Zoom levels**: Per-origin page zoom
+//! - **Favicons**: Per-origin icon cache, joined onto bookmarks by
+//!   `get_bookmarks_with_favicons`
+//! - **Autofill**: Per-origin form field values (never passwords or card
+//!   numbers, see `is_sensitive_field`)
+//! - **Secrets**: Values encrypted with XChaCha20-Poly1305 under a key
+//!   derived from a passphrase with Argon2id (`set_secret` / `get_secret`)
+//! - **Backup**: `backup_to` / `restore_from` copy the database with
+//!   SQLite's online backup API
+//! - **Export bundles**: `export_bundle` / `import_bundle` move history,
+//!   bookmarks and settings between machines as versioned JSON
 //!
 //! # Usage
 //!
@@ -25,13 +42,54 @@
 //! ```
 
 use anyhow::{Context, Result};
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection};
-use std::path::Path;
+use rusqlite::{
+    params, Connection, OpenFlags, OptionalExtension, Transaction, TransactionBehavior, MAIN_DB,
+};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
 use tracing::{debug, info};
 
+pub mod bundle;
+pub mod retry;
+
+pub use bundle::{ImportMode, BUNDLE_VERSION};
+pub use retry::RetryPolicy;
+
 /// Database schema version
-const SCHEMA_VERSION: i32 = 1;
+const SCHEMA_VERSION: i32 = 7;
+
+/// Zoom factor for origins without a stored zoom level
+pub const DEFAULT_ZOOM: f64 = 1.0;
+
+/// Random salt stored with each secret for key derivation
+const SECRET_SALT_LEN: usize = 16;
+
+/// Most suggestions returned by `autofill_suggestions`
+pub const AUTOFILL_SUGGESTION_LIMIT: usize = 10;
+
+/// Settings key for the page the home action opens
+pub const HOMEPAGE_SETTING: &str = "homepage";
+
+/// Settings key for the page new tabs open
+pub const NEW_TAB_URL_SETTING: &str = "new_tab_url";
+
+/// Homepage used when none is stored, or the stored one is invalid
+pub const DEFAULT_HOMEPAGE: &str = "https://example.com";
+
+/// New-tab page used when none is stored, or the stored one is invalid
+pub const DEFAULT_NEW_TAB_URL: &str = "https://example.com";
+
+/// Idle time after which the next visit starts a new history session
+pub const DEFAULT_SESSION_GAP: Duration = Duration::from_secs(30 * 60);
+
+/// Bookmark columns in the order `bookmark_from_row` expects
+const BOOKMARK_COLUMNS: &str = "id, url, title, folder, created_at, position";
 
 /// Browsing history entry
 #[derive(Debug, Clone, PartialEq)]
@@ -42,6 +100,19 @@ pub struct HistoryEntry {
     pub visit_time: DateTime<Utc>,
 }
 
+/// A run of history entries without a long gap between visits
+///
+/// See `Database::get_history_sessions`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistorySession {
+    /// First visit
+    pub start: DateTime<Utc>,
+    /// Last visit
+    pub end: DateTime<Utc>,
+    /// Visits in the session, newest first
+    pub entries: Vec<HistoryEntry>,
+}
+
 /// Bookmark entry
 #[derive(Debug, Clone, PartialEq)]
 pub struct Bookmark {
@@ -50,11 +121,39 @@ pub struct Bookmark {
     pub title: Option<String>,
     pub folder: String,
     pub created_at: DateTime<Utc>,
+    /// Order within the folder, lowest first
+    pub position: i64,
+}
+
+/// Typed storage errors callers may want to match on
+///
+/// Returned inside `anyhow::Error`; use `downcast_ref::<StorageError>()`.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum StorageError {
+    /// Write attempted through a handle from `Database::open_readonly`
+    #[error("Database is read-only: can't {0}")]
+    ReadOnly(&'static str),
+
+    /// Autofill save attempted for a password-like field
+    #[error("Refusing to store sensitive form field: {0}")]
+    SensitiveField(String),
+
+    /// Secret didn't decrypt: wrong passphrase or tampered data
+    #[error("Failed to decrypt secret {0}: wrong passphrase or corrupted data")]
+    SecretAuthFailed(String),
+
+    /// Export bundle written in a format this version can't read
+    #[error("Unsupported export bundle version {found} (expected {supported})")]
+    UnsupportedBundleVersion { found: u32, supported: u32 },
 }
 
 /// Browser database manager
 pub struct Database {
     conn: Connection,
+    retry_policy: RetryPolicy,
+    read_only: bool,
+    /// Gap between visits that splits history sessions
+    session_gap: Duration,
 }
 
 impl Database {
@@ -63,14 +162,73 @@ impl Database {
         info!("Opening database at {:?}", path);
 
         let conn = Connection::open(path).context("Failed to open SQLite database")?;
+        Self::migrated(conn)
+    }
+
+    /// Create an empty database in memory
+    ///
+    /// The database belongs to this connection: other `Database` handles,
+    /// including ones on other threads, can't see it, and it's gone once
+    /// dropped. For tests and for data that mustn't reach the disk, such as
+    /// a private session's.
+    pub fn new_in_memory() -> Result<Self> {
+        debug!("Opening in-memory database");
+
+        let conn =
+            Connection::open_in_memory().context("Failed to open in-memory SQLite database")?;
+        Self::migrated(conn)
+    }
 
-        let mut db = Self { conn };
+    /// Wrap a writable connection, bringing its schema up to date
+    fn migrated(conn: Connection) -> Result<Self> {
+        let mut db = Self {
+            conn,
+            retry_policy: RetryPolicy::default(),
+            read_only: false,
+            session_gap: DEFAULT_SESSION_GAP,
+        };
         db.run_migrations()
             .context("Failed to run database migrations")?;
 
         Ok(db)
     }
 
+    /// Open an existing database for reading only
+    ///
+    /// Uses SQLite's read-only open flag, so the connection never takes a
+    /// write lock. No migrations are run; the database must already have
+    /// been created by `Database::new`. Write methods return
+    /// `StorageError::ReadOnly`.
+    pub fn open_readonly(path: &Path) -> Result<Self> {
+        debug!("Opening database read-only at {:?}", path);
+
+        let flags = OpenFlags::SQLITE_OPEN_READ_ONLY
+            | OpenFlags::SQLITE_OPEN_URI
+            | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+        let conn = Connection::open_with_flags(path, flags)
+            .context("Failed to open SQLite database read-only")?;
+
+        Ok(Self {
+            conn,
+            retry_policy: RetryPolicy::default(),
+            read_only: true,
+            session_gap: DEFAULT_SESSION_GAP,
+        })
+    }
+
+    /// Whether this handle was opened with `open_readonly`
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Fail with `StorageError::ReadOnly` on a read-only handle
+    fn ensure_writable(&self, operation: &'static str) -> Result<()> {
+        if self.read_only {
+            return Err(StorageError::ReadOnly(operation).into());
+        }
+        Ok(())
+    }
+
     /// Run database migrations
     fn run_migrations(&mut self) -> Result<()> {
         // Create schema_version table if not exists
@@ -98,13 +256,34 @@ impl Database {
                 "Migrating database from version {} to {}",
                 current_version, SCHEMA_VERSION
             );
-            self.migrate_to_v1()?;
-
-            // Update schema version
-            self.conn.execute(
-                "INSERT OR REPLACE INTO schema_version (version) VALUES (?1)",
-                params![SCHEMA_VERSION],
-            )?;
+            if current_version < 1 {
+                self.migrate_to_v1()?;
+                self.set_schema_version(1)?;
+            }
+            if current_version < 2 {
+                self.migrate_to_v2()?;
+                self.set_schema_version(2)?;
+            }
+            if current_version < 3 {
+                self.migrate_to_v3()?;
+                self.set_schema_version(3)?;
+            }
+            if current_version < 4 {
+                self.migrate_to_v4()?;
+                self.set_schema_version(4)?;
+            }
+            if current_version < 5 {
+                self.migrate_to_v5()?;
+                self.set_schema_version(5)?;
+            }
+            if current_version < 6 {
+                self.migrate_to_v6()?;
+                self.set_schema_version(6)?;
+            }
+            if current_version < 7 {
+                self.migrate_to_v7()?;
+                self.set_schema_version(7)?;
+            }
 
             info!("Migration complete");
         }
@@ -112,6 +291,15 @@ impl Database {
         Ok(())
     }
 
+    /// Record that migrations up to `version` have run
+    fn set_schema_version(&self, version: i32) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO schema_version (version) VALUES (?1)",
+            params![version],
+        )?;
+        Ok(())
+    }
+
     /// Migrate to schema version 1
     fn migrate_to_v1(&mut self) -> Result<()> {
         self.conn.execute_batch(
@@ -146,22 +334,261 @@ impl Database {
         Ok(())
     }
 
+    /// Migrate to schema version 2: manual bookmark ordering
+    ///
+    /// Existing bookmarks are numbered oldest first within each folder.
+    /// Safe to race with another connection migrating the same file.
+    fn migrate_to_v2(&mut self) -> Result<()> {
+        // Take the write lock first so concurrent openers migrate in turn
+        let tx = self
+            .conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+        let has_position: bool = tx.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('bookmarks') WHERE name = 'position'",
+            [],
+            |row| row.get(0),
+        )?;
+        if has_position {
+            return Ok(());
+        }
+
+        tx.execute_batch(
+            r#"
+            ALTER TABLE bookmarks ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+
+            UPDATE bookmarks SET position = (
+                SELECT COUNT(*) FROM bookmarks AS earlier
+                WHERE earlier.folder = bookmarks.folder
+                  AND (earlier.created_at < bookmarks.created_at
+                       OR (earlier.created_at = bookmarks.created_at
+                           AND earlier.id < bookmarks.id))
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_bookmarks_folder_position
+                ON bookmarks(folder, position);
+            "#,
+        )?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Migrate to schema version 3: bookmark tags
+    ///
+    /// Tags are independent of folders; a bookmark has one folder and any
+    /// number of tags.
+    fn migrate_to_v3(&mut self) -> Result<()> {
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS bookmark_tags (
+                bookmark_id INTEGER NOT NULL REFERENCES bookmarks(id) ON DELETE CASCADE,
+                tag TEXT NOT NULL,
+                PRIMARY KEY (bookmark_id, tag)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_bookmark_tags_tag ON bookmark_tags(tag);
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    /// Migrate to schema version 4: per-origin zoom levels
+    fn migrate_to_v4(&mut self) -> Result<()> {
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS zoom_levels (
+                origin TEXT PRIMARY KEY,
+                factor REAL NOT NULL
+            );
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    /// Migrate to schema version 5: autofill values per origin and field
+    fn migrate_to_v5(&mut self) -> Result<()> {
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS autofill (
+                origin TEXT NOT NULL,
+                field_name TEXT NOT NULL,
+                value TEXT NOT NULL,
+                use_count INTEGER NOT NULL DEFAULT 1,
+                last_used INTEGER NOT NULL,
+                PRIMARY KEY (origin, field_name, value)
+            );
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    /// Migrate to schema version 6: encrypted secrets
+    fn migrate_to_v6(&mut self) -> Result<()> {
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS secrets (
+                key TEXT PRIMARY KEY,
+                salt BLOB NOT NULL,
+                nonce BLOB NOT NULL,
+                ciphertext BLOB NOT NULL
+            );
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    /// Migrate to schema version 7: cached favicons
+    fn migrate_to_v7(&mut self) -> Result<()> {
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS favicons (
+                origin TEXT PRIMARY KEY,
+                data BLOB NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+            "#,
+        )?;
+
+        Ok(())
+    }
+
     /// Get a reference to the underlying connection
     pub fn connection(&self) -> &Connection {
         &self.conn
     }
 
+    /// Replace the retry policy used by write operations
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    /// Set how long without a visit starts a new history session
+    /// (`DEFAULT_SESSION_GAP` by default)
+    pub fn set_session_gap(&mut self, gap: Duration) {
+        self.session_gap = gap;
+    }
+
+    /// Execute a write statement, retrying while the database is busy
+    fn execute_with_retry<P: rusqlite::Params + Clone>(
+        &self,
+        sql: &str,
+        params: P,
+    ) -> Result<usize> {
+        Ok(retry::with_retry(&self.retry_policy, || {
+            self.conn.execute(sql, params.clone())
+        })?)
+    }
+
+    /// Run `operation` in a write transaction, retrying the whole
+    /// transaction while the database is busy
+    fn transaction_with_retry<T>(
+        &self,
+        mut operation: impl FnMut(&Transaction) -> rusqlite::Result<T>,
+    ) -> Result<T> {
+        Ok(retry::with_retry(&self.retry_policy, || {
+            let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+            let result = operation(&tx)?;
+            tx.commit()?;
+            Ok(result)
+        })?)
+    }
+
+    // ========== Backup ==========
+
+    /// Back up the database to `dest`
+    ///
+    /// Uses SQLite's online backup API, so it's safe while other
+    /// connections are reading or writing. The copy is written next to
+    /// `dest` and renamed into place, so `dest` is either the old file or a
+    /// complete backup, never a partial one.
+    pub fn backup_to(&self, dest: &Path) -> Result<()> {
+        copy_database(&self.conn, dest, &self.retry_policy)?;
+        info!("Backed up database to {:?}", dest);
+        Ok(())
+    }
+
+    /// Restore a backup made by `backup_to` over the database at `dest`
+    ///
+    /// The backup is checked to be a browser database first, and `dest` is
+    /// replaced atomically. Connections already open on `dest` keep seeing
+    /// the old data; reopen them afterwards.
+    pub fn restore_from(backup: &Path, dest: &Path) -> Result<()> {
+        let src = Self::open_readonly(backup)?;
+        src.conn
+            .query_row("SELECT MAX(version) FROM schema_version", [], |row| {
+                row.get::<_, Option<i32>>(0)
+            })
+            .ok()
+            .flatten()
+            .with_context(|| format!("Not a browser database: {:?}", backup))?;
+
+        copy_database(&src.conn, dest, &src.retry_policy)?;
+        info!("Restored database {:?} from {:?}", dest, backup);
+        Ok(())
+    }
+
     // ========== History Operations ==========
 
     /// Add a history entry
+    ///
+    /// The URL is normalized (see `shared::url::normalize`) so variants
+    /// like a missing trailing slash share one spelling. Retried while the
+    /// database is busy.
     pub fn add_history(&self, url: &str, title: Option<&str>) -> Result<i64> {
-        let id = self.conn.execute(
+        self.ensure_writable("add history")?;
+        let url = normalize_url(url);
+        self.execute_with_retry(
             "INSERT INTO history (url, title, visit_time) VALUES (?1, ?2, ?3)",
             params![url, title, Utc::now().to_rfc3339()],
         )?;
+        let id = self.conn.last_insert_rowid();
 
         debug!("Added history entry: {} (id={})", url, id);
-        Ok(id as i64)
+        Ok(id)
+    }
+
+    /// Add a history entry unless it repeats the latest one
+    ///
+    /// Returns None without writing if the most recent entry has the same
+    /// (normalized) URL and is less than `window` old, so reloads and
+    /// repeated clicks don't fill history. Later re-visits are recorded.
+    pub fn add_history_collapsing(
+        &self,
+        url: &str,
+        title: Option<&str>,
+        window: std::time::Duration,
+    ) -> Result<Option<i64>> {
+        self.ensure_writable("add history")?;
+        let normalized = normalize_url(url);
+
+        let latest = self.conn.query_row(
+            "SELECT url, visit_time FROM history ORDER BY visit_time DESC, id DESC LIMIT 1",
+            [],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        );
+        let latest = match latest {
+            Ok(latest) => Some(latest),
+            Err(rusqlite::Error::QueryReturnedNoRows) => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        if let Some((latest_url, visit_time)) = latest {
+            let age = visit_time
+                .parse::<DateTime<Utc>>()
+                .ok()
+                .and_then(|visited| Utc::now().signed_duration_since(visited).to_std().ok());
+            if latest_url == normalized && age.is_some_and(|age| age < window) {
+                debug!("Collapsed repeat visit to {}", normalized);
+                return Ok(None);
+            }
+        }
+
+        self.add_history(url, title).map(Some)
     }
 
     /// Get recent history (limited by count)
@@ -171,17 +598,7 @@ impl Database {
              ORDER BY visit_time DESC LIMIT ?1",
         )?;
 
-        let entries = stmt.query_map(params![limit], |row| {
-            Ok(HistoryEntry {
-                id: row.get(0)?,
-                url: row.get(1)?,
-                title: row.get(2)?,
-                visit_time: row
-                    .get::<_, String>(3)?
-                    .parse::<DateTime<Utc>>()
-                    .unwrap_or_else(|_| Utc::now()),
-            })
-        })?;
+        let entries = stmt.query_map(params![limit], history_entry_from_row)?;
 
         entries
             .collect::<Result<Vec<_>, _>>()
@@ -197,43 +614,82 @@ impl Database {
         )?;
 
         let search_pattern = format!("%{}%", query);
-        let entries = stmt.query_map(params![search_pattern, limit], |row| {
-            Ok(HistoryEntry {
-                id: row.get(0)?,
-                url: row.get(1)?,
-                title: row.get(2)?,
-                visit_time: row
-                    .get::<_, String>(3)?
-                    .parse::<DateTime<Utc>>()
-                    .unwrap_or_else(|_| Utc::now()),
-            })
-        })?;
+        let entries = stmt.query_map(params![search_pattern, limit], history_entry_from_row)?;
 
         entries
             .collect::<Result<Vec<_>, _>>()
             .context("Failed to search history")
     }
 
+    /// Recent history grouped into browsing sessions, newest first
+    ///
+    /// A visit more than the session gap (see `set_session_gap`) after the
+    /// previous one starts a new session. Returns up to `limit_sessions`
+    /// sessions.
+    pub fn get_history_sessions(&self, limit_sessions: usize) -> Result<Vec<HistorySession>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, url, title, visit_time FROM history
+             ORDER BY visit_time DESC, id DESC",
+        )?;
+        let mut rows = stmt.query([])?;
+
+        let mut sessions: Vec<HistorySession> = Vec::new();
+        while let Some(row) = rows.next()? {
+            let entry = history_entry_from_row(row)?;
+            // Walking backwards in time, so a session grows at its start
+            let continues = sessions.last().is_some_and(|session| {
+                (session.start - entry.visit_time)
+                    .to_std()
+                    .is_ok_and(|gap| gap <= self.session_gap)
+            });
+
+            if continues {
+                let session = sessions.last_mut().expect("checked above");
+                session.start = entry.visit_time;
+                session.entries.push(entry);
+            } else if sessions.len() < limit_sessions {
+                sessions.push(HistorySession {
+                    start: entry.visit_time,
+                    end: entry.visit_time,
+                    entries: vec![entry],
+                });
+            } else {
+                break;
+            }
+        }
+
+        Ok(sessions)
+    }
+
     /// Clear all history
+    ///
+    /// Retried while the database is busy.
     pub fn clear_history(&self) -> Result<()> {
-        self.conn.execute("DELETE FROM history", [])?;
+        self.ensure_writable("clear history")?;
+        self.execute_with_retry("DELETE FROM history", [])?;
         info!("Cleared all history");
         Ok(())
     }
 
     // ========== Bookmark Operations ==========
 
-    /// Add a bookmark
+    /// Add a bookmark at the end of its folder
+    ///
+    /// Retried while the database is busy.
     pub fn add_bookmark(
         &self,
         url: &str,
         title: Option<&str>,
         folder: Option<&str>,
     ) -> Result<i64> {
+        self.ensure_writable("add bookmark")?;
+        let url = normalize_url(url);
         let folder = folder.unwrap_or("Unsorted");
 
-        let id = self.conn.execute(
-            "INSERT INTO bookmarks (url, title, folder, created_at) VALUES (?1, ?2, ?3, ?4)",
+        let id = self.execute_with_retry(
+            "INSERT INTO bookmarks (url, title, folder, created_at, position)
+             VALUES (?1, ?2, ?3, ?4,
+                     (SELECT COALESCE(MAX(position) + 1, 0) FROM bookmarks WHERE folder = ?3))",
             params![url, title, folder, Utc::now().to_rfc3339()],
         )?;
 
@@ -243,153 +699,812 @@ impl Database {
 
     /// Get all bookmarks
     pub fn get_bookmarks(&self) -> Result<Vec<Bookmark>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, url, title, folder, created_at FROM bookmarks
-             ORDER BY created_at DESC",
-        )?;
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM bookmarks ORDER BY created_at DESC",
+            BOOKMARK_COLUMNS
+        ))?;
 
-        let bookmarks = stmt.query_map([], |row| {
-            Ok(Bookmark {
-                id: row.get(0)?,
-                url: row.get(1)?,
-                title: row.get(2)?,
-                folder: row.get(3)?,
-                created_at: row
-                    .get::<_, String>(4)?
-                    .parse::<DateTime<Utc>>()
-                    .unwrap_or_else(|_| Utc::now()),
-            })
-        })?;
+        let bookmarks = stmt.query_map([], bookmark_from_row)?;
 
         bookmarks
             .collect::<Result<Vec<_>, _>>()
             .context("Failed to get bookmarks")
     }
 
-    /// Get bookmarks in a specific folder
+    /// Get all bookmarks with the cached favicon for each one's origin
+    ///
+    /// Bookmarks whose origin has no favicon come back with `None`.
+    pub fn get_bookmarks_with_favicons(&self) -> Result<Vec<(Bookmark, Option<Vec<u8>>)>> {
+        // The origin is everything before the first '/' after the scheme;
+        // stored URLs are normalized, so the host is already lowercase
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}, favicons.data FROM bookmarks
+             LEFT JOIN favicons ON favicons.origin = CASE
+                 WHEN instr(substr(url, instr(url, '://') + 3), '/') > 0
+                 THEN substr(url, 1, instr(url, '://') + 1
+                      + instr(substr(url, instr(url, '://') + 3), '/'))
+                 ELSE url
+             END
+             ORDER BY created_at DESC",
+            BOOKMARK_COLUMNS
+        ))?;
+
+        let bookmarks = stmt.query_map([], |row| Ok((bookmark_from_row(row)?, row.get(6)?)))?;
+
+        bookmarks
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to get bookmarks with favicons")
+    }
+
+    /// Get bookmarks in a specific folder, in their manual order
     pub fn get_bookmarks_by_folder(&self, folder: &str) -> Result<Vec<Bookmark>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, url, title, folder, created_at FROM bookmarks
-             WHERE folder = ?1 ORDER BY created_at DESC",
-        )?;
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM bookmarks WHERE folder = ?1 ORDER BY position, id",
+            BOOKMARK_COLUMNS
+        ))?;
 
-        let bookmarks = stmt.query_map(params![folder], |row| {
-            Ok(Bookmark {
-                id: row.get(0)?,
-                url: row.get(1)?,
-                title: row.get(2)?,
-                folder: row.get(3)?,
-                created_at: row
-                    .get::<_, String>(4)?
-                    .parse::<DateTime<Utc>>()
-                    .unwrap_or_else(|_| Utc::now()),
-            })
-        })?;
+        let bookmarks = stmt.query_map(params![folder], bookmark_from_row)?;
 
         bookmarks
             .collect::<Result<Vec<_>, _>>()
             .context("Failed to get bookmarks by folder")
     }
 
-    /// Remove a bookmark by URL
-    pub fn remove_bookmark(&self, url: &str) -> Result<()> {
-        self.conn
-            .execute("DELETE FROM bookmarks WHERE url = ?1", params![url])?;
-        debug!("Removed bookmark: {}", url);
+    /// Search bookmarks by URL, title or folder, newest first
+    pub fn search_bookmarks(&self, query: &str, limit: usize) -> Result<Vec<Bookmark>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM bookmarks
+             WHERE url LIKE ?1 OR title LIKE ?1 OR folder LIKE ?1
+             ORDER BY created_at DESC LIMIT ?2",
+            BOOKMARK_COLUMNS
+        ))?;
+
+        let search_pattern = format!("%{}%", query);
+        let bookmarks = stmt.query_map(params![search_pattern, limit], bookmark_from_row)?;
+
+        bookmarks
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to search bookmarks")
+    }
+
+    /// Move a bookmark to `new_position` within its folder
+    ///
+    /// Positions are zero-based; anything past the end moves the bookmark
+    /// to the end. The folder is renumbered `0..n` afterwards. Retried
+    /// while the database is busy.
+    pub fn reorder_bookmark(&self, url: &str, new_position: usize) -> Result<()> {
+        self.ensure_writable("reorder bookmark")?;
+        let url = normalize_url(url);
+
+        let folder = self
+            .transaction_with_retry(|tx| {
+                let Some((id, folder)) = tx
+                    .query_row(
+                        "SELECT id, folder FROM bookmarks WHERE url = ?1",
+                        params![url],
+                        |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)),
+                    )
+                    .optional()?
+                else {
+                    return Ok(None);
+                };
+
+                let mut ids = {
+                    let mut stmt = tx.prepare(
+                        "SELECT id FROM bookmarks WHERE folder = ?1 ORDER BY position, id",
+                    )?;
+                    let ids = stmt.query_map(params![folder], |row| row.get::<_, i64>(0))?;
+                    ids.collect::<Result<Vec<_>, _>>()?
+                };
+
+                ids.retain(|&other| other != id);
+                ids.insert(new_position.min(ids.len()), id);
+
+                let mut stmt = tx.prepare("UPDATE bookmarks SET position = ?1 WHERE id = ?2")?;
+                for (position, id) in ids.iter().enumerate() {
+                    stmt.execute(params![position as i64, id])?;
+                }
+                Ok(Some(folder))
+            })?
+            .with_context(|| format!("Bookmark not found: {}", url))?;
+
+        debug!(
+            "Moved bookmark {} to position {} in '{}'",
+            url, new_position, folder
+        );
         Ok(())
     }
 
-    // ========== Settings Operations ==========
+    /// Clear all bookmarks
+    ///
+    /// Retried while the database is busy.
+    pub fn clear_bookmarks(&self) -> Result<()> {
+        self.ensure_writable("clear bookmarks")?;
+        self.transaction_with_retry(|tx| {
+            tx.execute_batch("DELETE FROM bookmark_tags; DELETE FROM bookmarks;")
+        })?;
+        info!("Cleared all bookmarks");
+        Ok(())
+    }
 
-    /// Get a setting value
-    pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
-        let result = self.conn.query_row(
-            "SELECT value FROM settings WHERE key = ?1",
-            params![key],
+    /// Check whether a URL is bookmarked
+    ///
+    /// Matches normalized URLs, so `https://Example.com` finds a bookmark
+    /// saved as `https://example.com/`.
+    pub fn is_bookmarked(&self, url: &str) -> Result<bool> {
+        let url = normalize_url(url);
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM bookmarks WHERE url = ?1",
+            params![url],
             |row| row.get(0),
-        );
+        )?;
+        Ok(count > 0)
+    }
 
-        match result {
-            Ok(value) => Ok(Some(value)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e.into()),
-        }
+    /// Remove a bookmark by URL
+    ///
+    /// Retried while the database is busy.
+    pub fn remove_bookmark(&self, url: &str) -> Result<()> {
+        self.ensure_writable("remove bookmark")?;
+        let url = normalize_url(url);
+        self.transaction_with_retry(|tx| {
+            tx.execute(
+                "DELETE FROM bookmark_tags
+                 WHERE bookmark_id IN (SELECT id FROM bookmarks WHERE url = ?1)",
+                params![url],
+            )?;
+            tx.execute("DELETE FROM bookmarks WHERE url = ?1", params![url])
+        })?;
+        debug!("Removed bookmark: {}", url);
+        Ok(())
     }
 
-    /// Set a setting value
-    pub fn set_setting(&self, key: &str, value: &str) -> Result<()> {
-        self.conn.execute(
-            "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
-            params![key, value],
+    // ========== Bookmark Tag Operations ==========
+
+    /// Tag a bookmark
+    ///
+    /// Tags are trimmed; adding a tag the bookmark already has is a no-op.
+    /// Fails if the URL isn't bookmarked or the tag is empty.
+    pub fn add_tag(&self, url: &str, tag: &str) -> Result<()> {
+        self.ensure_writable("add tag")?;
+        let url = normalize_url(url);
+        let tag = tag.trim();
+        if tag.is_empty() {
+            anyhow::bail!("Tag can't be empty");
+        }
+
+        let inserted = self.execute_with_retry(
+            "INSERT OR IGNORE INTO bookmark_tags (bookmark_id, tag)
+             SELECT id, ?2 FROM bookmarks WHERE url = ?1",
+            params![url, tag],
         )?;
+        if inserted == 0 && !self.is_bookmarked(&url)? {
+            anyhow::bail!("Bookmark not found: {}", url);
+        }
 
-        debug!("Set setting: {} = {}", key, value);
+        debug!("Tagged bookmark {} with '{}'", url, tag);
         Ok(())
     }
 
-    /// Remove a setting
-    pub fn remove_setting(&self, key: &str) -> Result<()> {
-        self.conn
-            .execute("DELETE FROM settings WHERE key = ?1", params![key])?;
-        debug!("Removed setting: {}", key);
+    /// Remove a tag from a bookmark (no-op if it isn't tagged)
+    ///
+    /// Retried while the database is busy.
+    pub fn remove_tag(&self, url: &str, tag: &str) -> Result<()> {
+        self.ensure_writable("remove tag")?;
+        let url = normalize_url(url);
+        self.execute_with_retry(
+            "DELETE FROM bookmark_tags
+             WHERE tag = ?2 AND bookmark_id IN (SELECT id FROM bookmarks WHERE url = ?1)",
+            params![url, tag.trim()],
+        )?;
+        debug!("Removed tag '{}' from bookmark {}", tag, url);
         Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::NamedTempFile;
+    /// Tags on a bookmark, alphabetically
+    pub fn tags_for(&self, url: &str) -> Result<Vec<String>> {
+        let url = normalize_url(url);
+        let mut stmt = self.conn.prepare(
+            "SELECT t.tag FROM bookmark_tags t
+             JOIN bookmarks b ON b.id = t.bookmark_id
+             WHERE b.url = ?1 ORDER BY t.tag",
+        )?;
 
-    #[test]
-    fn test_database_creation() {
-        let temp_file = NamedTempFile::new().unwrap();
-        let _db = Database::new(temp_file.path()).unwrap();
-    }
+        let tags = stmt.query_map(params![url], |row| row.get(0))?;
 
-    #[test]
-    fn test_tables_exist() {
-        let temp_file = NamedTempFile::new().unwrap();
-        let db = Database::new(temp_file.path()).unwrap();
+        tags.collect::<Result<Vec<_>, _>>()
+            .context("Failed to get bookmark tags")
+    }
 
-        // Verify tables were created
-        let mut stmt = db
-            .connection()
-            .prepare("SELECT name FROM sqlite_master WHERE type='table'")
-            .unwrap();
+    /// Bookmarks with a tag, newest first
+    pub fn bookmarks_with_tag(&self, tag: &str) -> Result<Vec<Bookmark>> {
+        // Semi-join on idx_bookmark_tags_tag
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM bookmarks
+             WHERE id IN (SELECT bookmark_id FROM bookmark_tags WHERE tag = ?1)
+             ORDER BY created_at DESC",
+            BOOKMARK_COLUMNS
+        ))?;
 
-        let tables: Vec<String> = stmt
-            .query_map([], |row| row.get(0))
-            .unwrap()
-            .filter_map(Result::ok)
-            .collect();
+        let bookmarks = stmt.query_map(params![tag.trim()], bookmark_from_row)?;
 
-        assert!(tables.contains(&"history".to_string()));
-        assert!(tables.contains(&"bookmarks".to_string()));
-        assert!(tables.contains(&"settings".to_string()));
-        assert!(tables.contains(&"schema_version".to_string()));
+        bookmarks
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to get bookmarks by tag")
     }
 
-    #[test]
-    fn test_schema_version() {
-        let temp_file = NamedTempFile::new().unwrap();
-        let db = Database::new(temp_file.path()).unwrap();
+    // ========== Zoom Operations ==========
 
-        let version: i32 = db
-            .connection()
-            .query_row(
-                "SELECT version FROM schema_version ORDER BY version DESC LIMIT 1",
-                [],
-                |row| row.get(0),
-            )
-            .unwrap();
+    /// Zoom factor for an origin (e.g. `https://example.com`)
+    ///
+    /// Returns `DEFAULT_ZOOM` if none is stored.
+    pub fn get_zoom(&self, origin: &str) -> Result<f64> {
+        let result = self.conn.query_row(
+            "SELECT factor FROM zoom_levels WHERE origin = ?1",
+            params![origin],
+            |row| row.get(0),
+        );
 
-        assert_eq!(version, SCHEMA_VERSION);
+        match result {
+            Ok(factor) => Ok(factor),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(DEFAULT_ZOOM),
+            Err(e) => Err(e.into()),
+        }
     }
 
-    // ========== History Tests ==========
+    /// Remember the zoom factor for an origin
+    ///
+    /// Setting `DEFAULT_ZOOM` forgets the origin. Fails for factors that
+    /// aren't positive and finite.
+    pub fn set_zoom(&self, origin: &str, factor: f64) -> Result<()> {
+        self.ensure_writable("set zoom")?;
+        if !(factor.is_finite() && factor > 0.0) {
+            anyhow::bail!("Invalid zoom factor: {}", factor);
+        }
 
-    #[test]
+        if factor == DEFAULT_ZOOM {
+            self.execute_with_retry("DELETE FROM zoom_levels WHERE origin = ?1", params![origin])?;
+        } else {
+            self.execute_with_retry(
+                "INSERT OR REPLACE INTO zoom_levels (origin, factor) VALUES (?1, ?2)",
+                params![origin, factor],
+            )?;
+        }
+
+        debug!("Set zoom for {}: {}", origin, factor);
+        Ok(())
+    }
+
+    // ========== Favicon Operations ==========
+
+    /// Cached favicon for an origin (e.g. `https://example.com`)
+    pub fn get_favicon(&self, origin: &str) -> Result<Option<Vec<u8>>> {
+        let result = self.conn.query_row(
+            "SELECT data FROM favicons WHERE origin = ?1",
+            params![origin],
+            |row| row.get(0),
+        );
+
+        match result {
+            Ok(data) => Ok(Some(data)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Cache the favicon for an origin, replacing any previous one
+    pub fn set_favicon(&self, origin: &str, data: &[u8]) -> Result<()> {
+        self.ensure_writable("set favicon")?;
+        let now = Utc::now().to_rfc3339();
+        self.execute_with_retry(
+            "INSERT OR REPLACE INTO favicons (origin, data, updated_at) VALUES (?1, ?2, ?3)",
+            params![origin, data, now],
+        )?;
+
+        debug!("Cached favicon for {} ({} bytes)", origin, data.len());
+        Ok(())
+    }
+
+    // ========== Autofill Operations ==========
+
+    /// Remember a value typed into a form field on `origin`
+    ///
+    /// Saving a value again bumps its use count, ranking it higher in
+    /// suggestions. Blank values are ignored. Fails with
+    /// `StorageError::SensitiveField` for password-like fields.
+    pub fn save_autofill(&self, origin: &str, field: &str, value: &str) -> Result<()> {
+        self.ensure_writable("save autofill")?;
+        if is_sensitive_field(field) {
+            return Err(StorageError::SensitiveField(field.to_string()).into());
+        }
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok(());
+        }
+
+        let now = Utc::now().timestamp();
+        self.execute_with_retry(
+            "INSERT INTO autofill (origin, field_name, value, last_used)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (origin, field_name, value)
+             DO UPDATE SET use_count = use_count + 1, last_used = excluded.last_used",
+            params![origin, field, value, now],
+        )?;
+
+        debug!("Saved autofill value for {} on {}", field, origin);
+        Ok(())
+    }
+
+    /// Saved values for a field on `origin` starting with `prefix`
+    ///
+    /// Matching ignores ASCII case. Most used first, then most recent, up
+    /// to `AUTOFILL_SUGGESTION_LIMIT`. Sensitive fields never have
+    /// suggestions.
+    pub fn autofill_suggestions(
+        &self,
+        origin: &str,
+        field: &str,
+        prefix: &str,
+    ) -> Result<Vec<String>> {
+        if is_sensitive_field(field) {
+            return Ok(Vec::new());
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT value FROM autofill
+             WHERE origin = ?1 AND field_name = ?2 AND value LIKE ?3 ESCAPE '\\'
+             ORDER BY use_count DESC, last_used DESC, value
+             LIMIT ?4",
+        )?;
+
+        let pattern = format!("{}%", escape_like(prefix));
+        let values = stmt.query_map(
+            params![origin, field, pattern, AUTOFILL_SUGGESTION_LIMIT],
+            |row| row.get(0),
+        )?;
+
+        values
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to get autofill suggestions")
+    }
+
+    /// Forget all saved form values
+    ///
+    /// Retried while the database is busy.
+    pub fn clear_autofill(&self) -> Result<()> {
+        self.ensure_writable("clear autofill")?;
+        self.execute_with_retry("DELETE FROM autofill", [])?;
+        info!("Cleared all autofill data");
+        Ok(())
+    }
+
+    // ========== Secret Operations ==========
+
+    /// Store a value encrypted under `passphrase`
+    ///
+    /// Each write uses a fresh salt and nonce. The key name is
+    /// authenticated too, so a ciphertext copied to another key won't
+    /// decrypt.
+    pub fn set_secret(&self, key: &str, value: &str, passphrase: &str) -> Result<()> {
+        self.ensure_writable("set secret")?;
+
+        let mut salt = [0u8; SECRET_SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let cipher = secret_cipher(passphrase, &salt)?;
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: value.as_bytes(),
+                    aad: key.as_bytes(),
+                },
+            )
+            .map_err(|_| anyhow::anyhow!("Failed to encrypt secret {}", key))?;
+
+        self.execute_with_retry(
+            "INSERT OR REPLACE INTO secrets (key, salt, nonce, ciphertext)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![key, &salt[..], &nonce[..], ciphertext],
+        )?;
+
+        debug!("Set secret: {}", key);
+        Ok(())
+    }
+
+    /// Decrypt a stored secret, None if `key` isn't set
+    ///
+    /// Fails with `StorageError::SecretAuthFailed` for a wrong passphrase
+    /// or a modified row, never returning garbage.
+    pub fn get_secret(&self, key: &str, passphrase: &str) -> Result<Option<String>> {
+        let result = self.conn.query_row(
+            "SELECT salt, nonce, ciphertext FROM secrets WHERE key = ?1",
+            params![key],
+            |row| {
+                Ok((
+                    row.get::<_, Vec<u8>>(0)?,
+                    row.get::<_, Vec<u8>>(1)?,
+                    row.get::<_, Vec<u8>>(2)?,
+                ))
+            },
+        );
+        let (salt, nonce, ciphertext) = match result {
+            Ok(row) => row,
+            Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let auth_failed = || StorageError::SecretAuthFailed(key.to_string());
+        let nonce: [u8; 24] = nonce.try_into().map_err(|_| auth_failed())?;
+        let plaintext = secret_cipher(passphrase, &salt)?
+            .decrypt(
+                &XNonce::from(nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: key.as_bytes(),
+                },
+            )
+            .map_err(|_| auth_failed())?;
+
+        let value = String::from_utf8(plaintext).map_err(|_| auth_failed())?;
+        Ok(Some(value))
+    }
+
+    /// Delete a stored secret
+    ///
+    /// Retried while the database is busy.
+    pub fn remove_secret(&self, key: &str) -> Result<()> {
+        self.ensure_writable("remove secret")?;
+        self.execute_with_retry("DELETE FROM secrets WHERE key = ?1", params![key])?;
+        debug!("Removed secret: {}", key);
+        Ok(())
+    }
+
+    // ========== Settings Operations ==========
+
+    /// Get a setting value
+    pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        let result = self.conn.query_row(
+            "SELECT value FROM settings WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        );
+
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Set a setting value
+    ///
+    /// Retried while the database is busy.
+    pub fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        self.ensure_writable("set setting")?;
+        self.execute_with_retry(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+            params![key, value],
+        )?;
+
+        debug!("Set setting: {} = {}", key, value);
+        Ok(())
+    }
+
+    /// Remove a setting
+    ///
+    /// Retried while the database is busy.
+    pub fn remove_setting(&self, key: &str) -> Result<()> {
+        self.ensure_writable("remove setting")?;
+        self.execute_with_retry("DELETE FROM settings WHERE key = ?1", params![key])?;
+        debug!("Removed setting: {}", key);
+        Ok(())
+    }
+
+    /// Page the home action opens
+    ///
+    /// Falls back to `DEFAULT_HOMEPAGE` if unset, empty or not a valid URL.
+    pub fn homepage(&self) -> Result<String> {
+        self.url_setting(HOMEPAGE_SETTING, DEFAULT_HOMEPAGE)
+    }
+
+    /// Set the page the home action opens
+    ///
+    /// Fails for values that aren't an absolute http(s) or `about:` URL.
+    pub fn set_homepage(&self, url: &str) -> Result<()> {
+        self.set_url_setting(HOMEPAGE_SETTING, url)
+    }
+
+    /// Page new tabs open
+    ///
+    /// Falls back to `DEFAULT_NEW_TAB_URL` if unset, empty or not a valid URL.
+    pub fn new_tab_url(&self) -> Result<String> {
+        self.url_setting(NEW_TAB_URL_SETTING, DEFAULT_NEW_TAB_URL)
+    }
+
+    /// Set the page new tabs open
+    ///
+    /// Fails for values that aren't an absolute http(s) or `about:` URL.
+    pub fn set_new_tab_url(&self, url: &str) -> Result<()> {
+        self.set_url_setting(NEW_TAB_URL_SETTING, url)
+    }
+
+    fn url_setting(&self, key: &str, default: &str) -> Result<String> {
+        Ok(self
+            .get_setting(key)?
+            .filter(|value| is_valid_page_url(value))
+            .unwrap_or_else(|| default.to_string()))
+    }
+
+    fn set_url_setting(&self, key: &str, url: &str) -> Result<()> {
+        let url = url.trim();
+        if !is_valid_page_url(url) {
+            anyhow::bail!("Invalid URL for {}: {:?}", key, url);
+        }
+        self.set_setting(key, url)
+    }
+}
+
+/// Cipher keyed by Argon2id over the passphrase and salt
+fn secret_cipher(passphrase: &str, salt: &[u8]) -> Result<XChaCha20Poly1305> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("Failed to derive secret key: {}", e))?;
+    Ok(XChaCha20Poly1305::new(&key.into()))
+}
+
+/// Whether a form field's values must never be stored
+///
+/// Matches field names and `autocomplete` tokens for passwords, one-time
+/// codes, card numbers and security codes, e.g. `password`,
+/// `new-password`, `user_pwd`, `cc-number`, `cvc`.
+pub fn is_sensitive_field(field: &str) -> bool {
+    const SENSITIVE_WORDS: &[&str] = &["pass", "pwd", "pin", "otp", "cvv", "cvc", "csc", "ssn"];
+    const SENSITIVE_FRAGMENTS: &[&str] = &[
+        "password",
+        "passwd",
+        "passcode",
+        "secret",
+        "ccnumber",
+        "cardnumber",
+        "ccnum",
+        "securitycode",
+        "onetimecode",
+    ];
+
+    let field = field.to_ascii_lowercase();
+    let words: Vec<&str> = field
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let joined = words.concat();
+
+    words.iter().any(|word| SENSITIVE_WORDS.contains(word))
+        || SENSITIVE_FRAGMENTS
+            .iter()
+            .any(|fragment| joined.contains(fragment))
+}
+
+/// Escape `%`, `_` and `\` for a `LIKE ... ESCAPE '\'` pattern
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Whether a URL can be used as a homepage or new-tab page
+fn is_valid_page_url(value: &str) -> bool {
+    url::Url::parse(value)
+        .map(|url| matches!(url.scheme(), "http" | "https" | "about"))
+        .unwrap_or(false)
+}
+
+/// Copy `src` to `dest` via a temporary file renamed into place
+fn copy_database(src: &Connection, dest: &Path, policy: &RetryPolicy) -> Result<()> {
+    let mut partial = dest.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+
+    let result = retry::with_retry(policy, || {
+        // A failed attempt may leave a half-written file behind
+        let _ = std::fs::remove_file(&partial);
+        src.backup(MAIN_DB, &partial, None)
+    })
+    .context("Database backup failed")
+    .and_then(|()| {
+        std::fs::rename(&partial, dest)
+            .with_context(|| format!("Failed to move backup into place at {:?}", dest))
+    });
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&partial);
+    }
+    result
+}
+
+/// Map an `id, url, title, visit_time` history row to a `HistoryEntry`
+fn history_entry_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<HistoryEntry> {
+    Ok(HistoryEntry {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        title: row.get(2)?,
+        visit_time: row
+            .get::<_, String>(3)?
+            .parse::<DateTime<Utc>>()
+            .unwrap_or_else(|_| Utc::now()),
+    })
+}
+
+/// Map a row selected with `BOOKMARK_COLUMNS` to a `Bookmark`
+fn bookmark_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Bookmark> {
+    Ok(Bookmark {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        title: row.get(2)?,
+        folder: row.get(3)?,
+        created_at: row
+            .get::<_, String>(4)?
+            .parse::<DateTime<Utc>>()
+            .unwrap_or_else(|_| Utc::now()),
+        position: row.get(5)?,
+    })
+}
+
+/// Normalize a URL for storage, keeping unparsable input as-is
+fn normalize_url(url: &str) -> String {
+    shared::url::normalize(url).unwrap_or_else(|_| url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_database_creation() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let _db = Database::new(temp_file.path()).unwrap();
+    }
+
+    #[test]
+    fn test_tables_exist() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+
+        // Verify tables were created
+        let mut stmt = db
+            .connection()
+            .prepare("SELECT name FROM sqlite_master WHERE type='table'")
+            .unwrap();
+
+        let tables: Vec<String> = stmt
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .filter_map(Result::ok)
+            .collect();
+
+        assert!(tables.contains(&"history".to_string()));
+        assert!(tables.contains(&"bookmarks".to_string()));
+        assert!(tables.contains(&"settings".to_string()));
+        assert!(tables.contains(&"schema_version".to_string()));
+    }
+
+    #[test]
+    fn test_schema_version() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+
+        let version: i32 = db
+            .connection()
+            .query_row(
+                "SELECT version FROM schema_version ORDER BY version DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+
+        assert_eq!(version, SCHEMA_VERSION);
+    }
+
+    // ========== History Tests ==========
+
+    #[test]
+    fn test_in_memory_database() {
+        let db = Database::new_in_memory().unwrap();
+
+        for table in [
+            "history",
+            "bookmarks",
+            "settings",
+            "bookmark_tags",
+            "zoom_levels",
+            "autofill",
+            "secrets",
+        ] {
+            let exists: bool = db
+                .connection()
+                .query_row(
+                    "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+                    params![table],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            assert!(exists, "missing table {}", table);
+        }
+
+        db.add_history("https://example.com", Some("Example"))
+            .unwrap();
+        let history = db.get_recent_history(10).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].url, "https://example.com/");
+        assert_eq!(history[0].title.as_deref(), Some("Example"));
+
+        // Every in-memory database starts empty
+        let other = Database::new_in_memory().unwrap();
+        assert!(other.get_recent_history(10).unwrap().is_empty());
+    }
+
+    /// Add a history entry visited `minutes` after 10:00
+    fn add_visit_at(db: &Database, url: &str, minutes: i64) {
+        let visit_time = "2024-05-01T10:00:00Z".parse::<DateTime<Utc>>().unwrap()
+            + chrono::Duration::minutes(minutes);
+        db.connection()
+            .execute(
+                "INSERT INTO history (url, title, visit_time) VALUES (?1, NULL, ?2)",
+                params![url, visit_time.to_rfc3339()],
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_history_sessions() {
+        let mut db = Database::new_in_memory().unwrap();
+        for (url, minutes) in [
+            ("https://a.com/", 0),
+            ("https://b.com/", 10),
+            ("https://c.com/", 20),
+            // 70 minutes idle
+            ("https://d.com/", 90),
+            ("https://e.com/", 100),
+        ] {
+            add_visit_at(&db, url, minutes);
+        }
+
+        let sessions = db.get_history_sessions(10).unwrap();
+        assert_eq!(sessions.len(), 2);
+        let urls = |session: &HistorySession| {
+            session
+                .entries
+                .iter()
+                .map(|entry| entry.url.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(urls(&sessions[0]), vec!["https://e.com/", "https://d.com/"]);
+        assert_eq!(
+            urls(&sessions[1]),
+            vec!["https://c.com/", "https://b.com/", "https://a.com/"]
+        );
+        assert_eq!(sessions[0].start, sessions[0].entries[1].visit_time);
+        assert_eq!(sessions[0].end, sessions[0].entries[0].visit_time);
+        assert_eq!((sessions[1].end - sessions[1].start).num_minutes(), 20);
+
+        assert_eq!(db.get_history_sessions(1).unwrap().len(), 1);
+        assert!(db.get_history_sessions(0).unwrap().is_empty());
+
+        // A shorter gap splits every visit into its own session
+        db.set_session_gap(Duration::from_secs(5 * 60));
+        assert_eq!(db.get_history_sessions(10).unwrap().len(), 5);
+        db.set_session_gap(Duration::from_secs(2 * 60 * 60));
+        assert_eq!(db.get_history_sessions(10).unwrap().len(), 1);
+    }
+
+    #[test]
     fn test_add_history() {
         let temp_file = NamedTempFile::new().unwrap();
         let db = Database::new(temp_file.path()).unwrap();
@@ -400,6 +1515,33 @@ mod tests {
         assert!(id > 0);
     }
 
+    #[test]
+    fn test_add_history_collapsing() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+        let window = std::time::Duration::from_secs(60);
+
+        let first = db
+            .add_history_collapsing("https://example.com", None, window)
+            .unwrap();
+        assert_eq!(
+            db.add_history_collapsing("https://EXAMPLE.com/", None, window)
+                .unwrap(),
+            None
+        );
+        let second = db
+            .add_history_collapsing("https://example.com", None, std::time::Duration::ZERO)
+            .unwrap();
+
+        // The ids are the inserted entries'
+        let history = db.get_recent_history(10).unwrap();
+        assert_eq!(history.len(), 2);
+        let mut ids: Vec<i64> = history.iter().map(|entry| entry.id).collect();
+        ids.sort_unstable();
+        assert_eq!(vec![first.unwrap(), second.unwrap()], ids);
+        assert_ne!(first, second);
+    }
+
     #[test]
     fn test_get_recent_history() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -418,9 +1560,9 @@ mod tests {
         assert_eq!(history.len(), 2);
 
         // Most recent first (Wikipedia)
-        assert_eq!(history[0].url, "https://wikipedia.org");
+        assert_eq!(history[0].url, "https://wikipedia.org/");
         assert_eq!(history[0].title, Some("Wikipedia".to_string()));
-        assert_eq!(history[1].url, "https://github.com");
+        assert_eq!(history[1].url, "https://github.com/");
     }
 
     #[test]
@@ -438,12 +1580,12 @@ mod tests {
         // Search by URL
         let results = db.search_history("github", 10).unwrap();
         assert_eq!(results.len(), 1);
-        assert_eq!(results[0].url, "https://github.com");
+        assert_eq!(results[0].url, "https://github.com/");
 
         // Search by title
         let results = db.search_history("Example", 10).unwrap();
         assert_eq!(results.len(), 1);
-        assert_eq!(results[0].url, "https://example.com");
+        assert_eq!(results[0].url, "https://example.com/");
 
         // Search that matches multiple
         let results = db.search_history("https", 10).unwrap();
@@ -512,6 +1654,43 @@ mod tests {
         assert_eq!(bookmarks.len(), 2);
     }
 
+    #[test]
+    fn test_get_bookmarks_with_favicons() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+
+        db.add_bookmark("https://example.com/docs/page", Some("Example"), None)
+            .unwrap();
+        db.add_bookmark("https://github.com", Some("GitHub"), None)
+            .unwrap();
+        db.set_favicon("https://example.com", b"\x89PNG icon")
+            .unwrap();
+
+        let bookmarks = db.get_bookmarks_with_favicons().unwrap();
+        assert_eq!(bookmarks.len(), 2);
+        for (bookmark, favicon) in &bookmarks {
+            if bookmark.url.starts_with("https://example.com/") {
+                assert_eq!(favicon.as_deref(), Some(&b"\x89PNG icon"[..]));
+            } else {
+                assert_eq!(favicon, &None);
+            }
+        }
+
+        // Same order as get_bookmarks
+        let plain = db.get_bookmarks().unwrap();
+        let joined: Vec<_> = bookmarks
+            .into_iter()
+            .map(|(bookmark, _)| bookmark)
+            .collect();
+        assert_eq!(joined, plain);
+
+        assert_eq!(
+            db.get_favicon("https://example.com").unwrap().as_deref(),
+            Some(&b"\x89PNG icon"[..])
+        );
+        assert_eq!(db.get_favicon("https://github.com").unwrap(), None);
+    }
+
     #[test]
     fn test_get_bookmarks_by_folder() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -533,7 +1712,7 @@ mod tests {
 
         let work_bookmarks = db.get_bookmarks_by_folder("Work").unwrap();
         assert_eq!(work_bookmarks.len(), 1);
-        assert_eq!(work_bookmarks[0].url, "https://example.com");
+        assert_eq!(work_bookmarks[0].url, "https://example.com/");
     }
 
     #[test]
@@ -556,7 +1735,47 @@ mod tests {
         // Verify 1 bookmark remains
         let bookmarks = db.get_bookmarks().unwrap();
         assert_eq!(bookmarks.len(), 1);
-        assert_eq!(bookmarks[0].url, "https://github.com");
+        assert_eq!(bookmarks[0].url, "https://github.com/");
+    }
+
+    #[test]
+    fn test_is_bookmarked_matches_variants() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+
+        db.add_bookmark("https://Example.com", Some("Example"), None)
+            .unwrap();
+
+        assert!(db.is_bookmarked("https://example.com/").unwrap());
+        assert!(db.is_bookmarked("https://example.com:443#top").unwrap());
+        assert!(!db.is_bookmarked("https://example.com/other").unwrap());
+    }
+
+    #[test]
+    fn test_history_urls_normalized() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+
+        db.add_history("https://example.com", None).unwrap();
+        db.add_history("https://EXAMPLE.com/", None).unwrap();
+
+        let history = db.get_recent_history(10).unwrap();
+        assert!(history.iter().all(|e| e.url == "https://example.com/"));
+    }
+
+    #[test]
+    fn test_clear_bookmarks() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+
+        db.add_bookmark("https://example.com", Some("Example"), None)
+            .unwrap();
+        db.add_bookmark("https://github.com", Some("GitHub"), Some("Dev"))
+            .unwrap();
+        assert_eq!(db.get_bookmarks().unwrap().len(), 2);
+
+        db.clear_bookmarks().unwrap();
+        assert!(db.get_bookmarks().unwrap().is_empty());
     }
 
     #[test]
@@ -676,4 +1895,608 @@ mod tests {
             handle.join().unwrap();
         }
     }
+
+    fn folder_urls(db: &Database, folder: &str) -> Vec<String> {
+        db.get_bookmarks_by_folder(folder)
+            .unwrap()
+            .into_iter()
+            .map(|b| b.url)
+            .collect()
+    }
+
+    #[test]
+    fn test_bookmarks_append_to_folder() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+
+        db.add_bookmark("https://a.com", None, Some("Dev")).unwrap();
+        db.add_bookmark("https://x.com", None, Some("Work"))
+            .unwrap();
+        db.add_bookmark("https://b.com", None, Some("Dev")).unwrap();
+
+        let dev = db.get_bookmarks_by_folder("Dev").unwrap();
+        assert_eq!(dev[0].position, 0);
+        assert_eq!(dev[1].position, 1);
+        assert_eq!(db.get_bookmarks_by_folder("Work").unwrap()[0].position, 0);
+    }
+
+    #[test]
+    fn test_reorder_bookmark() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+
+        db.add_bookmark("https://a.com", None, Some("Dev")).unwrap();
+        db.add_bookmark("https://b.com", None, Some("Dev")).unwrap();
+        db.add_bookmark("https://c.com", None, Some("Dev")).unwrap();
+        db.add_bookmark("https://other.com", None, Some("Work"))
+            .unwrap();
+
+        // Last to first
+        db.reorder_bookmark("https://c.com", 0).unwrap();
+        assert_eq!(
+            folder_urls(&db, "Dev"),
+            ["https://c.com/", "https://a.com/", "https://b.com/"]
+        );
+
+        // First to last, with a position past the end
+        db.reorder_bookmark("https://c.com", 10).unwrap();
+        assert_eq!(
+            folder_urls(&db, "Dev"),
+            ["https://a.com/", "https://b.com/", "https://c.com/"]
+        );
+
+        // Into the middle, renumbering the folder
+        db.reorder_bookmark("https://a.com", 1).unwrap();
+        let dev = db.get_bookmarks_by_folder("Dev").unwrap();
+        let order: Vec<_> = dev.iter().map(|b| (b.url.as_str(), b.position)).collect();
+        assert_eq!(
+            order,
+            [
+                ("https://b.com/", 0),
+                ("https://a.com/", 1),
+                ("https://c.com/", 2)
+            ]
+        );
+
+        // Other folders are untouched
+        assert_eq!(folder_urls(&db, "Work"), ["https://other.com/"]);
+        assert!(db.reorder_bookmark("https://missing.com", 0).is_err());
+    }
+
+    #[test]
+    fn test_search_bookmarks() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+
+        db.add_bookmark("https://docs.rs", Some("Rust Docs"), Some("Dev"))
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        db.add_bookmark("https://github.com", Some("GitHub"), Some("Dev"))
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        db.add_bookmark(
+            "https://example.com",
+            Some("Quarterly report"),
+            Some("Work"),
+        )
+        .unwrap();
+
+        // By title (case-insensitive, like history search)
+        let results = db.search_bookmarks("rust", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://docs.rs/");
+
+        // By folder name, newest first
+        let results = db.search_bookmarks("Dev", 10).unwrap();
+        let urls: Vec<_> = results.iter().map(|b| b.url.as_str()).collect();
+        assert_eq!(urls, ["https://github.com/", "https://docs.rs/"]);
+
+        // By URL
+        let results = db.search_bookmarks("example.com", 10).unwrap();
+        assert_eq!(results[0].title.as_deref(), Some("Quarterly report"));
+
+        assert_eq!(db.search_bookmarks("Dev", 1).unwrap().len(), 1);
+        assert!(db.search_bookmarks("nothing", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_bookmark_tags() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+
+        db.add_bookmark("https://docs.rs", Some("Docs"), Some("Dev"))
+            .unwrap();
+        db.add_bookmark("https://example.com", None, Some("Work"))
+            .unwrap();
+
+        db.add_tag("https://docs.rs", "rust").unwrap();
+        db.add_tag("https://docs.rs", " reference ").unwrap();
+        db.add_tag("https://docs.rs", "rust").unwrap(); // duplicate ignored
+        db.add_tag("https://example.com", "reference").unwrap();
+
+        assert_eq!(
+            db.tags_for("https://docs.rs").unwrap(),
+            ["reference", "rust"]
+        );
+
+        let rust = db.bookmarks_with_tag("rust").unwrap();
+        assert_eq!(rust.len(), 1);
+        assert_eq!(rust[0].url, "https://docs.rs/");
+        assert_eq!(rust[0].folder, "Dev");
+        assert_eq!(db.bookmarks_with_tag("reference").unwrap().len(), 2);
+
+        db.remove_tag("https://docs.rs", "rust").unwrap();
+        assert_eq!(db.tags_for("https://docs.rs").unwrap(), ["reference"]);
+        assert!(db.bookmarks_with_tag("rust").unwrap().is_empty());
+        assert_eq!(db.bookmarks_with_tag("reference").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_tag_errors_and_cleanup() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+
+        assert!(db.add_tag("https://missing.com", "rust").is_err());
+
+        db.add_bookmark("https://docs.rs", None, None).unwrap();
+        assert!(db.add_tag("https://docs.rs", "  ").is_err());
+        db.add_tag("https://docs.rs", "rust").unwrap();
+
+        // Removing the bookmark drops its tags, so re-adding starts clean
+        db.remove_bookmark("https://docs.rs").unwrap();
+        assert!(db.bookmarks_with_tag("rust").unwrap().is_empty());
+        db.add_bookmark("https://docs.rs", None, None).unwrap();
+        assert!(db.tags_for("https://docs.rs").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_migrate_v1_bookmarks() {
+        let temp_file = NamedTempFile::new().unwrap();
+        {
+            // A database as created by schema version 1
+            let conn = Connection::open(temp_file.path()).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE schema_version (version INTEGER PRIMARY KEY);
+                 INSERT INTO schema_version (version) VALUES (1);
+                 CREATE TABLE bookmarks (
+                     id INTEGER PRIMARY KEY AUTOINCREMENT,
+                     url TEXT NOT NULL UNIQUE,
+                     title TEXT,
+                     folder TEXT DEFAULT 'Unsorted',
+                     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+                 );
+                 INSERT INTO bookmarks (url, folder, created_at) VALUES
+                     ('https://new.com/', 'Dev', '2024-02-01T00:00:00+00:00'),
+                     ('https://old.com/', 'Dev', '2024-01-01T00:00:00+00:00'),
+                     ('https://work.com/', 'Work', '2024-01-15T00:00:00+00:00');",
+            )
+            .unwrap();
+        }
+
+        let db = Database::new(temp_file.path()).unwrap();
+        assert_eq!(
+            folder_urls(&db, "Dev"),
+            ["https://old.com/", "https://new.com/"]
+        );
+        assert_eq!(db.get_bookmarks_by_folder("Work").unwrap()[0].position, 0);
+
+        // New bookmarks go after the migrated ones
+        db.add_bookmark("https://newest.com", None, Some("Dev"))
+            .unwrap();
+        assert_eq!(db.get_bookmarks_by_folder("Dev").unwrap()[2].position, 2);
+    }
+
+    #[test]
+    fn test_zoom_round_trip() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+
+        db.set_zoom("https://example.com", 1.5).unwrap();
+        db.set_zoom("https://docs.rs", 0.8).unwrap();
+        assert_eq!(db.get_zoom("https://example.com").unwrap(), 1.5);
+        assert_eq!(db.get_zoom("https://docs.rs").unwrap(), 0.8);
+
+        db.set_zoom("https://example.com", 2.0).unwrap();
+        assert_eq!(db.get_zoom("https://example.com").unwrap(), 2.0);
+
+        // Back to default forgets the origin
+        db.set_zoom("https://docs.rs", DEFAULT_ZOOM).unwrap();
+        let stored: i64 = db
+            .connection()
+            .query_row("SELECT COUNT(*) FROM zoom_levels", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(stored, 1);
+    }
+
+    #[test]
+    fn test_zoom_defaults_and_validation() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+
+        assert_eq!(
+            db.get_zoom("https://unknown.example").unwrap(),
+            DEFAULT_ZOOM
+        );
+        assert!(db.set_zoom("https://example.com", 0.0).is_err());
+        assert!(db.set_zoom("https://example.com", f64::NAN).is_err());
+        assert_eq!(db.get_zoom("https://example.com").unwrap(), DEFAULT_ZOOM);
+    }
+
+    #[test]
+    fn test_homepage_and_new_tab_url_round_trip() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+
+        db.set_homepage("https://start.example.com/").unwrap();
+        db.set_new_tab_url(" about:blank ").unwrap();
+
+        assert_eq!(db.homepage().unwrap(), "https://start.example.com/");
+        assert_eq!(db.new_tab_url().unwrap(), "about:blank");
+        assert_eq!(
+            db.get_setting(HOMEPAGE_SETTING).unwrap(),
+            Some("https://start.example.com/".to_string())
+        );
+    }
+
+    #[test]
+    fn test_homepage_and_new_tab_url_fallback() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+
+        // Unset
+        assert_eq!(db.homepage().unwrap(), DEFAULT_HOMEPAGE);
+        assert_eq!(db.new_tab_url().unwrap(), DEFAULT_NEW_TAB_URL);
+
+        // Invalid values are rejected by the typed setters...
+        assert!(db.set_homepage("").is_err());
+        assert!(db.set_homepage("not a url").is_err());
+        assert!(db.set_new_tab_url("javascript:alert(1)").is_err());
+        assert_eq!(db.get_setting(HOMEPAGE_SETTING).unwrap(), None);
+
+        // ...and ignored if written some other way
+        db.set_setting(HOMEPAGE_SETTING, "").unwrap();
+        db.set_setting(NEW_TAB_URL_SETTING, "file:///etc/passwd")
+            .unwrap();
+        assert_eq!(db.homepage().unwrap(), DEFAULT_HOMEPAGE);
+        assert_eq!(db.new_tab_url().unwrap(), DEFAULT_NEW_TAB_URL);
+    }
+
+    #[test]
+    fn test_autofill_suggestions() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+        let origin = "https://shop.example.com";
+
+        db.save_autofill(origin, "email", "alice@example.com")
+            .unwrap();
+        db.save_autofill(origin, "email", "alex@example.com")
+            .unwrap();
+        db.save_autofill(origin, "email", "bob@example.com")
+            .unwrap();
+        // Used twice, so ranked first
+        db.save_autofill(origin, "email", "alex@example.com")
+            .unwrap();
+        db.save_autofill(origin, "city", "Alexandria").unwrap();
+        db.save_autofill("https://other.example", "email", "al@other.example")
+            .unwrap();
+
+        assert_eq!(
+            db.autofill_suggestions(origin, "email", "AL").unwrap(),
+            vec!["alex@example.com", "alice@example.com"]
+        );
+        assert_eq!(
+            db.autofill_suggestions(origin, "email", "").unwrap().len(),
+            3
+        );
+        assert!(db
+            .autofill_suggestions(origin, "email", "carol")
+            .unwrap()
+            .is_empty());
+
+        // LIKE wildcards in the prefix are literal
+        assert!(db
+            .autofill_suggestions(origin, "email", "%")
+            .unwrap()
+            .is_empty());
+
+        db.clear_autofill().unwrap();
+        assert!(db
+            .autofill_suggestions(origin, "email", "")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_autofill_rejects_sensitive_fields() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+        let origin = "https://bank.example.com";
+
+        for field in [
+            "password",
+            "new-password",
+            "user_pwd",
+            "cc-number",
+            "cvc",
+            "PIN",
+        ] {
+            let err = db.save_autofill(origin, field, "hunter2").unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<StorageError>(),
+                Some(StorageError::SensitiveField(_))
+            ));
+            assert!(db
+                .autofill_suggestions(origin, field, "")
+                .unwrap()
+                .is_empty());
+        }
+
+        for field in ["email", "username", "shipping-address", "compass_heading"] {
+            assert!(!is_sensitive_field(field), "{field} flagged as sensitive");
+        }
+    }
+
+    #[test]
+    fn test_secret_round_trip() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+
+        assert_eq!(db.get_secret("sync_token", "hunter2").unwrap(), None);
+
+        db.set_secret("sync_token", "tok_123", "hunter2").unwrap();
+        assert_eq!(
+            db.get_secret("sync_token", "hunter2").unwrap().as_deref(),
+            Some("tok_123")
+        );
+
+        // Not stored in plaintext
+        let ciphertext: Vec<u8> = db
+            .connection()
+            .query_row(
+                "SELECT ciphertext FROM secrets WHERE key = 'sync_token'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(!ciphertext.windows(7).any(|window| window == b"tok_123"));
+
+        db.remove_secret("sync_token").unwrap();
+        assert_eq!(db.get_secret("sync_token", "hunter2").unwrap(), None);
+    }
+
+    #[test]
+    fn test_secret_wrong_passphrase() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).unwrap();
+        db.set_secret("sync_token", "tok_123", "hunter2").unwrap();
+
+        let err = db.get_secret("sync_token", "hunter3").unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<StorageError>(),
+            Some(StorageError::SecretAuthFailed(_))
+        ));
+
+        // Ciphertext moved to another key fails authentication too
+        db.connection()
+            .execute(
+                "INSERT INTO secrets (key, salt, nonce, ciphertext)
+                 SELECT 'other', salt, nonce, ciphertext FROM secrets WHERE key = 'sync_token'",
+                [],
+            )
+            .unwrap();
+        assert!(db.get_secret("other", "hunter2").is_err());
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("browser.db");
+        let backup_path = dir.path().join("backup.db");
+
+        let db = Database::new(&db_path).unwrap();
+        db.add_history("https://example.com", Some("Example"))
+            .unwrap();
+        db.add_bookmark("https://docs.rs", Some("Docs"), Some("Dev"))
+            .unwrap();
+        db.add_tag("https://docs.rs", "rust").unwrap();
+        db.set_setting("theme", "dark").unwrap();
+
+        // Backup while the source connection is still open
+        db.backup_to(&backup_path).unwrap();
+        assert!(!dir.path().join("backup.db.partial").exists());
+
+        let backup = Database::new(&backup_path).unwrap();
+        let history = backup.get_recent_history(10).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].title.as_deref(), Some("Example"));
+        assert_eq!(backup.tags_for("https://docs.rs").unwrap(), ["rust"]);
+        assert_eq!(
+            backup.get_setting("theme").unwrap(),
+            Some("dark".to_string())
+        );
+        drop(backup);
+
+        // Later changes are undone by restoring
+        db.clear_history().unwrap();
+        drop(db);
+        Database::restore_from(&backup_path, &db_path).unwrap();
+
+        let restored = Database::new(&db_path).unwrap();
+        assert_eq!(restored.get_recent_history(10).unwrap().len(), 1);
+        assert!(restored.is_bookmarked("https://docs.rs").unwrap());
+    }
+
+    #[test]
+    fn test_restore_rejects_non_browser_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("browser.db");
+        let other_path = dir.path().join("other.db");
+
+        Database::new(&db_path)
+            .unwrap()
+            .add_history("https://example.com", None)
+            .unwrap();
+        Connection::open(&other_path)
+            .unwrap()
+            .execute_batch("CREATE TABLE notes (body TEXT);")
+            .unwrap();
+
+        assert!(Database::restore_from(&other_path, &db_path).is_err());
+        assert!(Database::restore_from(&dir.path().join("missing.db"), &db_path).is_err());
+
+        // The original is untouched
+        let db = Database::new(&db_path).unwrap();
+        assert_eq!(db.get_recent_history(10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_open_readonly_reads() {
+        let temp_file = NamedTempFile::new().unwrap();
+        {
+            let db = Database::new(temp_file.path()).unwrap();
+            db.add_history("https://example.com", Some("Example"))
+                .unwrap();
+            db.add_bookmark("https://example.com", None, None).unwrap();
+            db.set_setting("theme", "dark").unwrap();
+        }
+
+        let db = Database::open_readonly(temp_file.path()).unwrap();
+        assert!(db.is_read_only());
+        assert_eq!(db.get_recent_history(10).unwrap().len(), 1);
+        assert_eq!(db.search_history("example", 10).unwrap().len(), 1);
+        assert!(db.is_bookmarked("https://example.com").unwrap());
+        assert_eq!(db.get_bookmarks().unwrap().len(), 1);
+        assert_eq!(db.get_setting("theme").unwrap(), Some("dark".to_string()));
+    }
+
+    #[test]
+    fn test_open_readonly_rejects_writes() {
+        let temp_file = NamedTempFile::new().unwrap();
+        Database::new(temp_file.path()).unwrap();
+        let db = Database::open_readonly(temp_file.path()).unwrap();
+
+        let error = db.add_history("https://example.com", None).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<StorageError>(),
+            Some(&StorageError::ReadOnly("add history"))
+        );
+        assert_eq!(
+            error.to_string(),
+            "Database is read-only: can't add history"
+        );
+
+        assert!(db.set_setting("theme", "dark").is_err());
+        assert!(db.add_bookmark("https://example.com", None, None).is_err());
+        assert!(db.clear_history().is_err());
+
+        // SQLite enforces it too, for raw connection access
+        assert!(db.connection().execute("DELETE FROM history", []).is_err());
+    }
+
+    #[test]
+    fn test_open_readonly_missing_file() {
+        assert!(Database::open_readonly(Path::new("/nonexistent/dir/browser.db")).is_err());
+    }
+
+    #[test]
+    fn test_write_retries_while_database_busy() {
+        use std::sync::mpsc;
+        use std::thread;
+        use std::time::Duration;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path().to_path_buf();
+        let mut db = Database::new(&path).unwrap();
+
+        // Fail fast on a lock so only our retry waits for the writer
+        db.connection().busy_timeout(Duration::ZERO).unwrap();
+        db.set_retry_policy(RetryPolicy {
+            max_attempts: 20,
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(50),
+        });
+
+        // Another connection holds the write lock for a while
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let writer = thread::spawn(move || {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch("BEGIN IMMEDIATE").unwrap();
+            locked_tx.send(()).unwrap();
+            thread::sleep(Duration::from_millis(100));
+            conn.execute_batch("COMMIT").unwrap();
+        });
+        locked_rx.recv().unwrap();
+
+        // Without retries the write fails immediately
+        let error = db
+            .connection()
+            .execute("INSERT INTO settings (key, value) VALUES ('k', 'v')", [])
+            .unwrap_err();
+        assert!(retry::is_transient(&error));
+
+        db.add_history("https://example.com", Some("Example"))
+            .unwrap();
+        writer.join().unwrap();
+
+        assert_eq!(db.get_recent_history(10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_deletes_and_reorder_retry_while_database_busy() {
+        use std::sync::mpsc;
+        use std::thread;
+        use std::time::Duration;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path().to_path_buf();
+        let mut db = Database::new(&path).unwrap();
+        db.add_bookmark("https://a.com", None, None).unwrap();
+        db.add_bookmark("https://b.com", None, None).unwrap();
+        db.add_bookmark("https://c.com", None, None).unwrap();
+
+        db.connection().busy_timeout(Duration::ZERO).unwrap();
+        db.set_retry_policy(RetryPolicy {
+            max_attempts: 20,
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(50),
+        });
+
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let writer = thread::spawn(move || {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch("BEGIN IMMEDIATE").unwrap();
+            locked_tx.send(()).unwrap();
+            thread::sleep(Duration::from_millis(100));
+            conn.execute_batch("COMMIT").unwrap();
+        });
+        locked_rx.recv().unwrap();
+
+        db.reorder_bookmark("https://c.com", 0).unwrap();
+        db.remove_bookmark("https://a.com").unwrap();
+        writer.join().unwrap();
+
+        assert_eq!(
+            folder_urls(&db, "Unsorted"),
+            vec!["https://c.com/", "https://b.com/"]
+        );
+    }
+
+    #[test]
+    fn test_constraint_violation_not_retried() {
+        use std::time::{Duration, Instant};
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut db = Database::new(temp_file.path()).unwrap();
+        db.set_retry_policy(RetryPolicy {
+            max_attempts: 5,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(1),
+        });
+
+        db.add_bookmark("https://example.com", None, None).unwrap();
+
+        let start = Instant::now();
+        assert!(db.add_bookmark("https://example.com", None, None).is_err());
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
 }
@@ -0,0 +1,132 @@
+//! Retry for transient SQLite errors
+//!
+//! Another connection holding a write lock makes statements fail with
+//! `SQLITE_BUSY` or `SQLITE_LOCKED`. Those are retried with bounded
+//! exponential backoff; any other error is returned immediately.
+
+use rusqlite::ErrorCode;
+use std::thread;
+use std::time::Duration;
+use tracing::debug;
+
+/// How often and how long to retry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_delay: Duration,
+    /// Upper bound for the doubling delay
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(200),
+        }
+    }
+}
+
+/// Whether `error` is a busy/locked database that may succeed on retry
+pub fn is_transient(error: &rusqlite::Error) -> bool {
+    matches!(
+        error.sqlite_error_code(),
+        Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
+
+/// Run `operation`, retrying transient errors according to `policy`
+///
+/// # Errors
+/// Returns the first non-transient error, or the last transient one once
+/// `policy.max_attempts` is reached
+pub fn with_retry<T>(
+    policy: &RetryPolicy,
+    mut operation: impl FnMut() -> rusqlite::Result<T>,
+) -> rusqlite::Result<T> {
+    let mut delay = policy.initial_delay;
+    let mut attempt = 1;
+
+    loop {
+        match operation() {
+            Err(e) if is_transient(&e) && attempt < policy.max_attempts => {
+                debug!(
+                    "Database busy (attempt {}/{}), retrying in {:?}: {}",
+                    attempt, policy.max_attempts, delay, e
+                );
+                thread::sleep(delay);
+                delay = (delay * 2).min(policy.max_delay);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::ffi;
+
+    fn sqlite_error(code: i32) -> rusqlite::Error {
+        rusqlite::Error::SqliteFailure(ffi::Error::new(code), None)
+    }
+
+    fn fast_policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(2),
+        }
+    }
+
+    #[test]
+    fn test_is_transient() {
+        assert!(is_transient(&sqlite_error(ffi::SQLITE_BUSY)));
+        assert!(is_transient(&sqlite_error(ffi::SQLITE_LOCKED)));
+        assert!(!is_transient(&sqlite_error(ffi::SQLITE_CONSTRAINT)));
+        assert!(!is_transient(&rusqlite::Error::QueryReturnedNoRows));
+    }
+
+    #[test]
+    fn test_retries_until_success() {
+        let mut calls = 0;
+        let result = with_retry(&fast_policy(5), || {
+            calls += 1;
+            if calls < 3 {
+                Err(sqlite_error(ffi::SQLITE_BUSY))
+            } else {
+                Ok(calls)
+            }
+        });
+
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[test]
+    fn test_gives_up_after_max_attempts() {
+        let mut calls = 0;
+        let result: rusqlite::Result<()> = with_retry(&fast_policy(3), || {
+            calls += 1;
+            Err(sqlite_error(ffi::SQLITE_LOCKED))
+        });
+
+        assert!(is_transient(&result.unwrap_err()));
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn test_non_transient_error_not_retried() {
+        let mut calls = 0;
+        let result: rusqlite::Result<()> = with_retry(&fast_policy(5), || {
+            calls += 1;
+            Err(sqlite_error(ffi::SQLITE_CONSTRAINT))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}